@@ -0,0 +1,494 @@
+use meowscript::ast::*;
+use meowscript::token::{Span, Token, TokenKind};
+use meowscript::value::GLOBAL_BUILTINS;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Constant,
+    Function,
+    Parameter,
+    Builtin,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+
+    pub kind: SymbolKind,
+
+    pub span: Option<Span>,
+
+    pub scope: (usize, usize),
+
+    pub detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub span: Span,
+
+    pub symbol: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    pub symbols: Vec<Symbol>,
+
+    pub references: Vec<Reference>,
+
+    pub diagnostics: Vec<Diagnostic>,
+
+    pub tokens: Vec<Token>,
+}
+
+type Scope = Rc<RefCell<Vec<(String, usize)>>>;
+
+struct Resolver {
+    analysis: Analysis,
+
+    braces: Vec<(usize, usize)>,
+
+    source_len: usize,
+
+    scopes: Vec<Scope>,
+
+    deferred: Vec<(Vec<Scope>, Rc<Function>)>,
+}
+
+impl Resolver {
+    fn enclosing_end(&self, offset: usize) -> usize {
+        self.braces
+            .iter()
+            .filter(|(start, end)| *start < offset && offset < *end)
+            .map(|(_, end)| *end)
+            .min()
+            .unwrap_or(self.source_len)
+    }
+
+    fn declare(&mut self, name: &Identifier, kind: SymbolKind, scope: (usize, usize)) {
+        let detail = match kind {
+            SymbolKind::Variable => format!("scratch {}", name.name),
+            SymbolKind::Constant => format!("purr {}", name.name),
+            SymbolKind::Parameter => format!("{} (parameter)", name.name),
+            _ => name.name.clone(),
+        };
+
+        self.add_symbol(Symbol {
+            name: name.name.clone(),
+            kind,
+            span: Some(name.span),
+            scope,
+            detail,
+        });
+    }
+
+    fn add_symbol(&mut self, symbol: Symbol) {
+        let index = self.analysis.symbols.len();
+        let name = symbol.name.clone();
+
+        self.analysis.symbols.push(symbol);
+        self.scopes.last().unwrap().borrow_mut().push((name, index));
+    }
+
+    fn lookup(&mut self, name: &Identifier) {
+        let symbol = self.scopes.iter().rev().find_map(|scope| {
+            scope
+                .borrow()
+                .iter()
+                .rev()
+                .find(|(x, _)| *x == name.name)
+                .map(|(_, index)| *index)
+        });
+
+        match symbol {
+            Some(symbol) => self.analysis.references.push(Reference {
+                span: name.span,
+                symbol,
+            }),
+            None => self.analysis.diagnostics.push(Diagnostic {
+                message: format!(
+                    "`{}` is not defined, declare it with `scratch` or `purr`",
+                    name.name
+                ),
+                span: name.span,
+            }),
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    fn block(&mut self, statements: &[Stmt]) {
+        self.push_scope();
+
+        for stmt in statements {
+            self.stmt(stmt);
+        }
+
+        self.scopes.pop();
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::Declaration {
+                constant,
+                name,
+                value,
+            } => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+
+                let kind = if *constant {
+                    SymbolKind::Constant
+                } else {
+                    SymbolKind::Variable
+                };
+
+                let end = self.enclosing_end(name.span.start);
+                self.declare(name, kind, (stmt.span.end, end));
+            }
+            StmtKind::Function(function) => {
+                let name = function.name.as_ref().unwrap();
+                let end = self.enclosing_end(name.span.start);
+
+                self.add_symbol(Symbol {
+                    name: name.name.clone(),
+                    kind: SymbolKind::Function,
+                    span: Some(name.span),
+                    scope: (name.span.start, end),
+                    detail: signature(function),
+                });
+
+                self.defer(function);
+            }
+            StmtKind::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.expr(condition);
+                self.block(then);
+
+                if let Some(otherwise) = otherwise {
+                    self.block(otherwise);
+                }
+            }
+            StmtKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.push_scope();
+
+                if let Some(init) = init {
+                    match &init.kind {
+                        StmtKind::Declaration {
+                            constant,
+                            name,
+                            value,
+                        } => {
+                            if let Some(value) = value {
+                                self.expr(value);
+                            }
+
+                            let kind = if *constant {
+                                SymbolKind::Constant
+                            } else {
+                                SymbolKind::Variable
+                            };
+
+                            self.declare(name, kind, (init.span.end, stmt.span.end));
+                        }
+                        _ => self.stmt(init),
+                    }
+                }
+
+                if let Some(condition) = condition {
+                    self.expr(condition);
+                }
+
+                if let Some(update) = update {
+                    self.expr(update);
+                }
+
+                self.block(body);
+                self.scopes.pop();
+            }
+            StmtKind::Try {
+                body,
+                binding,
+                handler,
+            } => {
+                self.block(body);
+                self.push_scope();
+
+                if let Some(binding) = binding {
+                    self.declare(
+                        binding,
+                        SymbolKind::Variable,
+                        (binding.span.end, stmt.span.end),
+                    );
+                }
+
+                self.block(handler);
+                self.scopes.pop();
+            }
+            StmtKind::Return(value) => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+            }
+            StmtKind::Break | StmtKind::Continue => (),
+            StmtKind::Block(statements) => self.block(statements),
+            StmtKind::Expression(expr) => self.expr(expr),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Number(_) | ExprKind::String(_) | ExprKind::Bool(_) | ExprKind::Null => (),
+            ExprKind::Variable(name) => self.lookup(name),
+            ExprKind::Member(object, _) => self.expr(object),
+            ExprKind::Call(callee, args) => {
+                self.expr(callee);
+
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+            ExprKind::Unary(_, operand) => self.expr(operand),
+            ExprKind::Binary(_, left, right) => {
+                self.expr(left);
+                self.expr(right);
+            }
+            ExprKind::Assign(_, name, value) => {
+                self.expr(value);
+                self.lookup(name);
+            }
+            ExprKind::Update { target, .. } => self.lookup(target),
+            ExprKind::Function(function) => self.defer(function),
+        }
+    }
+
+    fn defer(&mut self, function: &Rc<Function>) {
+        self.deferred.push((self.scopes.clone(), function.clone()));
+    }
+
+    fn resolve_deferred(&mut self) {
+        while !self.deferred.is_empty() {
+            let (scopes, function) = self.deferred.remove(0);
+
+            let saved = std::mem::replace(&mut self.scopes, scopes);
+
+            self.push_scope();
+
+            for param in &function.params {
+                self.declare(
+                    param,
+                    SymbolKind::Parameter,
+                    (param.span.start, function.span.end),
+                );
+            }
+
+            for stmt in &function.body {
+                self.stmt(stmt);
+            }
+
+            self.scopes = saved;
+        }
+    }
+}
+
+fn signature(function: &Function) -> String {
+    let params = function
+        .params
+        .iter()
+        .map(|x| x.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match &function.name {
+        Some(name) => format!("pawction {}({params})", name.name),
+        None => format!("pawction({params})"),
+    }
+}
+
+fn brace_pairs(tokens: &[Token]) -> Vec<(usize, usize)> {
+    let mut open = Vec::new();
+    let mut pairs = Vec::new();
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::LeftBrace => open.push(token.span.start),
+            TokenKind::RightBrace => {
+                if let Some(start) = open.pop() {
+                    pairs.push((start, token.span.start));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    pairs
+}
+
+fn declared_names(tokens: &[Token], source_len: usize) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    let mut in_params = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let TokenKind::Identifier(name) = &token.kind else {
+            in_params &= token.kind != TokenKind::RightParen;
+            continue;
+        };
+
+        let previous = i.checked_sub(1).map(|x| &tokens[x].kind);
+
+        let kind = match previous {
+            Some(TokenKind::Scratch) => SymbolKind::Variable,
+            Some(TokenKind::Purr) => SymbolKind::Constant,
+            Some(TokenKind::Pawction) => {
+                in_params = true;
+                SymbolKind::Function
+            }
+            _ if in_params => SymbolKind::Parameter,
+            _ => continue,
+        };
+
+        symbols.push(Symbol {
+            name: name.clone(),
+            kind,
+            span: Some(token.span),
+            scope: (0, source_len),
+            detail: name.clone(),
+        });
+    }
+
+    symbols
+}
+
+fn builtins(source_len: usize) -> Vec<Symbol> {
+    GLOBAL_BUILTINS
+        .iter()
+        .map(|(name, _)| (*name, SymbolKind::Builtin))
+        .chain(std::iter::once(("math", SymbolKind::Constant)))
+        .map(|(name, kind)| Symbol {
+            name: name.to_string(),
+            kind,
+            span: None,
+            scope: (0, source_len),
+            detail: format!("{name} (builtin)"),
+        })
+        .collect()
+}
+
+pub fn analyze(source: &str) -> Analysis {
+    let tokens = match meowscript::tokenize(source) {
+        Ok(x) => x,
+        Err(e) => {
+            return Analysis {
+                symbols: builtins(source.len()),
+                diagnostics: vec![Diagnostic {
+                    message: e.message,
+                    span: e.span,
+                }],
+                ..Default::default()
+            }
+        }
+    };
+
+    let program = match meowscript::parser::Parser::new(tokens.clone()).parse_program() {
+        Ok(x) => x,
+        Err(e) => {
+            let mut symbols = builtins(source.len());
+            symbols.extend(declared_names(&tokens, source.len()));
+
+            return Analysis {
+                symbols,
+                references: Vec::new(),
+                diagnostics: vec![Diagnostic {
+                    message: e.message,
+                    span: e.span,
+                }],
+                tokens,
+            };
+        }
+    };
+
+    let mut resolver = Resolver {
+        analysis: Analysis::default(),
+        braces: brace_pairs(&tokens),
+        source_len: source.len(),
+        scopes: Vec::new(),
+        deferred: Vec::new(),
+    };
+
+    resolver.push_scope();
+
+    for symbol in builtins(source.len()) {
+        resolver.add_symbol(symbol);
+    }
+
+    resolver.block(&program);
+    resolver.resolve_deferred();
+
+    let mut analysis = resolver.analysis;
+    analysis.tokens = tokens;
+    analysis
+}
+
+impl Analysis {
+    pub fn symbols_at(&self, offset: usize) -> Vec<&Symbol> {
+        let mut visible: Vec<&Symbol> = Vec::new();
+
+        for symbol in &self.symbols {
+            if symbol.scope.0 > offset || offset > symbol.scope.1 {
+                continue;
+            }
+
+            match visible.iter_mut().find(|x| x.name == symbol.name) {
+                Some(x) if x.scope.0 <= symbol.scope.0 => *x = symbol,
+                Some(_) => (),
+                None => visible.push(symbol),
+            }
+        }
+
+        visible
+    }
+
+    pub fn reference_at(&self, offset: usize) -> Option<&Symbol> {
+        if let Some(symbol) = self
+            .symbols
+            .iter()
+            .find(|x| x.span.is_some_and(|x| x.start <= offset && offset <= x.end))
+        {
+            return Some(symbol);
+        }
+
+        self.references
+            .iter()
+            .find(|x| x.span.start <= offset && offset <= x.span.end)
+            .map(|x| &self.symbols[x.symbol])
+    }
+
+    pub fn token_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.iter().find(|x| {
+            x.kind != TokenKind::Eof
+                && x.span.start <= offset
+                && offset < x.span.end.max(x.span.start + 1)
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use serde_json::{json, Value};
+
+pub struct Document {
+    pub text: String,
+
+    pub version: i64,
+
+    line_starts: Vec<usize>,
+}
+
+impl Document {
+    pub fn new(text: String, version: i64) -> Self {
+        let mut document = Self {
+            text,
+            version,
+            line_starts: Vec::new(),
+        };
+
+        document.index_lines();
+        document
+    }
+
+    fn index_lines(&mut self) {
+        self.line_starts = std::iter::once(0)
+            .chain(self.text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+    }
+
+    pub fn line(&self, line: usize) -> &str {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map(|x| x - 1)
+            .unwrap_or(self.text.len());
+
+        &self.text[start..end]
+    }
+
+    pub fn offset(&self, line: usize, character: usize) -> usize {
+        let Some(&start) = self.line_starts.get(line) else {
+            return self.text.len();
+        };
+
+        let mut units = 0;
+
+        for (i, c) in self.line(line).char_indices() {
+            if units >= character {
+                return start + i;
+            }
+
+            units += c.len_utf16();
+        }
+
+        start + self.line(line).len()
+    }
+
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|x| *x <= offset) - 1;
+        let start = self.line_starts[line];
+
+        let character = self.text[start..offset].chars().map(char::len_utf16).sum();
+
+        (line, character)
+    }
+
+    pub fn offset_of(&self, position: &Value) -> usize {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+
+        self.offset(line, character)
+    }
+
+    pub fn range(&self, start: usize, end: usize) -> Value {
+        let (start_line, start_character) = self.position(start);
+        let (end_line, end_character) = self.position(end);
+
+        json!({
+            "start": { "line": start_line, "character": start_character },
+            "end": { "line": end_line, "character": end_character },
+        })
+    }
+
+    pub fn apply_change(&mut self, change: &Value) {
+        let text = change["text"].as_str().unwrap_or_default();
+
+        match change.get("range") {
+            Some(range) => {
+                let start = self.offset_of(&range["start"]);
+                let end = self.offset_of(&range["end"]).max(start);
+
+                self.text.replace_range(start..end, text);
+            }
+            None => self.text = text.to_string(),
+        }
+
+        self.index_lines();
+    }
+
+    pub fn word_at(&self, offset: usize) -> Option<(usize, usize)> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+        let start = self.text[..offset]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_word(*c))
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(offset);
+
+        let end = self.text[offset..]
+            .char_indices()
+            .find(|(_, c)| !is_word(*c))
+            .map(|(i, _)| offset + i)
+            .unwrap_or(self.text.len());
+
+        (start < end).then_some((start, end))
+    }
+}
@@ -0,0 +1,96 @@
+use meowscript::token::TokenKind;
+
+fn block_comments(source: &str, gaps: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut comments = Vec::new();
+
+    for (start, end) in gaps {
+        let gap = &source[*start..*end];
+        let mut position = 0;
+
+        while position < gap.len() {
+            let rest = &gap[position..];
+
+            if rest.starts_with("//") {
+                position += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let length = comment.find("*/").map(|x| x + 4).unwrap_or(rest.len());
+
+                comments.push((start + position, start + position + length));
+                position += length;
+            } else {
+                position += rest.chars().next().unwrap().len_utf8();
+            }
+        }
+    }
+
+    comments
+}
+
+pub fn format(source: &str, indent: &str) -> Option<String> {
+    let tokens = meowscript::tokenize(source).ok()?;
+
+    let mut gaps = Vec::new();
+    let mut previous_end = 0;
+
+    for token in &tokens {
+        gaps.push((previous_end, token.span.start));
+        previous_end = token.span.end;
+    }
+
+    let comments = block_comments(source, &gaps);
+
+    let mut lines = Vec::new();
+    let mut depth = 0usize;
+    let mut next_token = 0;
+    let mut line_start = 0;
+
+    for line in source.split('\n') {
+        while next_token < tokens.len() && tokens[next_token].span.start < line_start {
+            match tokens[next_token].kind {
+                TokenKind::LeftBrace | TokenKind::LeftParen => depth += 1,
+                TokenKind::RightBrace | TokenKind::RightParen => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+
+            next_token += 1;
+        }
+
+        let inside_comment = comments
+            .iter()
+            .any(|(start, end)| *start < line_start && line_start < *end);
+
+        line_start += line.len() + 1;
+
+        if inside_comment {
+            lines.push(line.trim_end_matches('\r').to_string());
+            continue;
+        }
+
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            if lines.last().is_some_and(|x: &String| !x.is_empty()) {
+                lines.push(String::new());
+            }
+
+            continue;
+        }
+
+        let level = if trimmed.starts_with(['}', ')']) {
+            depth.saturating_sub(1)
+        } else {
+            depth
+        };
+
+        lines.push(format!("{}{trimmed}", indent.repeat(level)));
+    }
+
+    while lines.last().is_some_and(|x| x.is_empty()) {
+        lines.pop();
+    }
+
+    let mut formatted = lines.join("\n");
+    formatted.push('\n');
+
+    Some(formatted)
+}
@@ -0,0 +1,54 @@
+use meowtranspile::SYNTAX_MAP;
+
+const DESCRIPTIONS: &[(&str, &str)] = &[
+    ("!", "Logical not."),
+    (";", "Ends a statement, optional at the end of a line."),
+    ("=", "Assigns a value to a `scratch` binding."),
+    ("scratch", "Declares a variable."),
+    ("purr", "Declares a constant, it needs a value and can't be reassigned."),
+    ("meow", "Prints its arguments separated by spaces, followed by a newline."),
+    ("purrhaps", "Runs the block when the condition is truthy."),
+    ("null", "The absence of a value."),
+    ("meowctually", "The branch taken when the `purrhaps` condition is falsy."),
+    ("!==", "Not equal."),
+    ("!=", "Not equal."),
+    ("==", "Equal, values of different types are never equal."),
+    ("&&", "Logical and, the right side is only evaluated when the left is truthy."),
+    ("|", "Logical or, the right side is only evaluated when the left is falsy."),
+    ("||", "Logical or, the right side is only evaluated when the left is falsy."),
+    ("pawction", "Declares a function, `pawction name(a, b) { }` or `pawction name(a) -> a * 2`."),
+    ("math", "Math helpers: `floor`, `ceil`, `round`, `abs`, `sqrt`, `pow`, `min`, `max`, `random`, `pi` and `e`."),
+    ("for", "Loops while the condition holds, `for (init; condition; update) { }` or `for (condition) { }`."),
+    ("<", "Less than."),
+    (">", "Greater than."),
+    ("true", "Boolean true."),
+    ("false", "Boolean false."),
+    ("try", "Runs the block, jumping to `catch` if anything in it fails."),
+    ("catch", "Handles an error from the `try` block, `catch (e)` binds the error message."),
+    ("exec", "Runs a shell command and returns what it printed."),
+    ("reow", "Prints the optional prompt and reads a line of input, `null` at the end of input."),
+    ("-", "Subtracts numbers, or negates one."),
+    ("+", "Adds numbers, or joins strings."),
+    ("--", "Decrements a variable by one."),
+    ("++", "Increments a variable by one."),
+    ("*", "Multiplies numbers, or repeats a string."),
+    ("/", "Divides numbers."),
+    ("exit", "Stops the program, with an optional exit code."),
+    ("setTimeout", "Calls a pawction once after a delay in milliseconds and returns a timer id."),
+    ("setInterval", "Calls a pawction every interval in milliseconds and returns a timer id."),
+    ("+=", "Adds to a variable."),
+    ("-=", "Subtracts from a variable."),
+    ("*=", "Multiplies a variable."),
+    ("->", "Gives a pawction a single expression that is returned."),
+];
+
+pub fn describe(text: &str) -> Option<String> {
+    let (_, description) = DESCRIPTIONS.iter().find(|(x, _)| *x == text)?;
+
+    let mapping = match SYNTAX_MAP.iter().find(|(meow, _)| *meow == text) {
+        Some((_, replacement)) => format!("`{text}` → `{replacement}` in the replacement syntax"),
+        None => format!("`{text}` is the same in the replacement syntax"),
+    };
+
+    Some(format!("{mapping}\n\n{description}"))
+}
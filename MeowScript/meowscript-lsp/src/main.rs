@@ -0,0 +1,21 @@
+mod analysis;
+mod document;
+mod format;
+mod hover;
+mod rpc;
+mod server;
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut input = std::io::stdin().lock();
+    let mut server = server::Server::new(std::io::stdout().lock());
+
+    match server.run(&mut input) {
+        Ok(code) => ExitCode::from(code as u8),
+        Err(e) => {
+            eprintln!("meowscript-lsp: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
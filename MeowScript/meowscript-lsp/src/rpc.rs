@@ -0,0 +1,48 @@
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+// a message whose body isn't valid JSON is returned as the parse error, the stream itself is still usable
+pub fn read_message(
+    reader: &mut impl BufRead,
+) -> std::io::Result<Option<Result<Value, serde_json::Error>>> {
+    let mut length = None;
+
+    loop {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(length) = length else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "message without a Content-Length header",
+        ));
+    };
+
+    let mut content = vec![0; length];
+    reader.read_exact(&mut content)?;
+
+    Ok(Some(serde_json::from_slice(&content)))
+}
+
+pub fn write_message(writer: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let content = message.to_string();
+
+    write!(writer, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    writer.flush()
+}
@@ -0,0 +1,325 @@
+use crate::analysis::{analyze, Analysis, SymbolKind};
+use crate::document::Document;
+use crate::rpc::{read_message, write_message};
+use meowscript::token::KEYWORDS;
+use meowscript::value::{MATH_CONSTANTS, MATH_FUNCTIONS};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const COMPLETION_FUNCTION: i64 = 3;
+const COMPLETION_VARIABLE: i64 = 6;
+const COMPLETION_PROPERTY: i64 = 10;
+const COMPLETION_KEYWORD: i64 = 14;
+const COMPLETION_CONSTANT: i64 = 21;
+
+struct Entry {
+    document: Document,
+
+    analysis: Analysis,
+}
+
+pub struct Server<W: Write> {
+    output: W,
+
+    entries: HashMap<String, Entry>,
+
+    shutting_down: bool,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            entries: HashMap::new(),
+            shutting_down: false,
+        }
+    }
+
+    pub fn run(&mut self, input: &mut impl BufRead) -> std::io::Result<i32> {
+        while let Some(message) = read_message(input)? {
+            let message = match message {
+                Ok(x) => x,
+                Err(e) => {
+                    let response = json!({
+                        "jsonrpc": "2.0",
+                        "id": null,
+                        "error": { "code": PARSE_ERROR, "message": format!("Parse error: {e}") },
+                    });
+
+                    write_message(&mut self.output, &response)?;
+                    continue;
+                }
+            };
+
+            let Some(method) = message["method"].as_str() else {
+                continue;
+            };
+
+            let params = &message["params"];
+
+            match message.get("id") {
+                Some(id) => {
+                    let response = match self.request(method, params) {
+                        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                        Err((code, message)) => json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": code, "message": message },
+                        }),
+                    };
+
+                    write_message(&mut self.output, &response)?;
+                }
+                None if method == "exit" => return Ok(if self.shutting_down { 0 } else { 1 }),
+                None => self.notification(method, params)?,
+            }
+        }
+
+        Ok(1)
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": 2 },
+                    "completionProvider": { "triggerCharacters": ["."] },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": {
+                    "name": "meowscript-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutting_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/completion" => self.with_entry(params, completion),
+            "textDocument/hover" => self.with_entry(params, hover),
+            "textDocument/definition" => self.with_entry(params, definition),
+            "textDocument/formatting" => self.with_entry(params, formatting),
+            _ => Err((METHOD_NOT_FOUND, format!("unsupported request `{method}`"))),
+        }
+    }
+
+    fn with_entry(
+        &self,
+        params: &Value,
+        handler: fn(&Entry, &Value) -> Value,
+    ) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        match self.entries.get(uri) {
+            Some(entry) => Ok(handler(entry, params)),
+            None => Err((INVALID_PARAMS, format!("`{uri}` is not open"))),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> std::io::Result<()> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let version = params["textDocument"]["version"].as_i64().unwrap_or(0);
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                let document = Document::new(text.to_string(), version);
+
+                self.entries.insert(
+                    uri.clone(),
+                    Entry {
+                        analysis: analyze(&document.text),
+                        document,
+                    },
+                );
+            }
+            "textDocument/didChange" => {
+                let Some(entry) = self.entries.get_mut(&uri) else {
+                    return Ok(());
+                };
+
+                for change in params["contentChanges"].as_array().into_iter().flatten() {
+                    entry.document.apply_change(change);
+                }
+
+                entry.document.version = version;
+                entry.analysis = analyze(&entry.document.text);
+            }
+            "textDocument/didClose" => {
+                self.entries.remove(&uri);
+
+                return write_message(
+                    &mut self.output,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "method": "textDocument/publishDiagnostics",
+                        "params": { "uri": uri, "diagnostics": [] },
+                    }),
+                );
+            }
+            _ => return Ok(()),
+        }
+
+        self.publish_diagnostics(&uri)
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> std::io::Result<()> {
+        let Some(entry) = self.entries.get(uri) else {
+            return Ok(());
+        };
+
+        let diagnostics = entry
+            .analysis
+            .diagnostics
+            .iter()
+            .map(|x| {
+                json!({
+                    "range": entry.document.range(x.span.start, x.span.end.max(x.span.start)),
+                    "severity": 1,
+                    "source": "meowscript",
+                    "message": x.message,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "version": entry.document.version,
+                "diagnostics": diagnostics,
+            },
+        });
+
+        write_message(&mut self.output, &message)
+    }
+}
+
+fn completion(entry: &Entry, params: &Value) -> Value {
+    let document = &entry.document;
+    let offset = document.offset_of(&params["position"]);
+
+    let prefix_start = document
+        .word_at(offset)
+        .map(|(start, _)| start)
+        .unwrap_or(offset);
+
+    let before = document.text[..prefix_start].trim_end();
+
+    let items = if let Some(object) = before.strip_suffix('.') {
+        let is_math = object
+            .trim_end()
+            .strip_suffix("math")
+            .is_some_and(|x| !x.ends_with(|x: char| x.is_alphanumeric() || x == '_'));
+
+        if !is_math {
+            return json!([]);
+        }
+
+        MATH_FUNCTIONS
+            .iter()
+            .map(|(name, _)| json!({ "label": name, "kind": COMPLETION_FUNCTION, "detail": format!("math.{name}") }))
+            .chain(MATH_CONSTANTS.iter().map(|(name, value)| {
+                json!({ "label": name, "kind": COMPLETION_PROPERTY, "detail": format!("math.{name} = {value}") })
+            }))
+            .collect::<Vec<_>>()
+    } else {
+        let keywords = KEYWORDS
+            .iter()
+            .map(|(name, _)| json!({ "label": name, "kind": COMPLETION_KEYWORD }));
+
+        let symbols = entry.analysis.symbols_at(offset).into_iter().map(|x| {
+            let kind = match x.kind {
+                SymbolKind::Function | SymbolKind::Builtin => COMPLETION_FUNCTION,
+                SymbolKind::Constant => COMPLETION_CONSTANT,
+                SymbolKind::Variable | SymbolKind::Parameter => COMPLETION_VARIABLE,
+            };
+
+            json!({ "label": x.name, "kind": kind, "detail": x.detail })
+        });
+
+        keywords.chain(symbols).collect()
+    };
+
+    json!({ "isIncomplete": false, "items": items })
+}
+
+fn hover(entry: &Entry, params: &Value) -> Value {
+    let document = &entry.document;
+    let offset = document.offset_of(&params["position"]);
+
+    let (start, end, text) = match entry.analysis.token_at(offset) {
+        Some(token) => (
+            token.span.start,
+            token.span.end,
+            &document.text[token.span.start..token.span.end],
+        ),
+        None => match document.word_at(offset) {
+            Some((start, end)) => (start, end, &document.text[start..end]),
+            None => return Value::Null,
+        },
+    };
+
+    let user_symbol = entry
+        .analysis
+        .reference_at(offset)
+        .filter(|x| x.kind != SymbolKind::Builtin);
+
+    let contents = match user_symbol {
+        Some(symbol) => format!("```meowscript\n{}\n```", symbol.detail),
+        None => match crate::hover::describe(text) {
+            Some(x) => x,
+            None => return Value::Null,
+        },
+    };
+
+    json!({
+        "contents": { "kind": "markdown", "value": contents },
+        "range": document.range(start, end),
+    })
+}
+
+fn definition(entry: &Entry, params: &Value) -> Value {
+    let document = &entry.document;
+    let offset = document.offset_of(&params["position"]);
+
+    match entry.analysis.reference_at(offset).and_then(|x| x.span) {
+        Some(span) => json!({
+            "uri": params["textDocument"]["uri"],
+            "range": document.range(span.start, span.end),
+        }),
+        None => Value::Null,
+    }
+}
+
+fn formatting(entry: &Entry, params: &Value) -> Value {
+    let options = &params["options"];
+
+    let indent = if options["insertSpaces"].as_bool().unwrap_or(true) {
+        " ".repeat(options["tabSize"].as_u64().unwrap_or(4) as usize)
+    } else {
+        "\t".to_string()
+    };
+
+    let document = &entry.document;
+
+    match crate::format::format(&document.text, &indent) {
+        Some(formatted) if formatted != document.text => json!([{
+            "range": document.range(0, document.text.len()),
+            "newText": formatted,
+        }]),
+        _ => json!([]),
+    }
+}
@@ -0,0 +1,364 @@
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const URI: &str = "file:///tmp/cats.meow";
+
+struct Client {
+    child: Child,
+
+    stdin: ChildStdin,
+
+    stdout: BufReader<ChildStdout>,
+
+    next_id: i64,
+
+    notifications: Vec<Value>,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_meowscript-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+
+        let mut client = Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+            notifications: Vec::new(),
+        };
+
+        client.request("initialize", json!({ "capabilities": {} }));
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn send(&mut self, message: Value) {
+        let content = message.to_string();
+
+        write!(
+            self.stdin,
+            "Content-Length: {}\r\n\r\n{content}",
+            content.len()
+        )
+        .unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = 0;
+
+        loop {
+            let mut line = String::new();
+            self.stdout.read_line(&mut line).unwrap();
+
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+
+        let mut content = vec![0; length];
+        self.stdout.read_exact(&mut content).unwrap();
+
+        serde_json::from_slice(&content).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+
+        loop {
+            let message = self.receive();
+
+            if message["id"] == id {
+                return message;
+            }
+
+            self.notifications.push(message);
+        }
+    }
+
+    fn diagnostics(&mut self) -> Vec<Value> {
+        let message = match self.notifications.pop() {
+            Some(x) => x,
+            None => self.receive(),
+        };
+
+        assert_eq!(message["method"], "textDocument/publishDiagnostics");
+
+        message["params"]["diagnostics"].as_array().unwrap().clone()
+    }
+
+    fn open(&mut self, text: &str) -> Vec<Value> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "meowscript", "version": 1, "text": text },
+            }),
+        );
+
+        self.diagnostics()
+    }
+
+    fn at(&mut self, method: &str, line: usize, character: usize) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+            }),
+        )["result"]
+            .clone()
+    }
+
+    fn stop(mut self) {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+fn labels(completion: &Value) -> Vec<String> {
+    completion["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| x["label"].as_str().unwrap().to_string())
+        .collect()
+}
+
+const PROGRAM: &str = "purr name = \"Tom\";
+
+pawction greet(who) {
+    scratch greeting = \"Hello, \" + who;
+    meow(greeting);
+}
+
+greet(name);
+";
+
+#[test]
+fn initialize_advertises_capabilities() {
+    let mut client = Client::start();
+
+    let response = client.request("initialize", json!({ "capabilities": {} }));
+    let capabilities = &response["result"]["capabilities"];
+
+    assert_eq!(capabilities["textDocumentSync"]["change"], 2);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(capabilities["documentFormattingProvider"], true);
+
+    client.stop();
+}
+
+#[test]
+fn reports_syntax_and_undefined_name_errors() {
+    let mut client = Client::start();
+
+    assert!(client.open(PROGRAM).is_empty());
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{
+                "range": { "start": { "line": 7, "character": 6 }, "end": { "line": 7, "character": 10 } },
+                "text": "nmae",
+            }],
+        }),
+    );
+
+    let diagnostics = client.diagnostics();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0]["message"],
+        "`nmae` is not defined, declare it with `scratch` or `purr`"
+    );
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({ "start": { "line": 7, "character": 6 }, "end": { "line": 7, "character": 10 } })
+    );
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 3 },
+            "contentChanges": [{ "text": "scratch = 1;" }],
+        }),
+    );
+
+    let diagnostics = client.diagnostics();
+
+    assert_eq!(
+        diagnostics[0]["message"],
+        "expected a name after `scratch`, found `=`"
+    );
+
+    client.stop();
+}
+
+#[test]
+fn functions_may_use_names_declared_after_them() {
+    let mut client = Client::start();
+
+    let diagnostics =
+        client.open("pawction first() -> second()\npawction second() -> 1\nmeow(first());\n");
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    client.stop();
+}
+
+#[test]
+fn completes_keywords_and_names_in_scope() {
+    let mut client = Client::start();
+
+    client.open(PROGRAM);
+
+    let inside = labels(&client.at("textDocument/completion", 4, 4));
+
+    for expected in [
+        "scratch", "purrhaps", "meow", "name", "greet", "who", "greeting",
+    ] {
+        assert!(inside.contains(&expected.to_string()), "missing {expected}");
+    }
+
+    let outside = labels(&client.at("textDocument/completion", 7, 0));
+
+    assert!(outside.contains(&"greet".to_string()));
+    assert!(!outside.contains(&"who".to_string()));
+    assert!(!outside.contains(&"greeting".to_string()));
+
+    client.stop();
+}
+
+#[test]
+fn completes_math_members() {
+    let mut client = Client::start();
+
+    client.open("meow(math.fl);\n");
+
+    let members = labels(&client.at("textDocument/completion", 0, 12));
+
+    assert!(members.contains(&"floor".to_string()));
+    assert!(members.contains(&"pi".to_string()));
+    assert!(!members.contains(&"scratch".to_string()));
+
+    client.stop();
+}
+
+#[test]
+fn goes_to_pawction_definitions() {
+    let mut client = Client::start();
+
+    client.open(PROGRAM);
+
+    let location = client.at("textDocument/definition", 7, 2);
+
+    assert_eq!(location["uri"], URI);
+    assert_eq!(
+        location["range"],
+        json!({ "start": { "line": 2, "character": 9 }, "end": { "line": 2, "character": 14 } })
+    );
+
+    client.stop();
+}
+
+#[test]
+fn hover_shows_the_mapped_keyword() {
+    let mut client = Client::start();
+
+    client.open(PROGRAM);
+
+    let hover = client.at("textDocument/hover", 3, 6);
+    let contents = hover["contents"]["value"].as_str().unwrap();
+
+    assert!(contents.starts_with("`scratch` → `let` in the replacement syntax"));
+
+    let hover = client.at("textDocument/hover", 2, 1);
+    let contents = hover["contents"]["value"].as_str().unwrap();
+
+    assert!(contents.starts_with("`pawction` → `fn`"));
+
+    let hover = client.at("textDocument/hover", 7, 1);
+
+    assert_eq!(
+        hover["contents"]["value"],
+        "```meowscript\npawction greet(who)\n```"
+    );
+
+    client.stop();
+}
+
+#[test]
+fn answers_invalid_json_with_a_parse_error() {
+    let mut client = Client::start();
+
+    let content = "{\"jsonrpc\": \"2.0\", \"id\": ";
+
+    write!(
+        client.stdin,
+        "Content-Length: {}\r\n\r\n{content}",
+        content.len()
+    )
+    .unwrap();
+    client.stdin.flush().unwrap();
+
+    let response = client.receive();
+
+    assert_eq!(response["id"], Value::Null);
+    assert_eq!(response["error"]["code"], -32700);
+
+    let diagnostics = client.open(PROGRAM);
+
+    assert!(diagnostics.is_empty());
+
+    client.stop();
+}
+
+#[test]
+fn formats_indentation() {
+    let mut client = Client::start();
+
+    client.open(
+        "pawction f(x) {\npurrhaps x {\n  meow(\"a  b\")   \n}\n\n\n\n   /* keep\n      this */\nreturn x\n    }\n\n",
+    );
+
+    let edits = client.request(
+        "textDocument/formatting",
+        json!({
+            "textDocument": { "uri": URI },
+            "options": { "tabSize": 4, "insertSpaces": true },
+        }),
+    )["result"]
+        .clone();
+
+    assert_eq!(
+        edits[0]["newText"],
+        "pawction f(x) {\n    purrhaps x {\n        meow(\"a  b\")\n    }\n\n    /* keep\n      this */\n    return x\n}\n"
+    );
+
+    client.stop();
+}
@@ -0,0 +1,87 @@
+use crate::value::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+struct Binding {
+    value: Value,
+
+    constant: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    bindings: HashMap<String, Binding>,
+
+    parent: Option<Environment>,
+}
+
+#[derive(Clone, Default)]
+pub struct Environment(Rc<RefCell<Scope>>);
+
+pub enum AssignError {
+    Undefined,
+    Constant,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn child(&self) -> Self {
+        Self(Rc::new(RefCell::new(Scope {
+            bindings: HashMap::new(),
+            parent: Some(self.clone()),
+        })))
+    }
+
+    pub fn is_declared_here(&self, name: &str) -> bool {
+        self.0.borrow().bindings.contains_key(name)
+    }
+
+    pub fn define(&self, name: &str, value: Value, constant: bool) {
+        self.0
+            .borrow_mut()
+            .bindings
+            .insert(name.to_string(), Binding { value, constant });
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        let scope = self.0.borrow();
+
+        match scope.bindings.get(name) {
+            Some(x) => Some(x.value.clone()),
+            None => scope.parent.as_ref()?.get(name),
+        }
+    }
+
+    pub fn assign(&self, name: &str, value: Value) -> Result<(), AssignError> {
+        let mut scope = self.0.borrow_mut();
+
+        match scope.bindings.get_mut(name) {
+            Some(x) if x.constant => Err(AssignError::Constant),
+            Some(x) => {
+                x.value = value;
+                Ok(())
+            }
+            None => match &scope.parent {
+                Some(x) => x.assign(name, value),
+                None => Err(AssignError::Undefined),
+            },
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let scope = self.0.borrow();
+
+        let mut names = scope
+            .parent
+            .as_ref()
+            .map(Environment::names)
+            .unwrap_or_default();
+
+        names.extend(scope.bindings.keys().cloned());
+        names
+    }
+}
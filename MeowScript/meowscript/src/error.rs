@@ -0,0 +1,51 @@
+use crate::token::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Syntax,
+    Runtime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+
+    pub message: String,
+
+    pub span: Span,
+}
+
+impl Error {
+    pub fn syntax(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            kind: ErrorKind::Syntax,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn runtime(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            kind: ErrorKind::Runtime,
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let kind = match self.kind {
+            ErrorKind::Syntax => "syntax error",
+            ErrorKind::Runtime => "runtime error",
+        };
+
+        write!(
+            f,
+            "{}:{}: {kind}: {}",
+            self.span.line, self.span.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,742 @@
+use crate::ast::*;
+use crate::environment::{AssignError, Environment};
+use crate::error::Error;
+use crate::token::Span;
+use crate::value::*;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const MAX_CALL_DEPTH: usize = 512;
+
+// the longest string repeating one with `*` may make, in bytes
+const MAX_STRING_LENGTH: usize = 1 << 28;
+
+enum Control {
+    Error(Error),
+    Return(Value, Span),
+    Break(Span),
+    Continue(Span),
+    Exit,
+}
+
+impl From<Error> for Control {
+    fn from(error: Error) -> Self {
+        Self::Error(error)
+    }
+}
+
+type Flow<T> = Result<T, Control>;
+
+struct Timer {
+    id: u64,
+
+    due: Instant,
+
+    interval: Option<Duration>,
+
+    callback: Value,
+
+    span: Span,
+}
+
+pub struct Interpreter {
+    globals: Environment,
+
+    input: Box<dyn BufRead>,
+
+    output: Box<dyn Write>,
+
+    timers: Vec<Timer>,
+
+    next_timer_id: u64,
+
+    depth: usize,
+
+    exit_code: Option<i32>,
+
+    random_state: u64,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_io(
+            Box::new(std::io::BufReader::new(std::io::stdin())),
+            Box::new(std::io::stdout()),
+        )
+    }
+
+    pub fn with_io(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        let globals = Environment::new();
+
+        for (name, builtin) in GLOBAL_BUILTINS {
+            globals.define(name, Value::Builtin(*builtin), false);
+        }
+
+        globals.define("math", Value::Math, true);
+
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|x| x.as_nanos() as u64)
+            .unwrap_or(0x2545_f491_4f6c_dd1d);
+
+        Self {
+            globals,
+            input,
+            output,
+            timers: Vec::new(),
+            next_timer_id: 1,
+            depth: 0,
+            exit_code: None,
+            random_state: seed | 1,
+        }
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn global_names(&self) -> Vec<String> {
+        self.globals.names()
+    }
+
+    pub fn execute(&mut self, program: &Program) -> Result<Value, Error> {
+        let globals = self.globals.clone();
+
+        let mut last = Value::Null;
+
+        for stmt in program {
+            if self.exit_code.is_some() {
+                break;
+            }
+
+            let result = match &stmt.kind {
+                StmtKind::Expression(expr) => self.eval(expr, &globals).map(|x| last = x),
+                _ => {
+                    last = Value::Null;
+                    self.exec(stmt, &globals)
+                }
+            };
+
+            match result {
+                Ok(()) | Err(Control::Exit) => (),
+                Err(control) => return Err(self.escaped(control)),
+            }
+        }
+
+        self.output.flush().ok();
+
+        Ok(last)
+    }
+
+    pub fn run_event_loop(&mut self) -> Result<(), Error> {
+        while self.exit_code.is_none() {
+            let Some(index) =
+                (0..self.timers.len()).min_by_key(|x| (self.timers[*x].due, self.timers[*x].id))
+            else {
+                break;
+            };
+
+            let due = self.timers[index].due;
+
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                self.output.flush().ok();
+                std::thread::sleep(wait);
+            }
+
+            let timer = &mut self.timers[index];
+            let callback = timer.callback.clone();
+            let span = timer.span;
+
+            match timer.interval {
+                Some(interval) => timer.due += interval,
+                None => {
+                    self.timers.remove(index);
+                }
+            }
+
+            match self.call(&callback, Vec::new(), span) {
+                Ok(_) | Err(Control::Exit) => (),
+                Err(control) => return Err(self.escaped(control)),
+            }
+        }
+
+        self.output.flush().ok();
+
+        Ok(())
+    }
+
+    pub fn read_line(&mut self) -> std::io::Result<Option<String>> {
+        self.output.flush()?;
+
+        let mut line = String::new();
+
+        match self.input.read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line.trim_end_matches(['\n', '\r']).to_string())),
+        }
+    }
+
+    fn escaped(&mut self, control: Control) -> Error {
+        self.output.flush().ok();
+
+        match control {
+            Control::Error(e) => e,
+            Control::Return(_, span) => Error::runtime("`return` outside of a pawction", span),
+            Control::Break(span) => Error::runtime("`break` outside of a loop", span),
+            Control::Continue(span) => Error::runtime("`continue` outside of a loop", span),
+            Control::Exit => unreachable!(),
+        }
+    }
+
+    fn exec_block(&mut self, statements: &[Stmt], environment: &Environment) -> Flow<()> {
+        let scope = environment.child();
+
+        for stmt in statements {
+            self.exec(stmt, &scope)?;
+        }
+
+        Ok(())
+    }
+
+    fn declare(
+        &mut self,
+        name: &Identifier,
+        value: Value,
+        constant: bool,
+        environment: &Environment,
+    ) -> Flow<()> {
+        if environment.is_declared_here(&name.name) {
+            return Err(Error::runtime(
+                format!("`{}` is already declared in this scope", name.name),
+                name.span,
+            )
+            .into());
+        }
+
+        environment.define(&name.name, value, constant);
+
+        Ok(())
+    }
+
+    fn exec(&mut self, stmt: &Stmt, environment: &Environment) -> Flow<()> {
+        match &stmt.kind {
+            StmtKind::Declaration {
+                constant,
+                name,
+                value,
+            } => {
+                let value = match value {
+                    Some(x) => self.eval(x, environment)?,
+                    None => Value::Null,
+                };
+
+                self.declare(name, value, *constant, environment)
+            }
+            StmtKind::Function(function) => {
+                let closure = Value::Function(Rc::new(Closure {
+                    function: function.clone(),
+                    environment: environment.clone(),
+                }));
+
+                let name = function.name.as_ref().unwrap();
+
+                self.declare(name, closure, false, environment)
+            }
+            StmtKind::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if self.eval(condition, environment)?.is_truthy() {
+                    self.exec_block(then, environment)
+                } else if let Some(otherwise) = otherwise {
+                    self.exec_block(otherwise, environment)
+                } else {
+                    Ok(())
+                }
+            }
+            StmtKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                let scope = environment.child();
+
+                if let Some(init) = init {
+                    self.exec(init, &scope)?;
+                }
+
+                loop {
+                    if let Some(condition) = condition {
+                        if !self.eval(condition, &scope)?.is_truthy() {
+                            break;
+                        }
+                    }
+
+                    match self.exec_block(body, &scope) {
+                        Ok(()) | Err(Control::Continue(_)) => (),
+                        Err(Control::Break(_)) => break,
+                        Err(x) => return Err(x),
+                    }
+
+                    if let Some(update) = update {
+                        self.eval(update, &scope)?;
+                    }
+                }
+
+                Ok(())
+            }
+            StmtKind::Try {
+                body,
+                binding,
+                handler,
+            } => match self.exec_block(body, environment) {
+                Err(Control::Error(error)) => {
+                    let scope = environment.child();
+
+                    if let Some(binding) = binding {
+                        scope.define(
+                            &binding.name,
+                            Value::String(error.message.as_str().into()),
+                            false,
+                        );
+                    }
+
+                    self.exec_block(handler, &scope)
+                }
+                x => x,
+            },
+            StmtKind::Return(value) => {
+                let value = match value {
+                    Some(x) => self.eval(x, environment)?,
+                    None => Value::Null,
+                };
+
+                Err(Control::Return(value, stmt.span))
+            }
+            StmtKind::Break => Err(Control::Break(stmt.span)),
+            StmtKind::Continue => Err(Control::Continue(stmt.span)),
+            StmtKind::Block(statements) => self.exec_block(statements, environment),
+            StmtKind::Expression(expr) => self.eval(expr, environment).map(|_| ()),
+        }
+    }
+
+    fn lookup(&self, name: &Identifier, environment: &Environment) -> Flow<Value> {
+        environment.get(&name.name).ok_or_else(|| {
+            Error::runtime(
+                format!(
+                    "`{}` is not defined, declare it with `scratch` or `purr`",
+                    name.name
+                ),
+                name.span,
+            )
+            .into()
+        })
+    }
+
+    fn assign(&self, name: &Identifier, value: Value, environment: &Environment) -> Flow<()> {
+        environment.assign(&name.name, value).map_err(|e| {
+            let message = match e {
+                AssignError::Undefined => format!(
+                    "`{}` is not defined, declare it with `scratch` before assigning to it",
+                    name.name
+                ),
+                AssignError::Constant => {
+                    format!("`{}` is a `purr` constant and can't be changed", name.name)
+                }
+            };
+
+            Error::runtime(message, name.span).into()
+        })
+    }
+
+    fn eval(&mut self, expr: &Expr, environment: &Environment) -> Flow<Value> {
+        Ok(match &expr.kind {
+            ExprKind::Number(x) => Value::Number(*x),
+            ExprKind::String(x) => Value::String(x.as_str().into()),
+            ExprKind::Bool(x) => Value::Bool(*x),
+            ExprKind::Null => Value::Null,
+            ExprKind::Variable(name) => self.lookup(name, environment)?,
+            ExprKind::Member(object, member) => {
+                let object = self.eval(object, environment)?;
+                self.member(&object, member)?
+            }
+            ExprKind::Call(callee, args) => {
+                let callee = self.eval(callee, environment)?;
+
+                let args = args
+                    .iter()
+                    .map(|x| Ok((self.eval(x, environment)?, x.span)))
+                    .collect::<Flow<Vec<(Value, Span)>>>()?;
+
+                self.call(&callee, args, expr.span)?
+            }
+            ExprKind::Unary(op, operand) => {
+                let value = self.eval(operand, environment)?;
+
+                match (op, value) {
+                    (UnaryOp::Not, x) => Value::Bool(!x.is_truthy()),
+                    (UnaryOp::Negate, Value::Number(x)) => Value::Number(-x),
+                    (UnaryOp::Negate, x) => {
+                        return Err(Error::runtime(
+                            format!("can't negate a {}", x.type_name()),
+                            operand.span,
+                        )
+                        .into())
+                    }
+                }
+            }
+            ExprKind::Binary(BinaryOp::And, left, right) => {
+                let left = self.eval(left, environment)?;
+
+                if left.is_truthy() {
+                    self.eval(right, environment)?
+                } else {
+                    left
+                }
+            }
+            ExprKind::Binary(BinaryOp::Or, left, right) => {
+                let left = self.eval(left, environment)?;
+
+                if left.is_truthy() {
+                    left
+                } else {
+                    self.eval(right, environment)?
+                }
+            }
+            ExprKind::Binary(op, left, right) => {
+                let left_value = self.eval(left, environment)?;
+                let right_value = self.eval(right, environment)?;
+
+                binary(*op, left_value, right_value, right.span, expr.span)?
+            }
+            ExprKind::Assign(op, name, value) => {
+                let value = self.eval(value, environment)?;
+
+                let value = match op {
+                    AssignOp::Set => value,
+                    _ => {
+                        let current = self.lookup(name, environment)?;
+
+                        let op = match op {
+                            AssignOp::Add => BinaryOp::Add,
+                            AssignOp::Subtract => BinaryOp::Subtract,
+                            AssignOp::Multiply => BinaryOp::Multiply,
+                            _ => BinaryOp::Divide,
+                        };
+
+                        binary(op, current, value, expr.span, expr.span)?
+                    }
+                };
+
+                self.assign(name, value.clone(), environment)?;
+                value
+            }
+            ExprKind::Update { op, prefix, target } => {
+                let current = match self.lookup(target, environment)? {
+                    Value::Number(x) => x,
+                    x => {
+                        return Err(Error::runtime(
+                            format!("can't increment or decrement a {}", x.type_name()),
+                            target.span,
+                        )
+                        .into())
+                    }
+                };
+
+                let next = match op {
+                    UpdateOp::Increment => current + 1.0,
+                    UpdateOp::Decrement => current - 1.0,
+                };
+
+                self.assign(target, Value::Number(next), environment)?;
+
+                Value::Number(if *prefix { next } else { current })
+            }
+            ExprKind::Function(function) => Value::Function(Rc::new(Closure {
+                function: function.clone(),
+                environment: environment.clone(),
+            })),
+        })
+    }
+
+    fn member(&self, object: &Value, member: &Identifier) -> Flow<Value> {
+        if let Value::Math = object {
+            if let Some((_, x)) = MATH_FUNCTIONS.iter().find(|(x, _)| *x == member.name) {
+                return Ok(Value::Builtin(Builtin::Math(*x)));
+            }
+
+            if let Some((_, x)) = MATH_CONSTANTS.iter().find(|(x, _)| *x == member.name) {
+                return Ok(Value::Number(*x));
+            }
+        }
+
+        Err(Error::runtime(
+            format!("{} has no member `{}`", object.type_name(), member.name),
+            member.span,
+        )
+        .into())
+    }
+
+    fn call(&mut self, callee: &Value, args: Vec<(Value, Span)>, span: Span) -> Flow<Value> {
+        match callee {
+            Value::Function(closure) => {
+                let function = &closure.function;
+
+                if args.len() != function.params.len() {
+                    let name = function
+                        .name
+                        .as_ref()
+                        .map(|x| format!("pawction `{}`", x.name))
+                        .unwrap_or_else(|| "this pawction".to_string());
+
+                    return Err(Error::runtime(
+                        format!(
+                            "{name} takes {} argument{} but {} {} given",
+                            function.params.len(),
+                            if function.params.len() == 1 { "" } else { "s" },
+                            args.len(),
+                            if args.len() == 1 { "was" } else { "were" },
+                        ),
+                        span,
+                    )
+                    .into());
+                }
+
+                if self.depth >= MAX_CALL_DEPTH {
+                    return Err(Error::runtime("too much recursion", span).into());
+                }
+
+                let scope = closure.environment.child();
+
+                for (param, (value, _)) in function.params.iter().zip(args) {
+                    scope.define(&param.name, value, false);
+                }
+
+                self.depth += 1;
+
+                let result = function
+                    .body
+                    .iter()
+                    .try_for_each(|stmt| self.exec(stmt, &scope));
+
+                self.depth -= 1;
+
+                match result {
+                    Ok(()) => Ok(Value::Null),
+                    Err(Control::Return(value, _)) => Ok(value),
+                    Err(Control::Break(span)) => {
+                        Err(Error::runtime("`break` outside of a loop", span).into())
+                    }
+                    Err(Control::Continue(span)) => {
+                        Err(Error::runtime("`continue` outside of a loop", span).into())
+                    }
+                    Err(x) => Err(x),
+                }
+            }
+            Value::Builtin(builtin) => self.call_builtin(*builtin, args, span),
+            x => Err(Error::runtime(format!("a {} is not a pawction", x.type_name()), span).into()),
+        }
+    }
+
+    fn call_builtin(
+        &mut self,
+        builtin: Builtin,
+        args: Vec<(Value, Span)>,
+        span: Span,
+    ) -> Flow<Value> {
+        let number = |index: usize, name: &str| -> Flow<f64> {
+            match args.get(index) {
+                Some((Value::Number(x), _)) => Ok(*x),
+                Some((x, span)) => Err(Error::runtime(
+                    format!("`{name}` expects a number, got a {}", x.type_name()),
+                    *span,
+                )
+                .into()),
+                None => Err(Error::runtime(format!("`{name}` expects a number"), span).into()),
+            }
+        };
+
+        Ok(match builtin {
+            Builtin::Meow => {
+                let line = args
+                    .iter()
+                    .map(|(x, _)| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                writeln!(self.output, "{line}")
+                    .map_err(|e| Error::runtime(format!("failed to write output: {e}"), span))?;
+
+                Value::Null
+            }
+            Builtin::Reow => {
+                if let Some((prompt, _)) = args.first() {
+                    write!(self.output, "{prompt}").ok();
+                }
+
+                match self.read_line() {
+                    Ok(None) => Value::Null,
+                    Ok(Some(line)) => Value::String(line.into()),
+                    Err(e) => {
+                        return Err(
+                            Error::runtime(format!("failed to read input: {e}"), span).into()
+                        )
+                    }
+                }
+            }
+            Builtin::Exec => {
+                let command = match args.first() {
+                    Some((Value::String(x), _)) => x.clone(),
+                    _ => return Err(Error::runtime("`exec` expects a command string", span).into()),
+                };
+
+                self.output.flush().ok();
+
+                let output = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&*command)
+                    .stderr(std::process::Stdio::inherit())
+                    .output()
+                    .map_err(|e| Error::runtime(format!("failed to run `{command}`: {e}"), span))?;
+
+                let stdout = String::from_utf8_lossy(&output.stdout);
+
+                Value::String(stdout.trim_end_matches('\n').into())
+            }
+            Builtin::Exit => {
+                self.exit_code = Some(match args.first() {
+                    Some(_) => number(0, "exit")? as i32,
+                    None => 0,
+                });
+
+                return Err(Control::Exit);
+            }
+            Builtin::SetTimeout | Builtin::SetInterval => {
+                let callback = match args.first() {
+                    Some((x @ (Value::Function(_) | Value::Builtin(_)), _)) => x.clone(),
+                    _ => {
+                        return Err(Error::runtime(
+                            "expected a pawction to call when the timer fires",
+                            span,
+                        )
+                        .into())
+                    }
+                };
+
+                let delay = Duration::from_secs_f64(
+                    (args
+                        .get(1)
+                        .map(|_| number(1, "delay"))
+                        .transpose()?
+                        .unwrap_or(0.0)
+                        / 1000.0)
+                        .max(0.0),
+                );
+
+                let id = self.next_timer_id;
+
+                self.next_timer_id += 1;
+
+                self.timers.push(Timer {
+                    id,
+                    due: Instant::now() + delay,
+                    interval: (builtin == Builtin::SetInterval)
+                        .then_some(delay.max(Duration::from_millis(1))),
+                    callback,
+                    span,
+                });
+
+                Value::Number(id as f64)
+            }
+            Builtin::ClearTimeout => {
+                let id = number(0, "timer id")? as u64;
+
+                self.timers.retain(|x| x.id != id);
+
+                Value::Null
+            }
+            Builtin::Math(function) => Value::Number(match function {
+                MathFunction::Floor => number(0, "math.floor")?.floor(),
+                MathFunction::Ceil => number(0, "math.ceil")?.ceil(),
+                MathFunction::Round => number(0, "math.round")?.round(),
+                MathFunction::Abs => number(0, "math.abs")?.abs(),
+                MathFunction::Sqrt => number(0, "math.sqrt")?.sqrt(),
+                MathFunction::Pow => number(0, "math.pow")?.powf(number(1, "math.pow")?),
+                MathFunction::Min => number(0, "math.min")?.min(number(1, "math.min")?),
+                MathFunction::Max => number(0, "math.max")?.max(number(1, "math.max")?),
+                MathFunction::Random => {
+                    self.random_state ^= self.random_state << 13;
+                    self.random_state ^= self.random_state >> 7;
+                    self.random_state ^= self.random_state << 17;
+
+                    (self.random_state >> 11) as f64 / (1u64 << 53) as f64
+                }
+            }),
+        })
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value, right_span: Span, span: Span) -> Flow<Value> {
+    let mismatch = |left: &Value, right: &Value, verb: &str| -> Control {
+        Error::runtime(
+            format!(
+                "can't {verb} a {} and a {}",
+                left.type_name(),
+                right.type_name()
+            ),
+            span,
+        )
+        .into()
+    };
+
+    Ok(match (op, &left, &right) {
+        (BinaryOp::Equal, a, b) => Value::Bool(a.equals(b)),
+        (BinaryOp::NotEqual, a, b) => Value::Bool(!a.equals(b)),
+        (BinaryOp::Add, Value::Number(a), Value::Number(b)) => Value::Number(a + b),
+        (BinaryOp::Add, Value::String(_), _) | (BinaryOp::Add, _, Value::String(_)) => {
+            Value::String(format!("{left}{right}").into())
+        }
+        (BinaryOp::Subtract, Value::Number(a), Value::Number(b)) => Value::Number(a - b),
+        (BinaryOp::Multiply, Value::Number(a), Value::Number(b)) => Value::Number(a * b),
+        (BinaryOp::Multiply, Value::String(a), Value::Number(b))
+        | (BinaryOp::Multiply, Value::Number(b), Value::String(a)) => {
+            let count = b.max(0.0) as usize;
+
+            match a.len().checked_mul(count) {
+                Some(length) if length <= MAX_STRING_LENGTH => {
+                    Value::String(a.repeat(count).into())
+                }
+                _ => return Err(Error::runtime("string repeat count too large", span).into()),
+            }
+        }
+        (BinaryOp::Divide | BinaryOp::Remainder, Value::Number(_), Value::Number(b))
+            if *b == 0.0 =>
+        {
+            return Err(Error::runtime("division by zero", right_span).into())
+        }
+        (BinaryOp::Divide, Value::Number(a), Value::Number(b)) => Value::Number(a / b),
+        (BinaryOp::Remainder, Value::Number(a), Value::Number(b)) => Value::Number(a % b),
+        (BinaryOp::Less, Value::Number(a), Value::Number(b)) => Value::Bool(a < b),
+        (BinaryOp::Greater, Value::Number(a), Value::Number(b)) => Value::Bool(a > b),
+        (BinaryOp::LessEqual, Value::Number(a), Value::Number(b)) => Value::Bool(a <= b),
+        (BinaryOp::GreaterEqual, Value::Number(a), Value::Number(b)) => Value::Bool(a >= b),
+        (BinaryOp::Less, Value::String(a), Value::String(b)) => Value::Bool(a < b),
+        (BinaryOp::Greater, Value::String(a), Value::String(b)) => Value::Bool(a > b),
+        (BinaryOp::LessEqual, Value::String(a), Value::String(b)) => Value::Bool(a <= b),
+        (BinaryOp::GreaterEqual, Value::String(a), Value::String(b)) => Value::Bool(a >= b),
+        (BinaryOp::Add, a, b) => return Err(mismatch(a, b, "add")),
+        (BinaryOp::Subtract, a, b) => return Err(mismatch(a, b, "subtract")),
+        (BinaryOp::Multiply, a, b) => return Err(mismatch(a, b, "multiply")),
+        (BinaryOp::Divide | BinaryOp::Remainder, a, b) => return Err(mismatch(a, b, "divide")),
+        (_, a, b) => return Err(mismatch(a, b, "compare")),
+    })
+}
@@ -0,0 +1,257 @@
+use crate::error::Error;
+use crate::token::{Span, Token, TokenKind, KEYWORDS};
+
+const OPERATORS: &[(&str, TokenKind)] = &[
+    ("!==", TokenKind::NotEqual),
+    ("!=", TokenKind::NotEqual),
+    ("==", TokenKind::Equal),
+    ("&&", TokenKind::And),
+    ("||", TokenKind::Or),
+    ("<=", TokenKind::LessEqual),
+    (">=", TokenKind::GreaterEqual),
+    ("++", TokenKind::PlusPlus),
+    ("--", TokenKind::MinusMinus),
+    ("+=", TokenKind::PlusAssign),
+    ("-=", TokenKind::MinusAssign),
+    ("*=", TokenKind::StarAssign),
+    ("/=", TokenKind::SlashAssign),
+    ("->", TokenKind::Arrow),
+    ("!", TokenKind::Bang),
+    ("=", TokenKind::Assign),
+    ("|", TokenKind::Or),
+    ("<", TokenKind::Less),
+    (">", TokenKind::Greater),
+    ("+", TokenKind::Plus),
+    ("-", TokenKind::Minus),
+    ("*", TokenKind::Star),
+    ("/", TokenKind::Slash),
+    ("%", TokenKind::Percent),
+    ("(", TokenKind::LeftParen),
+    (")", TokenKind::RightParen),
+    ("{", TokenKind::LeftBrace),
+    ("}", TokenKind::RightBrace),
+    (",", TokenKind::Comma),
+    (";", TokenKind::Semicolon),
+    (".", TokenKind::Dot),
+];
+
+pub struct Lexer<'a> {
+    source: &'a str,
+
+    position: usize,
+
+    line: usize,
+
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    pub fn tokenize(mut self) -> Result<Vec<Token>, Error> {
+        let mut tokens = Vec::new();
+
+        loop {
+            let token = self.next_token()?;
+            let done = token.kind == TokenKind::Eof;
+
+            tokens.push(token);
+
+            if done {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+
+        self.position += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn span_from(&self, start: Span) -> Span {
+        Span {
+            end: self.position,
+            ..start
+        }
+    }
+
+    fn here(&self) -> Span {
+        Span {
+            start: self.position,
+            end: self.position,
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), Error> {
+        loop {
+            if self.peek().is_some_and(char::is_whitespace) {
+                self.advance();
+            } else if self.rest().starts_with("//") {
+                while self.peek().is_some_and(|x| x != '\n') {
+                    self.advance();
+                }
+            } else if self.rest().starts_with("/*") {
+                let start = self.here();
+
+                self.advance();
+                self.advance();
+
+                while !self.rest().starts_with("*/") {
+                    if self.advance().is_none() {
+                        return Err(Error::syntax(
+                            "unterminated block comment",
+                            self.span_from(start),
+                        ));
+                    }
+                }
+
+                self.advance();
+                self.advance();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token, Error> {
+        self.skip_trivia()?;
+
+        let start = self.here();
+
+        let Some(c) = self.peek() else {
+            return Ok(Token {
+                kind: TokenKind::Eof,
+                span: start,
+            });
+        };
+
+        let kind = if c.is_ascii_digit() {
+            self.number()
+        } else if c == '"' || c == '\'' {
+            self.string(c, start)?
+        } else if c.is_alphabetic() || c == '_' {
+            self.identifier()
+        } else if let Some((text, kind)) =
+            OPERATORS.iter().find(|(x, _)| self.rest().starts_with(x))
+        {
+            for _ in 0..text.len() {
+                self.advance();
+            }
+
+            kind.clone()
+        } else {
+            self.advance();
+
+            return Err(Error::syntax(
+                format!("unexpected character `{c}`"),
+                self.span_from(start),
+            ));
+        };
+
+        Ok(Token {
+            kind,
+            span: self.span_from(start),
+        })
+    }
+
+    fn number(&mut self) -> TokenKind {
+        let start = self.position;
+
+        while self.peek().is_some_and(|x| x.is_ascii_digit()) {
+            self.advance();
+        }
+
+        let mut chars = self.rest().chars();
+
+        if chars.next() == Some('.') && chars.next().is_some_and(|x| x.is_ascii_digit()) {
+            self.advance();
+
+            while self.peek().is_some_and(|x| x.is_ascii_digit()) {
+                self.advance();
+            }
+        }
+
+        TokenKind::Number(self.source[start..self.position].parse().unwrap())
+    }
+
+    fn string(&mut self, quote: char, start: Span) -> Result<TokenKind, Error> {
+        let mut string = String::new();
+
+        self.advance();
+
+        loop {
+            let escape_start = self.here();
+
+            match self.advance() {
+                None | Some('\n') => {
+                    return Err(Error::syntax("unterminated string", self.span_from(start)))
+                }
+                Some(x) if x == quote => return Ok(TokenKind::String(string)),
+                Some('\\') => match self.advance() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some('0') => string.push('\0'),
+                    Some(x @ ('\\' | '"' | '\'')) => string.push(x),
+                    Some(x) => {
+                        return Err(Error::syntax(
+                            format!("unknown escape sequence `\\{x}`"),
+                            self.span_from(escape_start),
+                        ))
+                    }
+                    None => {
+                        return Err(Error::syntax("unterminated string", self.span_from(start)))
+                    }
+                },
+                Some(x) => string.push(x),
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> TokenKind {
+        let start = self.position;
+
+        while self.peek().is_some_and(|x| x.is_alphanumeric() || x == '_') {
+            self.advance();
+        }
+
+        let text = &self.source[start..self.position];
+
+        KEYWORDS
+            .iter()
+            .find(|(x, _)| *x == text)
+            .map(|(_, kind)| kind.clone())
+            .unwrap_or_else(|| TokenKind::Identifier(text.to_string()))
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    Lexer::new(source).tokenize()
+}
@@ -0,0 +1,24 @@
+pub mod ast;
+pub mod environment;
+pub mod error;
+pub mod interpreter;
+pub mod lexer;
+pub mod parser;
+pub mod token;
+pub mod value;
+
+pub use error::{Error, ErrorKind};
+pub use interpreter::Interpreter;
+pub use lexer::tokenize;
+pub use parser::parse;
+pub use value::Value;
+
+pub fn run(source: &str, interpreter: &mut Interpreter) -> Result<Value, Error> {
+    let program = parse(source)?;
+
+    let value = interpreter.execute(&program)?;
+
+    interpreter.run_event_loop()?;
+
+    Ok(value)
+}
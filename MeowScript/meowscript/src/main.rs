@@ -0,0 +1,134 @@
+use meowscript::token::TokenKind;
+use meowscript::{Interpreter, Value};
+use std::io::Write;
+use std::process::ExitCode;
+
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+fn exit_code(code: i32) -> ExitCode {
+    ExitCode::from(code.clamp(0, 255) as u8)
+}
+
+fn run_file(path: &str) -> ExitCode {
+    let source = match std::fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("meowscript: can't read {path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut interpreter = Interpreter::new();
+
+    if let Err(e) = meowscript::run(&source, &mut interpreter) {
+        eprintln!("{path}:{e}");
+        return ExitCode::FAILURE;
+    }
+
+    exit_code(interpreter.exit_code().unwrap_or(0))
+}
+
+fn is_complete(source: &str) -> bool {
+    match meowscript::tokenize(source) {
+        Ok(tokens) => {
+            let mut depth = 0i32;
+
+            for token in tokens {
+                match token.kind {
+                    TokenKind::LeftBrace | TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightBrace | TokenKind::RightParen => depth -= 1,
+                    _ => (),
+                }
+            }
+
+            depth <= 0
+        }
+        Err(e) => !e.message.starts_with("unterminated"),
+    }
+}
+
+fn repl() -> ExitCode {
+    let mut interpreter = Interpreter::new();
+
+    println!(
+        "MeowScript {} (exit() or Ctrl+D to leave)",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let mut source = String::new();
+
+    loop {
+        print!(
+            "{}",
+            if source.is_empty() {
+                "=^.^= "
+            } else {
+                "  ... "
+            }
+        );
+        std::io::stdout().flush().ok();
+
+        let line = match interpreter.read_line() {
+            Ok(Some(x)) => x,
+            Ok(None) => {
+                println!();
+                break;
+            }
+            Err(e) => {
+                eprintln!("meowscript: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+
+        source.push_str(&line);
+        source.push('\n');
+
+        if !is_complete(&source) {
+            continue;
+        }
+
+        match meowscript::run(&source, &mut interpreter) {
+            Ok(Value::Null) => (),
+            Ok(value) => println!("{value}"),
+            Err(e) => eprintln!("{e}"),
+        }
+
+        source.clear();
+
+        if let Some(code) = interpreter.exit_code() {
+            return exit_code(code);
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(start)
+        .and_then(|x| {
+            x.join()
+                .map_err(|_| std::io::Error::other("interpreter panicked"))
+        })
+        .unwrap_or(ExitCode::FAILURE)
+}
+
+fn start() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        [] => repl(),
+        [flag] if flag == "-h" || flag == "--help" => {
+            println!("Usage: meowscript [FILE]");
+            println!();
+            println!("Runs FILE, or starts an interactive session when no file is given.");
+            ExitCode::SUCCESS
+        }
+        [path] => run_file(path),
+        _ => {
+            eprintln!("Usage: meowscript [FILE]");
+            ExitCode::FAILURE
+        }
+    }
+}
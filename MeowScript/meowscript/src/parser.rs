@@ -0,0 +1,592 @@
+use crate::ast::*;
+use crate::error::Error;
+use crate::lexer::tokenize;
+use crate::token::{Span, Token, TokenKind};
+use std::rc::Rc;
+
+pub struct Parser {
+    tokens: Vec<Token>,
+
+    position: usize,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            position: 0,
+        }
+    }
+
+    pub fn parse_program(mut self) -> Result<Program, Error> {
+        let mut program = Vec::new();
+
+        while !self.check(&TokenKind::Eof) {
+            program.push(self.statement()?);
+        }
+
+        Ok(program)
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position.min(self.tokens.len() - 1)]
+    }
+
+    fn peek_next(&self) -> &TokenKind {
+        &self.tokens[(self.position + 1).min(self.tokens.len() - 1)].kind
+    }
+
+    fn previous_span(&self) -> Span {
+        self.tokens[self.position.saturating_sub(1)].span
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+
+        if token.kind != TokenKind::Eof {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn check(&self, kind: &TokenKind) -> bool {
+        &self.peek().kind == kind
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if self.check(kind) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, kind: &TokenKind, context: &str) -> Result<Token, Error> {
+        if self.check(kind) {
+            Ok(self.advance())
+        } else {
+            Err(self.unexpected(&format!("`{}` {context}", kind.text())))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        let token = self.peek();
+
+        Error::syntax(
+            format!("expected {expected}, found {}", token.kind.describe()),
+            token.span,
+        )
+    }
+
+    fn identifier(&mut self, context: &str) -> Result<Identifier, Error> {
+        match self.peek().kind.clone() {
+            TokenKind::Identifier(name) => Ok(Identifier {
+                name,
+                span: self.advance().span,
+            }),
+            _ => Err(self.unexpected(&format!("a name {context}"))),
+        }
+    }
+
+    fn end_statement(&mut self) -> Result<(), Error> {
+        if self.eat(&TokenKind::Semicolon) {
+            return Ok(());
+        }
+
+        if self.check(&TokenKind::RightBrace) || self.check(&TokenKind::Eof) {
+            return Ok(());
+        }
+
+        let previous = self.previous_span();
+
+        if self.peek().span.line > previous.line {
+            return Ok(());
+        }
+
+        Err(self.unexpected("`;` after statement"))
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
+        self.expect(&TokenKind::LeftBrace, "to start a block")?;
+
+        let mut statements = Vec::new();
+
+        while !self.check(&TokenKind::RightBrace) {
+            if self.check(&TokenKind::Eof) {
+                return Err(self.unexpected("`}` to close the block"));
+            }
+
+            statements.push(self.statement()?);
+        }
+
+        self.advance();
+
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, Error> {
+        let start = self.peek().span;
+
+        let kind = match self.peek().kind {
+            TokenKind::Scratch | TokenKind::Purr => {
+                let kind = self.declaration()?;
+                self.end_statement()?;
+                kind
+            }
+            TokenKind::Pawction if matches!(self.peek_next(), TokenKind::Identifier(_)) => {
+                StmtKind::Function(self.function()?)
+            }
+            TokenKind::Purrhaps => self.if_statement()?,
+            TokenKind::For => self.for_statement()?,
+            TokenKind::Try => self.try_statement()?,
+            TokenKind::Return => {
+                self.advance();
+
+                let value = if self.check(&TokenKind::Semicolon)
+                    || self.check(&TokenKind::RightBrace)
+                    || self.peek().span.line > start.line
+                {
+                    None
+                } else {
+                    Some(self.expression()?)
+                };
+
+                self.end_statement()?;
+                StmtKind::Return(value)
+            }
+            TokenKind::Break => {
+                self.advance();
+                self.end_statement()?;
+                StmtKind::Break
+            }
+            TokenKind::Continue => {
+                self.advance();
+                self.end_statement()?;
+                StmtKind::Continue
+            }
+            TokenKind::LeftBrace => StmtKind::Block(self.block()?),
+            TokenKind::Semicolon => {
+                self.advance();
+                StmtKind::Block(Vec::new())
+            }
+            _ => {
+                let expr = self.expression()?;
+                self.end_statement()?;
+                StmtKind::Expression(expr)
+            }
+        };
+
+        Ok(Stmt {
+            kind,
+            span: start.to(self.previous_span()),
+        })
+    }
+
+    fn declaration(&mut self) -> Result<StmtKind, Error> {
+        let constant = self.advance().kind == TokenKind::Purr;
+
+        let keyword = if constant { "purr" } else { "scratch" };
+
+        let name = self.identifier(&format!("after `{keyword}`"))?;
+
+        let value = if self.eat(&TokenKind::Assign) {
+            Some(self.expression()?)
+        } else if constant {
+            return Err(self.unexpected("`=` because `purr` bindings need a value"));
+        } else {
+            None
+        };
+
+        Ok(StmtKind::Declaration {
+            constant,
+            name,
+            value,
+        })
+    }
+
+    fn function(&mut self) -> Result<Rc<Function>, Error> {
+        let start = self.advance().span;
+
+        let name = match self.peek().kind {
+            TokenKind::Identifier(_) => Some(self.identifier("")?),
+            _ => None,
+        };
+
+        self.expect(&TokenKind::LeftParen, "before the parameters")?;
+
+        let mut params = Vec::new();
+
+        while !self.check(&TokenKind::RightParen) {
+            params.push(self.identifier("for a parameter")?);
+
+            if !self.eat(&TokenKind::Comma) {
+                break;
+            }
+        }
+
+        self.expect(&TokenKind::RightParen, "after the parameters")?;
+
+        // `-> { ... }` takes a block like the plain form, `-> value` returns a single expression
+        let body = if self.check(&TokenKind::Arrow) && *self.peek_next() == TokenKind::LeftBrace {
+            self.advance();
+            self.block()?
+        } else if self.check(&TokenKind::Arrow) {
+            let arrow = self.advance().span;
+            let value = self.expression()?;
+            let span = arrow.to(value.span);
+
+            if name.is_some() {
+                self.end_statement()?;
+            }
+
+            vec![Stmt {
+                kind: StmtKind::Return(Some(value)),
+                span,
+            }]
+        } else {
+            self.block()?
+        };
+
+        Ok(Rc::new(Function {
+            name,
+            params,
+            body,
+            span: start.to(self.previous_span()),
+        }))
+    }
+
+    fn if_statement(&mut self) -> Result<StmtKind, Error> {
+        self.advance();
+
+        let condition = self.expression()?;
+        let then = self.block()?;
+
+        let otherwise = if self.eat(&TokenKind::Meowctually) {
+            if self.check(&TokenKind::Purrhaps) {
+                let start = self.peek().span;
+                let kind = self.if_statement()?;
+
+                Some(vec![Stmt {
+                    kind,
+                    span: start.to(self.previous_span()),
+                }])
+            } else {
+                Some(self.block()?)
+            }
+        } else {
+            None
+        };
+
+        Ok(StmtKind::If {
+            condition,
+            then,
+            otherwise,
+        })
+    }
+
+    fn for_statement(&mut self) -> Result<StmtKind, Error> {
+        self.advance();
+        self.expect(&TokenKind::LeftParen, "after `for`")?;
+
+        let init_start = self.peek().span;
+
+        let init = if self.check(&TokenKind::Semicolon) {
+            None
+        } else if self.check(&TokenKind::Scratch) || self.check(&TokenKind::Purr) {
+            let kind = self.declaration()?;
+
+            Some(Stmt {
+                kind,
+                span: init_start.to(self.previous_span()),
+            })
+        } else {
+            let expr = self.expression()?;
+
+            if self.eat(&TokenKind::RightParen) {
+                return Ok(StmtKind::For {
+                    init: None,
+                    condition: Some(expr),
+                    update: None,
+                    body: self.block()?,
+                });
+            }
+
+            Some(Stmt {
+                span: expr.span,
+                kind: StmtKind::Expression(expr),
+            })
+        };
+
+        self.expect(&TokenKind::Semicolon, "after the loop initializer")?;
+
+        let condition = if self.check(&TokenKind::Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.expect(&TokenKind::Semicolon, "after the loop condition")?;
+
+        let update = if self.check(&TokenKind::RightParen) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.expect(&TokenKind::RightParen, "after the loop header")?;
+
+        Ok(StmtKind::For {
+            init: init.map(Box::new),
+            condition,
+            update,
+            body: self.block()?,
+        })
+    }
+
+    fn try_statement(&mut self) -> Result<StmtKind, Error> {
+        self.advance();
+
+        let body = self.block()?;
+
+        self.expect(&TokenKind::Catch, "after the `try` block")?;
+
+        let binding = if self.eat(&TokenKind::LeftParen) {
+            let binding = self.identifier("for the caught error")?;
+            self.expect(&TokenKind::RightParen, "after the caught error")?;
+            Some(binding)
+        } else {
+            None
+        };
+
+        Ok(StmtKind::Try {
+            body,
+            binding,
+            handler: self.block()?,
+        })
+    }
+
+    pub fn expression(&mut self) -> Result<Expr, Error> {
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Result<Expr, Error> {
+        let target = self.binary(0)?;
+
+        let op = match self.peek().kind {
+            TokenKind::Assign => AssignOp::Set,
+            TokenKind::PlusAssign => AssignOp::Add,
+            TokenKind::MinusAssign => AssignOp::Subtract,
+            TokenKind::StarAssign => AssignOp::Multiply,
+            TokenKind::SlashAssign => AssignOp::Divide,
+            _ => return Ok(target),
+        };
+
+        let operator = self.advance();
+
+        let ExprKind::Variable(name) = target.kind else {
+            return Err(Error::syntax(
+                format!(
+                    "can't assign to this, `{}` needs a name on its left",
+                    operator.kind.text()
+                ),
+                target.span,
+            ));
+        };
+
+        let value = self.assignment()?;
+
+        Ok(Expr {
+            span: target.span.to(value.span),
+            kind: ExprKind::Assign(op, name, Box::new(value)),
+        })
+    }
+
+    fn binary_operator(&self) -> Option<(BinaryOp, usize)> {
+        Some(match self.peek().kind {
+            TokenKind::Or => (BinaryOp::Or, 0),
+            TokenKind::And => (BinaryOp::And, 1),
+            TokenKind::Equal => (BinaryOp::Equal, 2),
+            TokenKind::NotEqual => (BinaryOp::NotEqual, 2),
+            TokenKind::Less => (BinaryOp::Less, 3),
+            TokenKind::Greater => (BinaryOp::Greater, 3),
+            TokenKind::LessEqual => (BinaryOp::LessEqual, 3),
+            TokenKind::GreaterEqual => (BinaryOp::GreaterEqual, 3),
+            TokenKind::Plus => (BinaryOp::Add, 4),
+            TokenKind::Minus => (BinaryOp::Subtract, 4),
+            TokenKind::Star => (BinaryOp::Multiply, 5),
+            TokenKind::Slash => (BinaryOp::Divide, 5),
+            TokenKind::Percent => (BinaryOp::Remainder, 5),
+            _ => return None,
+        })
+    }
+
+    fn binary(&mut self, min_precedence: usize) -> Result<Expr, Error> {
+        let mut left = self.unary()?;
+
+        while let Some((op, precedence)) = self.binary_operator() {
+            if precedence < min_precedence {
+                break;
+            }
+
+            self.advance();
+
+            let right = self.binary(precedence + 1)?;
+
+            left = Expr {
+                span: left.span.to(right.span),
+                kind: ExprKind::Binary(op, Box::new(left), Box::new(right)),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        let start = self.peek().span;
+
+        let op = match self.peek().kind {
+            TokenKind::Bang => UnaryOp::Not,
+            TokenKind::Minus => UnaryOp::Negate,
+            TokenKind::PlusPlus | TokenKind::MinusMinus => {
+                let op = if self.advance().kind == TokenKind::PlusPlus {
+                    UpdateOp::Increment
+                } else {
+                    UpdateOp::Decrement
+                };
+
+                let target = self.identifier("after the increment operator")?;
+
+                return Ok(Expr {
+                    span: start.to(target.span),
+                    kind: ExprKind::Update {
+                        op,
+                        prefix: true,
+                        target,
+                    },
+                });
+            }
+            _ => return self.postfix(),
+        };
+
+        self.advance();
+
+        let operand = self.unary()?;
+
+        Ok(Expr {
+            span: start.to(operand.span),
+            kind: ExprKind::Unary(op, Box::new(operand)),
+        })
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.primary()?;
+
+        loop {
+            match self.peek().kind {
+                TokenKind::LeftParen => {
+                    self.advance();
+
+                    let mut args = Vec::new();
+
+                    while !self.check(&TokenKind::RightParen) {
+                        args.push(self.expression()?);
+
+                        if !self.eat(&TokenKind::Comma) {
+                            break;
+                        }
+                    }
+
+                    self.expect(&TokenKind::RightParen, "after the arguments")?;
+
+                    expr = Expr {
+                        span: expr.span.to(self.previous_span()),
+                        kind: ExprKind::Call(Box::new(expr), args),
+                    };
+                }
+                TokenKind::Dot => {
+                    self.advance();
+
+                    let member = self.identifier("after `.`")?;
+
+                    expr = Expr {
+                        span: expr.span.to(member.span),
+                        kind: ExprKind::Member(Box::new(expr), member),
+                    };
+                }
+                TokenKind::PlusPlus | TokenKind::MinusMinus => {
+                    let ExprKind::Variable(target) = &expr.kind else {
+                        break;
+                    };
+
+                    let operator = self.advance();
+
+                    expr = Expr {
+                        span: expr.span.to(operator.span),
+                        kind: ExprKind::Update {
+                            op: if operator.kind == TokenKind::PlusPlus {
+                                UpdateOp::Increment
+                            } else {
+                                UpdateOp::Decrement
+                            },
+                            prefix: false,
+                            target: target.clone(),
+                        },
+                    };
+                }
+                _ => return Ok(expr),
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, Error> {
+        let token = self.peek().clone();
+
+        let kind = match token.kind {
+            TokenKind::Number(x) => ExprKind::Number(x),
+            TokenKind::String(x) => ExprKind::String(x),
+            TokenKind::True => ExprKind::Bool(true),
+            TokenKind::False => ExprKind::Bool(false),
+            TokenKind::Null => ExprKind::Null,
+            TokenKind::Identifier(name) => ExprKind::Variable(Identifier {
+                name,
+                span: token.span,
+            }),
+            TokenKind::Pawction => {
+                let function = self.function()?;
+
+                return Ok(Expr {
+                    span: function.span,
+                    kind: ExprKind::Function(function),
+                });
+            }
+            TokenKind::LeftParen => {
+                self.advance();
+
+                let inner = self.expression()?;
+
+                self.expect(&TokenKind::RightParen, "to close the parenthesis")?;
+
+                return Ok(Expr {
+                    span: token.span.to(self.previous_span()),
+                    kind: inner.kind,
+                });
+            }
+            _ => return Err(self.unexpected("an expression")),
+        };
+
+        self.advance();
+
+        Ok(Expr {
+            kind,
+            span: token.span,
+        })
+    }
+}
+
+pub fn parse(source: &str) -> Result<Program, Error> {
+    Parser::new(tokenize(source)?).parse_program()
+}
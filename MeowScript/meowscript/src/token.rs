@@ -0,0 +1,151 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+
+    pub end: usize,
+
+    pub line: usize,
+
+    pub column: usize,
+}
+
+impl Span {
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end.max(self.end),
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Number(f64),
+    String(String),
+    Identifier(String),
+
+    Scratch,
+    Purr,
+    Pawction,
+    Purrhaps,
+    Meowctually,
+    For,
+    Try,
+    Catch,
+    Return,
+    Break,
+    Continue,
+    True,
+    False,
+    Null,
+
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Semicolon,
+    Dot,
+
+    Bang,
+    Assign,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    PlusPlus,
+    MinusMinus,
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    Arrow,
+
+    Eof,
+}
+
+pub const KEYWORDS: &[(&str, TokenKind)] = &[
+    ("scratch", TokenKind::Scratch),
+    ("purr", TokenKind::Purr),
+    ("pawction", TokenKind::Pawction),
+    ("purrhaps", TokenKind::Purrhaps),
+    ("meowctually", TokenKind::Meowctually),
+    ("for", TokenKind::For),
+    ("try", TokenKind::Try),
+    ("catch", TokenKind::Catch),
+    ("return", TokenKind::Return),
+    ("break", TokenKind::Break),
+    ("continue", TokenKind::Continue),
+    ("true", TokenKind::True),
+    ("false", TokenKind::False),
+    ("null", TokenKind::Null),
+];
+
+impl TokenKind {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Number(x) => format!("number `{x}`"),
+            Self::String(_) => "string".to_string(),
+            Self::Identifier(x) => format!("`{x}`"),
+            Self::Eof => "end of file".to_string(),
+            x => format!("`{}`", x.text()),
+        }
+    }
+
+    pub fn text(&self) -> &'static str {
+        if let Some((text, _)) = KEYWORDS.iter().find(|(_, kind)| kind == self) {
+            return text;
+        }
+
+        match self {
+            Self::LeftParen => "(",
+            Self::RightParen => ")",
+            Self::LeftBrace => "{",
+            Self::RightBrace => "}",
+            Self::Comma => ",",
+            Self::Semicolon => ";",
+            Self::Dot => ".",
+            Self::Bang => "!",
+            Self::Assign => "=",
+            Self::Equal => "==",
+            Self::NotEqual => "!==",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::Less => "<",
+            Self::Greater => ">",
+            Self::LessEqual => "<=",
+            Self::GreaterEqual => ">=",
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Star => "*",
+            Self::Slash => "/",
+            Self::Percent => "%",
+            Self::PlusPlus => "++",
+            Self::MinusMinus => "--",
+            Self::PlusAssign => "+=",
+            Self::MinusAssign => "-=",
+            Self::StarAssign => "*=",
+            Self::SlashAssign => "/=",
+            Self::Arrow => "->",
+            _ => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+
+    pub span: Span,
+}
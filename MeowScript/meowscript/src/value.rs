@@ -0,0 +1,126 @@
+use crate::ast::Function;
+use crate::environment::Environment;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    Meow,
+    Reow,
+    Exec,
+    Exit,
+    SetTimeout,
+    SetInterval,
+    ClearTimeout,
+    Math(MathFunction),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathFunction {
+    Floor,
+    Ceil,
+    Round,
+    Abs,
+    Sqrt,
+    Pow,
+    Min,
+    Max,
+    Random,
+}
+
+pub const GLOBAL_BUILTINS: &[(&str, Builtin)] = &[
+    ("meow", Builtin::Meow),
+    ("reow", Builtin::Reow),
+    ("exec", Builtin::Exec),
+    ("exit", Builtin::Exit),
+    ("setTimeout", Builtin::SetTimeout),
+    ("setInterval", Builtin::SetInterval),
+    ("clearTimeout", Builtin::ClearTimeout),
+    ("clearInterval", Builtin::ClearTimeout),
+];
+
+pub const MATH_FUNCTIONS: &[(&str, MathFunction)] = &[
+    ("floor", MathFunction::Floor),
+    ("ceil", MathFunction::Ceil),
+    ("round", MathFunction::Round),
+    ("abs", MathFunction::Abs),
+    ("sqrt", MathFunction::Sqrt),
+    ("pow", MathFunction::Pow),
+    ("min", MathFunction::Min),
+    ("max", MathFunction::Max),
+    ("random", MathFunction::Random),
+];
+
+pub const MATH_CONSTANTS: &[(&str, f64)] =
+    &[("pi", std::f64::consts::PI), ("e", std::f64::consts::E)];
+
+#[derive(Clone)]
+pub struct Closure {
+    pub function: Rc<Function>,
+
+    pub environment: Environment,
+}
+
+#[derive(Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Rc<str>),
+    Function(Rc<Closure>),
+    Builtin(Builtin),
+    Math,
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "boolean",
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+            Self::Function(_) | Self::Builtin(_) => "pawction",
+            Self::Math => "math",
+        }
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Null => false,
+            Self::Bool(x) => *x,
+            Self::Number(x) => *x != 0.0 && !x.is_nan(),
+            Self::String(x) => !x.is_empty(),
+            _ => true,
+        }
+    }
+
+    pub fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Self::Null, Self::Null) => true,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Number(a), Self::Number(b)) => a == b,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Function(a), Self::Function(b)) => Rc::ptr_eq(a, b),
+            (Self::Builtin(a), Self::Builtin(b)) => a == b,
+            (Self::Math, Self::Math) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(x) => write!(f, "{x}"),
+            Self::Number(x) if x.fract() == 0.0 && x.abs() < 1e15 => write!(f, "{}", *x as i64),
+            Self::Number(x) => write!(f, "{x}"),
+            Self::String(x) => write!(f, "{x}"),
+            Self::Function(x) => match &x.function.name {
+                Some(name) => write!(f, "<pawction {}>", name.name),
+                None => write!(f, "<pawction>"),
+            },
+            Self::Builtin(_) => write!(f, "<builtin pawction>"),
+            Self::Math => write!(f, "<math>"),
+        }
+    }
+}
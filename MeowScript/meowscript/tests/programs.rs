@@ -0,0 +1,191 @@
+use meowscript::Interpreter;
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn run(source: &str, input: &str) -> (Result<(), meowscript::Error>, String, Option<i32>) {
+    let output = Output::default();
+
+    let mut interpreter = Interpreter::with_io(
+        Box::new(std::io::Cursor::new(input.to_string())),
+        Box::new(output.clone()),
+    );
+
+    let result = meowscript::run(source, &mut interpreter).map(|_| ());
+
+    let text = String::from_utf8(output.0.borrow().clone()).unwrap();
+
+    (result, text, interpreter.exit_code())
+}
+
+fn run_program(name: &str) -> (String, Option<i32>) {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/programs");
+
+    let source = std::fs::read_to_string(directory.join(format!("{name}.meow"))).unwrap();
+    let expected = std::fs::read_to_string(directory.join(format!("{name}.out"))).unwrap();
+    let input = std::fs::read_to_string(directory.join(format!("{name}.in"))).unwrap_or_default();
+
+    let (result, output, exit_code) = run(&source, &input);
+
+    if let Err(e) = result {
+        panic!("{name}.meow:{e}");
+    }
+
+    assert_eq!(output, expected, "output of {name}.meow");
+
+    (output, exit_code)
+}
+
+fn error_of(source: &str) -> String {
+    match run(source, "").0 {
+        Ok(()) => panic!("expected an error from {source:?}"),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn variables() {
+    run_program("variables");
+}
+
+#[test]
+fn control_flow() {
+    run_program("control_flow");
+}
+
+#[test]
+fn functions() {
+    run_program("functions");
+}
+
+#[test]
+fn math() {
+    run_program("math");
+}
+
+#[test]
+fn errors() {
+    run_program("errors");
+}
+
+#[test]
+fn timers() {
+    run_program("timers");
+}
+
+#[test]
+fn input() {
+    run_program("input");
+}
+
+#[test]
+fn exit() {
+    assert_eq!(run_program("exit").1, Some(3));
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(
+        error_of("scratch = 5;"),
+        "1:9: syntax error: expected a name after `scratch`, found `=`"
+    );
+    assert_eq!(
+        error_of("meow(\"unclosed);"),
+        "1:6: syntax error: unterminated string"
+    );
+    assert_eq!(
+        error_of("purrhaps true {\n    meow(1);\n"),
+        "3:1: syntax error: expected `}` to close the block, found end of file"
+    );
+    assert_eq!(
+        error_of("purr x;"),
+        "1:7: syntax error: expected `=` because `purr` bindings need a value, found `;`"
+    );
+    assert_eq!(
+        error_of("scratch a = 1 @ 2;"),
+        "1:15: syntax error: unexpected character `@`"
+    );
+    assert_eq!(
+        error_of("scratch x = 1;\n\n5 = x;"),
+        "3:1: syntax error: can't assign to this, `=` needs a name on its left"
+    );
+}
+
+#[test]
+fn runtime_errors() {
+    assert_eq!(
+        error_of("meow(1);\nmeow(missing);"),
+        "2:6: runtime error: `missing` is not defined, declare it with `scratch` or `purr`"
+    );
+    assert_eq!(
+        error_of("scratch a = 1;\nscratch a = 2;"),
+        "2:9: runtime error: `a` is already declared in this scope"
+    );
+    assert_eq!(
+        error_of("pawction f(a, b) {}\nf(1);"),
+        "2:1: runtime error: pawction `f` takes 2 arguments but 1 was given"
+    );
+    assert_eq!(
+        error_of("scratch n = 5;\nn();"),
+        "2:1: runtime error: a number is not a pawction"
+    );
+    assert_eq!(
+        error_of("break;"),
+        "1:1: runtime error: `break` outside of a loop"
+    );
+    assert_eq!(
+        error_of("meow(math.tau);"),
+        "1:11: runtime error: math has no member `tau`"
+    );
+}
+
+#[test]
+fn string_repeat_counts() {
+    assert_eq!(
+        error_of("meow(\"ab\" * 100000000000000000000);"),
+        "1:6: runtime error: string repeat count too large"
+    );
+
+    let (result, output, _) = run("meow(\"ab\" * -3);\nmeow(3 * \"ab\");", "");
+
+    assert!(result.is_ok());
+    assert_eq!(output, "\nababab\n");
+}
+
+#[test]
+fn runaway_recursion() {
+    let error = std::thread::Builder::new()
+        .stack_size(256 * 1024 * 1024)
+        .spawn(|| error_of("pawction forever(n) -> forever(n + 1)\nforever(0);"))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(error, "1:24: runtime error: too much recursion");
+}
+
+#[test]
+fn output_before_an_error_is_kept() {
+    let (result, output, _) = run("meow(\"first\");\nmeow(1 - \"x\");", "");
+
+    assert_eq!(output, "first\n");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "2:6: runtime error: can't subtract a number and a string"
+    );
+}
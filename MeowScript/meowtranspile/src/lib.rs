@@ -0,0 +1,132 @@
+pub mod sourcemap;
+
+use meowscript::token::TokenKind;
+use meowscript::Error;
+pub use sourcemap::{Mapping, SourceMap};
+
+pub const SYNTAX_MAP: &[(&str, &str)] = &[
+    ("scratch", "let"),
+    ("purr", "const"),
+    ("meow", "println"),
+    ("purrhaps", "if"),
+    ("meowctually", "else"),
+    ("!==", "!="),
+    ("pawction", "fn"),
+    ("reow", "input"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ToReplacement,
+    ToMeowScript,
+}
+
+impl Direction {
+    fn replace(self, text: &str) -> Option<&'static str> {
+        SYNTAX_MAP
+            .iter()
+            .find_map(|(meow, replacement)| match self {
+                Self::ToReplacement => (*meow == text).then_some(*replacement),
+                Self::ToMeowScript => (*replacement == text).then_some(*meow),
+            })
+    }
+
+    fn is_reserved_in_output(self, text: &str) -> bool {
+        SYNTAX_MAP.iter().any(|(meow, replacement)| match self {
+            Self::ToReplacement => *replacement == text,
+            Self::ToMeowScript => *meow == text,
+        })
+    }
+
+    fn target_name(self) -> &'static str {
+        match self {
+            Self::ToReplacement => "the replacement syntax",
+            Self::ToMeowScript => "MeowScript",
+        }
+    }
+}
+
+pub struct Output {
+    pub code: String,
+
+    pub source_map: SourceMap,
+}
+
+struct Writer {
+    code: String,
+
+    line: usize,
+
+    column: usize,
+}
+
+impl Writer {
+    fn push(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+
+        self.code.push_str(text);
+    }
+}
+
+fn is_word(text: &str) -> bool {
+    text.starts_with(|x: char| x.is_alphabetic() || x == '_')
+}
+
+pub fn transpile(source: &str, direction: Direction) -> Result<Output, Error> {
+    let tokens = meowscript::tokenize(source)?;
+
+    let mut writer = Writer {
+        code: String::with_capacity(source.len()),
+        line: 1,
+        column: 1,
+    };
+
+    let mut source_map = SourceMap::default();
+    let mut position = 0;
+
+    for token in tokens {
+        writer.push(&source[position..token.span.start]);
+        position = token.span.end;
+
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+
+        let text = &source[token.span.start..token.span.end];
+
+        let replacement = match &token.kind {
+            TokenKind::String(_) | TokenKind::Number(_) => None,
+            _ if is_word(text) && direction.is_reserved_in_output(text) => {
+                return Err(Error::syntax(
+                    format!(
+                        "`{text}` is reserved in {}, rename it before converting",
+                        direction.target_name()
+                    ),
+                    token.span,
+                ))
+            }
+            _ => direction.replace(text),
+        };
+
+        source_map.add(Mapping {
+            generated_line: writer.line,
+            generated_column: writer.column,
+            original_line: token.span.line,
+            original_column: token.span.column,
+        });
+
+        writer.push(replacement.unwrap_or(text));
+    }
+
+    Ok(Output {
+        code: writer.code,
+        source_map,
+    })
+}
@@ -0,0 +1,101 @@
+use meowtranspile::Direction;
+use std::io::{Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "Usage: meowtranspile [-r|--reverse] [-o|--output FILE] [-m|--source-map FILE] [INPUT]
+
+Converts MeowScript into the replacement syntax from syntax.md, or back with --reverse.
+Reads INPUT (or stdin) and writes to --output (or stdout).";
+
+struct Options {
+    direction: Direction,
+
+    input: Option<String>,
+
+    output: Option<String>,
+
+    source_map: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        direction: Direction::ToReplacement,
+        input: None,
+        output: None,
+        source_map: None,
+    };
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            "-r" | "--reverse" => options.direction = Direction::ToMeowScript,
+            "-o" | "--output" => {
+                options.output = Some(args.next().ok_or("--output needs a file name")?)
+            }
+            "-m" | "--source-map" => {
+                options.source_map = Some(args.next().ok_or("--source-map needs a file name")?)
+            }
+            x if x.starts_with('-') && x != "-" => return Err(format!("unknown option `{x}`")),
+            _ if options.input.is_some() => return Err("only one input file can be given".into()),
+            _ => options.input = Some(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let input_name = options.input.as_deref().unwrap_or("<stdin>");
+
+    let source = match options.input.as_deref() {
+        None | Some("-") => {
+            let mut source = String::new();
+
+            std::io::stdin()
+                .read_to_string(&mut source)
+                .map_err(|e| format!("can't read stdin: {e}"))?;
+
+            source
+        }
+        Some(path) => {
+            std::fs::read_to_string(path).map_err(|e| format!("can't read {path}: {e}"))?
+        }
+    };
+
+    let output = meowtranspile::transpile(&source, options.direction)
+        .map_err(|e| format!("{input_name}:{e}"))?;
+
+    match &options.output {
+        Some(path) => {
+            std::fs::write(path, &output.code).map_err(|e| format!("can't write {path}: {e}"))?
+        }
+        None => std::io::stdout()
+            .write_all(output.code.as_bytes())
+            .map_err(|e| format!("can't write output: {e}"))?,
+    }
+
+    if let Some(path) = &options.source_map {
+        let file = options.output.as_deref().unwrap_or("<stdout>");
+
+        std::fs::write(path, output.source_map.to_json(file, input_name))
+            .map_err(|e| format!("can't write {path}: {e}"))?;
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("meowtranspile: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
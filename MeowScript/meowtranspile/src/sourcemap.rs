@@ -0,0 +1,121 @@
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_line: usize,
+
+    pub generated_column: usize,
+
+    pub original_line: usize,
+
+    pub original_column: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    pub mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    pub fn add(&mut self, mapping: Mapping) {
+        self.mappings.push(mapping);
+    }
+
+    pub fn original_position(&self, line: usize, column: usize) -> Option<(usize, usize)> {
+        self.mappings
+            .iter()
+            .filter(|x| x.generated_line == line && x.generated_column <= column)
+            .max_by_key(|x| x.generated_column)
+            .map(|x| {
+                (
+                    x.original_line,
+                    x.original_column + (column - x.generated_column),
+                )
+            })
+    }
+
+    pub fn to_json(&self, file: &str, source: &str) -> String {
+        let mut mappings = String::new();
+
+        let mut line = 1;
+        let mut previous_generated_column = 0;
+        let mut previous_original_line = 0;
+        let mut previous_original_column = 0;
+
+        let mut sorted = self.mappings.clone();
+        sorted.sort_by_key(|x| (x.generated_line, x.generated_column));
+
+        for (i, mapping) in sorted.iter().enumerate() {
+            if mapping.generated_line != line {
+                for _ in line..mapping.generated_line {
+                    mappings.push(';');
+                }
+
+                line = mapping.generated_line;
+                previous_generated_column = 0;
+            } else if i > 0 {
+                mappings.push(',');
+            }
+
+            let generated_column = mapping.generated_column as i64 - 1;
+            let original_line = mapping.original_line as i64 - 1;
+            let original_column = mapping.original_column as i64 - 1;
+
+            encode_vlq(&mut mappings, generated_column - previous_generated_column);
+            encode_vlq(&mut mappings, 0);
+            encode_vlq(&mut mappings, original_line - previous_original_line);
+            encode_vlq(&mut mappings, original_column - previous_original_column);
+
+            previous_generated_column = generated_column;
+            previous_original_line = original_line;
+            previous_original_column = original_column;
+        }
+
+        format!(
+            "{{\"version\":3,\"file\":{},\"sources\":[{}],\"names\":[],\"mappings\":\"{mappings}\"}}",
+            json_string(file),
+            json_string(source),
+        )
+    }
+}
+
+fn encode_vlq(output: &mut String, value: i64) {
+    let mut value = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+
+    loop {
+        let mut digit = value & 0b11111;
+
+        value >>= 5;
+
+        if value > 0 {
+            digit |= 0b100000;
+        }
+
+        output.push(BASE64[digit as usize] as char);
+
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
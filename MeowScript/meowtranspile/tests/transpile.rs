@@ -0,0 +1,93 @@
+use meowtranspile::{transpile, Direction};
+
+const MEOWSCRIPT: &str = r#"// meow around the house
+scratch meowCount = 0;
+purr name = reow("name? ");
+
+pawction greet(who) {
+    purrhaps who !== "" {
+        meow("scratch purr meow", who);
+    } meowctually {
+        meow("no cat");
+    }
+}
+
+/* pawction purrhaps */
+greet(name);
+"#;
+
+const REPLACEMENT: &str = r#"// meow around the house
+let meowCount = 0;
+const name = input("name? ");
+
+fn greet(who) {
+    if who != "" {
+        println("scratch purr meow", who);
+    } else {
+        println("no cat");
+    }
+}
+
+/* pawction purrhaps */
+greet(name);
+"#;
+
+#[test]
+fn converts_tokens_but_not_strings_comments_or_identifiers() {
+    let output = transpile(MEOWSCRIPT, Direction::ToReplacement).unwrap();
+
+    assert_eq!(output.code, REPLACEMENT);
+}
+
+#[test]
+fn reverse_direction_round_trips() {
+    let output = transpile(REPLACEMENT, Direction::ToMeowScript).unwrap();
+
+    assert_eq!(output.code, MEOWSCRIPT);
+}
+
+#[test]
+fn source_map_points_back_to_the_original() {
+    let output = transpile(MEOWSCRIPT, Direction::ToReplacement).unwrap();
+    let map = &output.source_map;
+
+    assert_eq!(map.original_position(2, 1), Some((2, 1)));
+    assert_eq!(map.original_position(2, 5), Some((2, 9)));
+    assert_eq!(map.original_position(6, 8), Some((6, 14)));
+    assert_eq!(map.original_position(7, 9), Some((7, 9)));
+    assert_eq!(map.original_position(7, 17), Some((7, 14)));
+
+    assert!(output.source_map.to_json("out.txt", "in.meow").starts_with(
+        "{\"version\":3,\"file\":\"out.txt\",\"sources\":[\"in.meow\"],\"names\":[],\"mappings\":\";AACA,IAAQ"
+    ));
+}
+
+#[test]
+fn names_that_would_collide_are_errors() {
+    let error = transpile("scratch let = 1;", Direction::ToReplacement)
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        error.to_string(),
+        "1:9: syntax error: `let` is reserved in the replacement syntax, rename it before converting"
+    );
+
+    let error = transpile("let x = 1;\nlet scratch = 2;", Direction::ToMeowScript)
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        error.to_string(),
+        "2:5: syntax error: `scratch` is reserved in MeowScript, rename it before converting"
+    );
+}
+
+#[test]
+fn lexing_errors_keep_their_position() {
+    let error = transpile("meow(\"oops);", Direction::ToReplacement)
+        .err()
+        .unwrap();
+
+    assert_eq!(error.to_string(), "1:6: syntax error: unterminated string");
+}
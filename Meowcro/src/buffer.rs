@@ -0,0 +1,435 @@
+use crate::config::Settings;
+use crate::conflict::{find_conflicts, Conflict};
+use crate::editor::TextEditor;
+use crate::git::GitChanges;
+use crate::lsp::{
+    path_to_uri, uri_to_path, ContentChange, Diagnostic, LanguageServer, Position, TextEdit,
+};
+use crate::save::{save_file, Backup};
+use crate::snippets::{SnippetContext, SnippetSession, SnippetSet};
+use crate::syntax::{GrammarSet, Highlighter};
+use crate::terminal::TermLineLayoutSettings;
+use crate::unicode::word_start;
+use crate::watcher::FileStamp;
+use crate::words::WordIndex;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+pub const SCRATCH_NAME: &str = "untitled";
+
+pub struct LspDocument {
+    pub server: Rc<RefCell<LanguageServer>>,
+
+    pub uri: String,
+
+    version: i64,
+}
+
+pub struct Buffer {
+    pub editor: TextEditor<TermLineLayoutSettings>,
+
+    pub highlighter: Option<Highlighter>,
+
+    pub path: PathBuf,
+
+    pub settings: Settings,
+
+    pub lsp: Option<LspDocument>,
+
+    pub words: WordIndex,
+
+    pub snippet: Option<SnippetSession>,
+
+    pub git: Option<GitChanges>,
+
+    pub conflicts: Vec<Conflict>,
+
+    pub stamp: Option<FileStamp>,
+}
+
+impl Buffer {
+    pub fn new(
+        editor: TextEditor<TermLineLayoutSettings>,
+        path: PathBuf,
+        settings: Settings,
+        grammars: &GrammarSet,
+    ) -> Self {
+        let highlighter = grammars
+            .for_path(&path)
+            .map(|x| Highlighter::new(x, editor.get_text()));
+
+        let words = WordIndex::new(editor.get_text());
+
+        let git = GitChanges::load(&path);
+
+        let conflicts = find_conflicts(editor.get_text());
+
+        let stamp = FileStamp::read(&path);
+
+        Self {
+            editor,
+            highlighter,
+            path,
+            settings,
+            lsp: None,
+            words,
+            snippet: None,
+            git,
+            conflicts,
+            stamp,
+        }
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.editor.has_changed_since_save()
+            && (self.editor.get_text().len_bytes() > 0 || self.path.exists())
+    }
+
+    pub fn is_scratch(&self) -> bool {
+        self.path.as_os_str().is_empty()
+    }
+
+    pub fn get_name(&self) -> String {
+        if self.is_scratch() {
+            return SCRATCH_NAME.to_string();
+        }
+
+        self.path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+
+    pub fn get_uri(&self) -> Option<String> {
+        match &self.lsp {
+            Some(x) => Some(x.uri.clone()),
+            None => (!self.is_scratch()).then(|| path_to_uri(&self.path)),
+        }
+    }
+
+    pub fn get_title(&self) -> String {
+        if self.is_scratch() {
+            SCRATCH_NAME.to_string()
+        } else {
+            self.path.to_string_lossy().to_string()
+        }
+    }
+
+    pub fn set_path(&mut self, path: PathBuf, settings: Settings, grammars: &GrammarSet) {
+        self.sync_changes();
+
+        if settings.tab_width != self.settings.tab_width {
+            self.editor.set_layout_settings(
+                TermLineLayoutSettings::new(settings.tab_width),
+                settings.tab_width,
+            );
+        }
+
+        self.highlighter = grammars
+            .for_path(&path)
+            .map(|x| Highlighter::new(x, self.editor.get_text()));
+
+        self.path = path;
+        self.settings = settings;
+
+        self.reload_git();
+        self.update_stamp();
+    }
+
+    pub fn update_stamp(&mut self) {
+        self.stamp = FileStamp::read(&self.path);
+    }
+
+    pub fn has_changed_on_disk(&self) -> bool {
+        !self.is_scratch() && FileStamp::read(&self.path) != self.stamp
+    }
+
+    // replaces the text with the file on disk, keeping every view's cursor on the same line and column
+    pub fn reload(&mut self) -> Result<(), String> {
+        let content = std::fs::read_to_string(&self.path);
+
+        self.update_stamp();
+
+        let content = content.map_err(|e| format!("couldn't reload {}: {e}", self.get_name()))?;
+
+        self.editor.replace_text(&content);
+        self.editor.set_saved();
+        self.reload_git();
+
+        Ok(())
+    }
+
+    pub fn reload_git(&mut self) {
+        self.git = GitChanges::load(&self.path);
+    }
+
+    pub fn update_git(&mut self) {
+        if let Some(x) = self.git.as_mut() {
+            x.update(self.editor.get_text());
+        }
+    }
+
+    pub fn attach_language_server(
+        &mut self,
+        server: Rc<RefCell<LanguageServer>>,
+        language_id: &str,
+    ) -> Result<(), String> {
+        self.sync_changes();
+
+        let uri = path_to_uri(&self.path);
+
+        server
+            .borrow_mut()
+            .did_open(&uri, language_id, 1, &self.editor.to_string())?;
+
+        self.lsp = Some(LspDocument {
+            server,
+            uri,
+            version: 1,
+        });
+
+        Ok(())
+    }
+
+    pub fn detach_language_server(&mut self) {
+        if let Some(x) = self.lsp.take() {
+            x.server.borrow_mut().did_close(&x.uri).ok();
+        }
+    }
+
+    pub fn sync_changes(&mut self) {
+        loop {
+            let changes = self.editor.take_changes();
+
+            if let Some(x) = self.highlighter.as_mut() {
+                x.apply_changes(&changes);
+            }
+
+            if changes.is_empty() {
+                break;
+            }
+
+            if let Some(x) = self.git.as_mut() {
+                x.mark_outdated();
+            }
+
+            self.words.apply_changes(&changes);
+            self.words.update(self.editor.get_text());
+
+            self.conflicts = find_conflicts(self.editor.get_text());
+
+            if self.snippet.as_mut().is_some_and(|x| !x.apply_changes(&changes)) {
+                self.snippet = None;
+            }
+
+            if let Some(x) = self.lsp.as_mut() {
+                let changes = changes
+                    .into_iter()
+                    .map(|x| ContentChange {
+                        start: Position::new(x.start_position.0, x.start_position.1),
+                        end: Position::new(x.old_end_position.0, x.old_end_position.1),
+                        text: x.text,
+                    })
+                    .collect::<Vec<_>>();
+
+                x.version += 1;
+                x.server
+                    .borrow_mut()
+                    .did_change(&x.uri, x.version, &changes, self.editor.get_text())
+                    .ok();
+            }
+
+            let mirrored = match self.snippet.as_mut() {
+                Some(x) => x.apply_mirror_edits(&mut self.editor),
+                None => false,
+            };
+
+            if !mirrored {
+                break;
+            }
+        }
+    }
+
+    pub fn expand_snippet(&mut self, snippets: &SnippetSet) -> bool {
+        if self.editor.get_selection_range().is_some() {
+            return false;
+        }
+
+        self.sync_changes();
+
+        let text = self.editor.get_text();
+        let cursor = self.editor.get_cursor();
+        let start = word_start(text, cursor);
+
+        let trigger = text.byte_slice(start..cursor).to_string();
+
+        let Some(snippet) = snippets.find(&self.path, &trigger) else {
+            return false;
+        };
+
+        let line = text.byte_to_line(cursor);
+
+        let indent = text
+            .line(line)
+            .chars()
+            .take_while(|x| *x == ' ' || *x == '\t')
+            .collect::<String>();
+
+        let expansion = snippet.expand(&SnippetContext {
+            path: &self.path,
+            line,
+            indent: &indent,
+            now: SystemTime::now(),
+        });
+
+        self.editor.replace_range(start, cursor, &expansion.text, true);
+        self.sync_changes();
+
+        let session = SnippetSession::new(start, &expansion);
+
+        self.editor.set_selection(session.get_active());
+
+        self.snippet = (!session.is_finished()).then_some(session);
+
+        true
+    }
+
+    pub fn move_snippet_stop(&mut self, forward: bool) {
+        self.sync_changes();
+
+        let Some(session) = self.snippet.as_mut() else {
+            return;
+        };
+
+        let range = session.move_active(forward);
+
+        self.editor.set_selection(range);
+
+        if session.is_finished() {
+            self.snippet = None;
+        }
+    }
+
+    pub fn update_snippet(&mut self) {
+        self.sync_changes();
+
+        let cursor = self.editor.get_cursor();
+
+        if self.snippet.as_ref().is_some_and(|x| !x.contains(cursor)) {
+            self.snippet = None;
+        }
+    }
+
+    pub fn update_highlighting(&mut self, until_line: usize) {
+        if let Some(x) = self.highlighter.as_mut() {
+            x.update(self.editor.get_text(), until_line);
+        }
+    }
+
+    pub fn notify_saved(&mut self) {
+        if let Some(x) = &self.lsp {
+            x.server.borrow_mut().did_save(&x.uri).ok();
+        }
+    }
+
+    pub fn get_diagnostics(&self) -> Vec<Diagnostic> {
+        self.lsp
+            .as_ref()
+            .map(|x| x.server.borrow().diagnostics(&x.uri).to_vec())
+            .unwrap_or_default()
+    }
+
+    pub fn get_cursor_position(&self) -> Position {
+        let (line, character) = self.editor.byte_to_position(self.editor.get_cursor());
+
+        Position::new(line, character)
+    }
+
+    pub fn position_to_byte(&self, position: Position) -> usize {
+        self.editor
+            .position_to_byte(position.line, position.character)
+    }
+}
+
+pub fn apply_text_edits(editor: &mut TextEditor<TermLineLayoutSettings>, edits: &[TextEdit]) {
+    let mut ranges = edits
+        .iter()
+        .map(|x| {
+            (
+                editor.position_to_byte(x.start.line, x.start.character),
+                editor.position_to_byte(x.end.line, x.end.character),
+                x.text.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    ranges.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+
+    for (start, end, text) in ranges {
+        editor.remove_range(start, end, true, true, false);
+        editor.insert_string(start, text, true, true, false);
+    }
+}
+
+// buffers with unsaved changes are left alone, the first of them is returned to ask what to do
+pub fn reload_changed_buffers(
+    buffers: &mut [Buffer],
+    message: &mut Option<String>,
+) -> Option<usize> {
+    let mut modified = None;
+
+    for (index, buffer) in buffers.iter_mut().enumerate() {
+        if !buffer.has_changed_on_disk() {
+            continue;
+        }
+
+        if buffer.is_modified() {
+            modified = modified.or(Some(index));
+            continue;
+        }
+
+        *message = Some(match buffer.reload() {
+            Ok(()) => format!("reloaded {}", buffer.get_name()),
+            Err(e) => e,
+        });
+    }
+
+    modified
+}
+
+// open files are edited in their buffers, so the edit can be undone and reaches the language server
+pub fn apply_workspace_edit(
+    buffers: &mut [Buffer],
+    edits: &HashMap<String, Vec<TextEdit>>,
+    backup: Backup,
+) -> Result<usize, String> {
+    for (uri, edits) in edits {
+        if let Some(buffer) = buffers.iter_mut().find(|x| x.get_uri().as_ref() == Some(uri)) {
+            let history = buffer.editor.get_history_position();
+
+            apply_text_edits(&mut buffer.editor, edits);
+
+            buffer.editor.group_changes_since(history);
+            buffer.sync_changes();
+
+            continue;
+        }
+
+        let path = uri_to_path(uri).ok_or_else(|| format!("can't edit `{uri}`"))?;
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}: {e}", path.to_string_lossy()))?;
+
+        let mut editor = TextEditor::new(&content, TermLineLayoutSettings::new(4), 4, false);
+
+        apply_text_edits(&mut editor, edits);
+
+        save_file(&path, editor.to_string().as_bytes(), backup)
+            .map_err(|e| format!("{}: {e}", path.to_string_lossy()))?;
+    }
+
+    Ok(edits.len())
+}
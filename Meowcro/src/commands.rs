@@ -0,0 +1,482 @@
+use crate::conflict::Resolution;
+use crate::diffview::Side;
+use crate::fuzzy::fuzzy_score;
+use crate::panes::Orientation;
+use crate::ui::Align;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+const MAX_RECENT_COMMANDS: usize = 8;
+
+const NAME_WIDTH: usize = 30;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Save,
+    Undo,
+    Redo,
+    DiscardChanges,
+    SaveAs,
+    MoveBuffer,
+    WriteSelection,
+    NextChange,
+    PreviousChange,
+    PreviewChange,
+    RevertChange,
+    DiffSaved,
+    NextConflict,
+    PreviousConflict,
+    KeepInConflict(Resolution),
+    Copy,
+    Cut,
+    Paste,
+    CopySystem,
+    CutSystem,
+    PasteSystem,
+    NewBuffer,
+    OpenFile,
+    FindFile,
+    CloseBuffer,
+    NextBuffer,
+    PreviousBuffer,
+    SwitchBuffer,
+    Search,
+    NextSearchResult,
+    PreviousSearchResult,
+    Replace,
+    UndoReplace,
+    ToggleExplorer,
+    CreateFile,
+    RenameFile,
+    MoveFile,
+    DeleteFile,
+    Split(Orientation),
+    ClosePane,
+    FocusPane(Align),
+    ResizePane(Align),
+    CompleteWord,
+    NextSnippetField,
+    PreviousSnippetField,
+    ShowHover,
+    GoToDefinition,
+    RenameSymbol,
+    NextDiffChange,
+    PreviousDiffChange,
+    CopyChange(Side),
+    SaveDiff,
+    CloseDiff,
+    CommandPalette,
+    Quit,
+}
+
+// where a command's keybinding works, global ones work everywhere and workspace ones also
+// while a panel has focus, but not in the diff view
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Context {
+    Global,
+
+    Workspace,
+
+    Editor,
+
+    Explorer,
+
+    Snippet,
+
+    Diff,
+}
+
+impl Action {
+    pub fn get_context(self) -> Context {
+        match self {
+            Action::CommandPalette | Action::Quit => Context::Global,
+            Action::FindFile
+            | Action::Search
+            | Action::NextSearchResult
+            | Action::PreviousSearchResult
+            | Action::Replace
+            | Action::UndoReplace
+            | Action::ToggleExplorer => Context::Workspace,
+            Action::CreateFile | Action::RenameFile | Action::MoveFile | Action::DeleteFile => {
+                Context::Explorer
+            }
+            Action::NextSnippetField | Action::PreviousSnippetField => Context::Snippet,
+            Action::NextDiffChange
+            | Action::PreviousDiffChange
+            | Action::CopyChange(_)
+            | Action::SaveDiff
+            | Action::CloseDiff => Context::Diff,
+            _ => Context::Editor,
+        }
+    }
+}
+
+pub struct Command {
+    pub name: &'static str,
+
+    pub action: Action,
+
+    pub code: KeyCode,
+
+    pub modifiers: KeyModifiers,
+}
+
+const fn command(
+    name: &'static str,
+    action: Action,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> Command {
+    Command {
+        name,
+        action,
+        code,
+        modifiers,
+    }
+}
+
+pub const COMMANDS: &[Command] = &[
+    command("Save", Action::Save, KeyCode::Char('s'), KeyModifiers::CONTROL),
+    command("Undo", Action::Undo, KeyCode::Char('z'), KeyModifiers::CONTROL),
+    command("Redo", Action::Redo, KeyCode::Char('y'), KeyModifiers::CONTROL),
+    command("Discard changes", Action::DiscardChanges, KeyCode::Char('d'), KeyModifiers::ALT),
+    command("Save as", Action::SaveAs, KeyCode::Char('s'), KeyModifiers::ALT),
+    command("Rename file on disk", Action::MoveBuffer, KeyCode::Char('n'), KeyModifiers::ALT),
+    command(
+        "Write selection to file",
+        Action::WriteSelection,
+        KeyCode::Char('a'),
+        KeyModifiers::ALT,
+    ),
+    command("Next change", Action::NextChange, KeyCode::Char('j'), KeyModifiers::ALT),
+    command("Previous change", Action::PreviousChange, KeyCode::Char('k'), KeyModifiers::ALT),
+    command("Preview change", Action::PreviewChange, KeyCode::Char('h'), KeyModifiers::ALT),
+    command("Revert change", Action::RevertChange, KeyCode::Char('g'), KeyModifiers::ALT),
+    command("Diff against saved file", Action::DiffSaved, KeyCode::F(7), KeyModifiers::NONE),
+    command("Next conflict", Action::NextConflict, KeyCode::F(8), KeyModifiers::NONE),
+    command("Previous conflict", Action::PreviousConflict, KeyCode::F(8), KeyModifiers::SHIFT),
+    command(
+        "Keep ours in conflict",
+        Action::KeepInConflict(Resolution::Ours),
+        KeyCode::Char('1'),
+        KeyModifiers::ALT,
+    ),
+    command(
+        "Keep theirs in conflict",
+        Action::KeepInConflict(Resolution::Theirs),
+        KeyCode::Char('2'),
+        KeyModifiers::ALT,
+    ),
+    command(
+        "Keep both in conflict",
+        Action::KeepInConflict(Resolution::Both),
+        KeyCode::Char('3'),
+        KeyModifiers::ALT,
+    ),
+    command(
+        "Keep base in conflict",
+        Action::KeepInConflict(Resolution::Base),
+        KeyCode::Char('4'),
+        KeyModifiers::ALT,
+    ),
+    command("Copy", Action::Copy, KeyCode::Char('c'), KeyModifiers::CONTROL),
+    command("Cut", Action::Cut, KeyCode::Char('x'), KeyModifiers::CONTROL),
+    command("Paste", Action::Paste, KeyCode::Char('v'), KeyModifiers::CONTROL),
+    command("Copy to system clipboard", Action::CopySystem, KeyCode::Char('c'), KeyModifiers::ALT),
+    command("Cut to system clipboard", Action::CutSystem, KeyCode::Char('x'), KeyModifiers::ALT),
+    command(
+        "Paste from system clipboard",
+        Action::PasteSystem,
+        KeyCode::Char('v'),
+        KeyModifiers::ALT,
+    ),
+    command("New buffer", Action::NewBuffer, KeyCode::Char('n'), KeyModifiers::CONTROL),
+    command("Open file", Action::OpenFile, KeyCode::Char('o'), KeyModifiers::CONTROL),
+    command("Find file", Action::FindFile, KeyCode::Char('p'), KeyModifiers::CONTROL),
+    command("Close buffer", Action::CloseBuffer, KeyCode::Char('w'), KeyModifiers::CONTROL),
+    command("Next buffer", Action::NextBuffer, KeyCode::PageDown, KeyModifiers::CONTROL),
+    command("Previous buffer", Action::PreviousBuffer, KeyCode::PageUp, KeyModifiers::CONTROL),
+    command("Switch buffer", Action::SwitchBuffer, KeyCode::Char('b'), KeyModifiers::CONTROL),
+    command("Search in files", Action::Search, KeyCode::Char('f'), KeyModifiers::ALT),
+    command("Next search result", Action::NextSearchResult, KeyCode::F(4), KeyModifiers::NONE),
+    command(
+        "Previous search result",
+        Action::PreviousSearchResult,
+        KeyCode::F(4),
+        KeyModifiers::SHIFT,
+    ),
+    command("Replace in files", Action::Replace, KeyCode::Char('r'), KeyModifiers::ALT),
+    command("Undo project replace", Action::UndoReplace, KeyCode::Char('u'), KeyModifiers::ALT),
+    command(
+        "Toggle file explorer",
+        Action::ToggleExplorer,
+        KeyCode::Char('e'),
+        KeyModifiers::CONTROL,
+    ),
+    command("Create file", Action::CreateFile, KeyCode::Char('a'), KeyModifiers::NONE),
+    command("Rename file", Action::RenameFile, KeyCode::Char('r'), KeyModifiers::NONE),
+    command("Move file", Action::MoveFile, KeyCode::Char('m'), KeyModifiers::NONE),
+    command("Delete file", Action::DeleteFile, KeyCode::Char('d'), KeyModifiers::NONE),
+    command(
+        "Split side by side",
+        Action::Split(Orientation::SideBySide),
+        KeyCode::Char('\\'),
+        KeyModifiers::ALT,
+    ),
+    command(
+        "Split stacked",
+        Action::Split(Orientation::Stacked),
+        KeyCode::Char('-'),
+        KeyModifiers::ALT,
+    ),
+    command("Close pane", Action::ClosePane, KeyCode::Char('w'), KeyModifiers::ALT),
+    command("Focus pane left", Action::FocusPane(Align::Left), KeyCode::Left, KeyModifiers::ALT),
+    command("Focus pane right", Action::FocusPane(Align::Right), KeyCode::Right, KeyModifiers::ALT),
+    command("Focus pane above", Action::FocusPane(Align::Top), KeyCode::Up, KeyModifiers::ALT),
+    command("Focus pane below", Action::FocusPane(Align::Bottom), KeyCode::Down, KeyModifiers::ALT),
+    command(
+        "Resize pane left",
+        Action::ResizePane(Align::Left),
+        KeyCode::Left,
+        KeyModifiers::ALT.union(KeyModifiers::SHIFT),
+    ),
+    command(
+        "Resize pane right",
+        Action::ResizePane(Align::Right),
+        KeyCode::Right,
+        KeyModifiers::ALT.union(KeyModifiers::SHIFT),
+    ),
+    command(
+        "Resize pane up",
+        Action::ResizePane(Align::Top),
+        KeyCode::Up,
+        KeyModifiers::ALT.union(KeyModifiers::SHIFT),
+    ),
+    command(
+        "Resize pane down",
+        Action::ResizePane(Align::Bottom),
+        KeyCode::Down,
+        KeyModifiers::ALT.union(KeyModifiers::SHIFT),
+    ),
+    command("Complete word", Action::CompleteWord, KeyCode::Char(' '), KeyModifiers::CONTROL),
+    command("Next snippet field", Action::NextSnippetField, KeyCode::Tab, KeyModifiers::NONE),
+    command(
+        "Previous snippet field",
+        Action::PreviousSnippetField,
+        KeyCode::BackTab,
+        KeyModifiers::SHIFT,
+    ),
+    command("Show hover", Action::ShowHover, KeyCode::Char('k'), KeyModifiers::CONTROL),
+    command("Go to definition", Action::GoToDefinition, KeyCode::F(12), KeyModifiers::NONE),
+    command("Rename symbol", Action::RenameSymbol, KeyCode::F(2), KeyModifiers::NONE),
+    command("Next change in diff", Action::NextDiffChange, KeyCode::Char('j'), KeyModifiers::ALT),
+    command(
+        "Previous change in diff",
+        Action::PreviousDiffChange,
+        KeyCode::Char('k'),
+        KeyModifiers::ALT,
+    ),
+    command(
+        "Copy change to left",
+        Action::CopyChange(Side::Left),
+        KeyCode::Char('<'),
+        KeyModifiers::NONE,
+    ),
+    command(
+        "Copy change to right",
+        Action::CopyChange(Side::Right),
+        KeyCode::Char('>'),
+        KeyModifiers::NONE,
+    ),
+    command("Save buffers in diff", Action::SaveDiff, KeyCode::Char('s'), KeyModifiers::CONTROL),
+    command("Close diff", Action::CloseDiff, KeyCode::Esc, KeyModifiers::NONE),
+    command(
+        "Command palette",
+        Action::CommandPalette,
+        KeyCode::Char('p'),
+        KeyModifiers::CONTROL.union(KeyModifiers::SHIFT),
+    ),
+    command("Quit", Action::Quit, KeyCode::Char('q'), KeyModifiers::ALT),
+];
+
+// more chords for some commands, the palette only shows the first one
+const ALIASES: &[(KeyCode, KeyModifiers, Action)] = &[
+    (
+        KeyCode::Char('P'),
+        KeyModifiers::CONTROL.union(KeyModifiers::SHIFT),
+        Action::CommandPalette,
+    ),
+    // for terminals that don't tell ctrl+shift+p apart from ctrl+p
+    (KeyCode::F(1), KeyModifiers::NONE, Action::CommandPalette),
+    (KeyCode::Char('.'), KeyModifiers::ALT, Action::NextBuffer),
+    (KeyCode::Char(','), KeyModifiers::ALT, Action::PreviousBuffer),
+    (KeyCode::Delete, KeyModifiers::NONE, Action::DeleteFile),
+    (KeyCode::Char('<'), KeyModifiers::SHIFT, Action::CopyChange(Side::Left)),
+    (KeyCode::Char('>'), KeyModifiers::SHIFT, Action::CopyChange(Side::Right)),
+];
+
+// finds the command bound to a key, among the commands that work in one of the contexts
+pub fn find_command(
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    contexts: &[Context],
+) -> Option<Action> {
+    // terminals differ on whether shift+tab also reports shift
+    let modifiers = match code {
+        KeyCode::BackTab => modifiers | KeyModifiers::SHIFT,
+        _ => modifiers,
+    };
+
+    COMMANDS
+        .iter()
+        .map(|x| (x.code, x.modifiers, x.action))
+        .chain(ALIASES.iter().copied())
+        .find(|(x, y, action)| {
+            *x == code && *y == modifiers && contexts.contains(&action.get_context())
+        })
+        .map(|(_, _, action)| action)
+}
+
+impl Command {
+    pub fn get_key_label(&self) -> String {
+        let mut label = String::new();
+
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                label.push_str(name);
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(c) => label.push(c),
+            KeyCode::Tab | KeyCode::BackTab => label.push_str("Tab"),
+            KeyCode::F(x) => label.push_str(&format!("F{x}")),
+            KeyCode::PageUp => label.push_str("PageUp"),
+            KeyCode::PageDown => label.push_str("PageDown"),
+            KeyCode::Left => label.push_str("Left"),
+            KeyCode::Right => label.push_str("Right"),
+            KeyCode::Up => label.push_str("Up"),
+            KeyCode::Down => label.push_str("Down"),
+            x => label.push_str(&format!("{x:?}")),
+        }
+
+        label
+    }
+
+    pub fn get_label(&self) -> String {
+        format!("{:<NAME_WIDTH$} {}", self.name, self.get_key_label())
+    }
+}
+
+pub struct CommandPalette {
+    query: String,
+
+    matches: Vec<usize>,
+
+    labels: Vec<String>,
+
+    recent: Vec<usize>,
+
+    contexts: Vec<Context>,
+
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new(recent: &[usize], contexts: &[Context]) -> Self {
+        let mut palette = Self {
+            query: String::new(),
+            matches: Vec::new(),
+            labels: COMMANDS.iter().map(Command::get_label).collect(),
+            recent: recent.to_vec(),
+            contexts: contexts.to_vec(),
+            selected: 0,
+        };
+
+        palette.update_matches();
+
+        palette
+    }
+
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.update_matches();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.update_matches();
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+
+        self.selected = (self.selected as isize + delta).rem_euclid(self.matches.len() as isize) as usize;
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_selected_command(&self) -> Option<usize> {
+        self.matches.get(self.selected).copied()
+    }
+
+    pub fn get_matches(&self) -> Vec<&str> {
+        self.matches.iter().map(|x| self.labels[*x].as_str()).collect()
+    }
+
+    pub fn get_status(&self) -> String {
+        let available = COMMANDS
+            .iter()
+            .filter(|x| self.contexts.contains(&x.action.get_context()))
+            .count();
+
+        format!("{}/{available}", self.matches.len())
+    }
+
+    fn update_matches(&mut self) {
+        let recency = |x: usize| {
+            self.recent
+                .iter()
+                .position(|y| *y == x)
+                .unwrap_or(self.recent.len())
+        };
+
+        let mut matches = COMMANDS
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| self.contexts.contains(&x.action.get_context()))
+            .filter_map(|(index, x)| Some((fuzzy_score(&self.query, x.name)?, index)))
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            let order = if self.query.is_empty() {
+                recency(*a).cmp(&recency(*b))
+            } else {
+                b_score.cmp(a_score).then_with(|| recency(*a).cmp(&recency(*b)))
+            };
+
+            order.then_with(|| a.cmp(b))
+        });
+
+        self.matches = matches.into_iter().map(|(_, x)| x).collect();
+        self.selected = 0;
+    }
+}
+
+pub fn remember_command(recent: &mut Vec<usize>, command: usize) {
+    recent.retain(|x| *x != command);
+    recent.insert(0, command);
+    recent.truncate(MAX_RECENT_COMMANDS);
+}
@@ -0,0 +1,128 @@
+use crate::buffer::Buffer;
+use crate::fuzzy::fuzzy_score;
+use crate::lsp::CompletionItem;
+use crate::unicode::{is_word_character, word_start};
+use crate::words::WordIndex;
+use std::cmp::Reverse;
+
+pub struct CompletionMenu {
+    items: Vec<CompletionItem>,
+
+    start: usize,
+
+    matches: Vec<usize>,
+
+    selected: usize,
+}
+
+impl CompletionMenu {
+    pub fn new(items: Vec<CompletionItem>, buffer: &Buffer) -> Option<Self> {
+        let editor = &buffer.editor;
+
+        let mut menu = Self {
+            items,
+            start: word_start(editor.get_text(), editor.get_cursor()),
+            matches: Vec::new(),
+            selected: 0,
+        };
+
+        menu.update(buffer).then_some(menu)
+    }
+
+    pub fn from_words(indexes: &[&WordIndex], buffer: &Buffer) -> Option<Self> {
+        let text = buffer.editor.get_text();
+        let cursor = buffer.editor.get_cursor();
+
+        let typed = text
+            .byte_slice(word_start(text, cursor)..cursor)
+            .to_string();
+
+        let mut words: Vec<(&str, usize)> = Vec::new();
+
+        for (word, count) in indexes.iter().flat_map(|x| x.words()) {
+            match words.iter_mut().find(|(x, _)| *x == word) {
+                Some((_, x)) => *x += count,
+                None => words.push((word, count)),
+            }
+        }
+
+        words.retain(|(word, count)| *word != typed || *count > 1);
+        words.sort_by_key(|(word, count)| (Reverse(*count), word.len(), *word));
+
+        let items = words
+            .into_iter()
+            .map(|(word, _)| CompletionItem {
+                label: word.to_string(),
+                detail: None,
+                insert_text: word.to_string(),
+                edit: None,
+            })
+            .collect();
+
+        Self::new(items, buffer)
+    }
+
+    pub fn update(&mut self, buffer: &Buffer) -> bool {
+        let text = buffer.editor.get_text();
+        let cursor = buffer.editor.get_cursor();
+
+        if cursor < self.start || cursor > text.len_bytes() {
+            return false;
+        }
+
+        let prefix = text.byte_slice(self.start..cursor).to_string();
+
+        if !prefix.chars().all(is_word_character) {
+            return false;
+        }
+
+        let mut scored = (0..self.items.len())
+            .filter_map(|x| Some((fuzzy_score(&prefix, &self.items[x].label)?, x)))
+            .collect::<Vec<_>>();
+
+        scored.sort_by_key(|(score, index)| (Reverse(*score), *index));
+
+        self.matches = scored.into_iter().map(|(_, x)| x).collect();
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+
+        !self.matches.is_empty()
+    }
+
+    pub fn move_selection(&mut self, amount: isize) {
+        let len = self.matches.len() as isize;
+
+        if len > 0 {
+            self.selected = (self.selected as isize + amount).rem_euclid(len) as usize;
+        }
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_lines(&self) -> Vec<String> {
+        self.matches
+            .iter()
+            .map(|x| &self.items[*x])
+            .map(|x| match &x.detail {
+                Some(detail) if *detail != x.label => format!("{}  {detail}", x.label),
+                _ => x.label.clone(),
+            })
+            .collect()
+    }
+
+    pub fn accept(&self, buffer: &mut Buffer) {
+        let Some(item) = self.matches.get(self.selected).map(|x| &self.items[*x]) else {
+            return;
+        };
+
+        let (start, text) = match &item.edit {
+            Some(edit) => (buffer.position_to_byte(edit.start), edit.text.as_str()),
+            None => (self.start, item.insert_text.as_str()),
+        };
+
+        let cursor = buffer.editor.get_cursor();
+
+        buffer.editor.replace_range(start.min(cursor), cursor, text, true);
+    }
+}
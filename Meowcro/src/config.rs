@@ -0,0 +1,422 @@
+use crate::save::Backup;
+use crate::statusline::Segment;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const PROJECT_CONFIG_NAME: &str = ".meowcro.toml";
+
+#[derive(Deserialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageServerConfig {
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FiletypeConfig {
+    pub tab_width: Option<usize>,
+
+    pub relative_line_numbers: Option<bool>,
+
+    pub language_server: Option<LanguageServerConfig>,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct StatusLineFile {
+    pub left: Option<Vec<Segment>>,
+
+    pub center: Option<Vec<Segment>>,
+
+    pub right: Option<Vec<Segment>>,
+
+    pub message_timeout: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub tab_width: Option<usize>,
+
+    pub relative_line_numbers: Option<bool>,
+
+    pub disable_mouse_interaction: Option<bool>,
+
+    pub theme: Option<String>,
+
+    pub status_line: Option<StatusLineFile>,
+
+    pub backup: Option<Backup>,
+
+    #[serde(default)]
+    pub filetype: HashMap<String, FiletypeConfig>,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct StatusLineSettings {
+    pub left: Vec<Segment>,
+
+    pub center: Vec<Segment>,
+
+    pub right: Vec<Segment>,
+
+    pub message_timeout: u64,
+}
+
+impl Default for StatusLineSettings {
+    fn default() -> Self {
+        Self {
+            left: vec![Segment::Mode, Segment::File, Segment::Message],
+            center: Vec::new(),
+            right: vec![
+                Segment::Selection,
+                Segment::Filetype,
+                Segment::Encoding,
+                Segment::LineEnding,
+                Segment::Indent,
+                Segment::Lines,
+                Segment::Percent,
+                Segment::Position,
+            ],
+            message_timeout: 5,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Settings {
+    pub tab_width: usize,
+
+    pub relative_line_numbers: bool,
+
+    pub disable_mouse_interaction: bool,
+
+    pub theme: String,
+
+    pub language_server: Option<LanguageServerConfig>,
+
+    pub status_line: StatusLineSettings,
+
+    pub backup: Backup,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tab_width: 4,
+            relative_line_numbers: false,
+            disable_mouse_interaction: false,
+            theme: "default".to_string(),
+            language_server: None,
+            status_line: StatusLineSettings::default(),
+            backup: Backup::default(),
+        }
+    }
+}
+
+impl Settings {
+    fn apply_file(&mut self, file: &ConfigFile) {
+        if let Some(x) = file.tab_width {
+            self.tab_width = x;
+        }
+
+        if let Some(x) = file.relative_line_numbers {
+            self.relative_line_numbers = x;
+        }
+
+        if let Some(x) = file.disable_mouse_interaction {
+            self.disable_mouse_interaction = x;
+        }
+
+        if let Some(x) = &file.theme {
+            self.theme = x.clone();
+        }
+
+        if let Some(x) = &file.status_line {
+            let status_line = &mut self.status_line;
+
+            for (setting, value) in [
+                (&mut status_line.left, &x.left),
+                (&mut status_line.center, &x.center),
+                (&mut status_line.right, &x.right),
+            ] {
+                if let Some(value) = value {
+                    *setting = value.clone();
+                }
+            }
+
+            if let Some(value) = x.message_timeout {
+                status_line.message_timeout = value;
+            }
+        }
+
+        if let Some(x) = file.backup {
+            self.backup = x;
+        }
+    }
+
+    fn apply_filetype(&mut self, file: &FiletypeConfig) {
+        if let Some(x) = file.tab_width {
+            self.tab_width = x;
+        }
+
+        if let Some(x) = file.relative_line_numbers {
+            self.relative_line_numbers = x;
+        }
+
+        if let Some(x) = &file.language_server {
+            self.language_server = Some(x.clone());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: PathBuf,
+
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.to_string_lossy(), self.message)
+    }
+}
+
+#[derive(Clone)]
+struct WatchedFile {
+    path: PathBuf,
+
+    modified: Option<SystemTime>,
+
+    content: ConfigFile,
+}
+
+impl WatchedFile {
+    fn load(path: PathBuf) -> Result<Self, ConfigError> {
+        let modified = modified_time(&path);
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(x) => parse(&path, &x)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ConfigFile::default(),
+            Err(e) => {
+                return Err(ConfigError {
+                    path,
+                    message: e.to_string(),
+                })
+            }
+        };
+
+        Ok(Self {
+            path,
+            modified,
+            content,
+        })
+    }
+
+    fn has_changed(&self) -> bool {
+        modified_time(&self.path) != self.modified
+    }
+}
+
+pub struct Config {
+    global: Option<WatchedFile>,
+
+    // the project file found above each directory that buffers were opened from
+    projects: RefCell<HashMap<PathBuf, Option<WatchedFile>>>,
+
+    // project files that failed to load after startup, reported on the next reload check
+    errors: RefCell<Vec<ConfigError>>,
+
+    overrides: ConfigFile,
+}
+
+impl Config {
+    pub fn load(file_path: &Path, overrides: ConfigFile) -> Result<Self, ConfigError> {
+        Self::load_with_global(global_config_path(), file_path, overrides)
+    }
+
+    pub fn load_with_global(
+        global: Option<PathBuf>,
+        file_path: &Path,
+        overrides: ConfigFile,
+    ) -> Result<Self, ConfigError> {
+        let directory = get_directory(file_path);
+
+        let project = find_project_config(&directory)
+            .map(WatchedFile::load)
+            .transpose()?;
+
+        Ok(Self {
+            global: global.map(WatchedFile::load).transpose()?,
+            projects: RefCell::new(HashMap::from([(directory, project)])),
+            errors: RefCell::new(Vec::new()),
+            overrides,
+        })
+    }
+
+    pub fn settings_for(&self, file_path: &Path) -> Settings {
+        let mut settings = Settings::default();
+
+        let filetype = crate::filetype::detect(file_path);
+
+        let project = self.get_project(file_path).map(|x| x.content);
+
+        let files = || self.global.iter().map(|x| &x.content).chain(project.as_ref());
+
+        for file in files() {
+            settings.apply_file(file);
+        }
+
+        for file in files() {
+            if let Some(x) = filetype.and_then(|x| file.filetype.get(x)) {
+                settings.apply_filetype(x);
+            }
+        }
+
+        settings.apply_file(&self.overrides);
+
+        settings
+    }
+
+    pub fn project_root(&self, file_path: &Path) -> Option<PathBuf> {
+        self.get_project(file_path)
+            .and_then(|x| x.path.parent().map(Path::to_path_buf))
+    }
+
+    pub fn reload_if_changed(&mut self) -> Option<Result<(), ConfigError>> {
+        if let Some(e) = self.errors.get_mut().pop() {
+            return Some(Err(e));
+        }
+
+        let projects = self.projects.get_mut();
+
+        let changed = self.global.iter().any(WatchedFile::has_changed)
+            || projects.iter().any(|(directory, project)| {
+                find_project_config(directory) != project.as_ref().map(|x| x.path.clone())
+                    || project.as_ref().is_some_and(WatchedFile::has_changed)
+            });
+
+        if !changed {
+            return None;
+        }
+
+        Some(self.reload())
+    }
+
+    fn reload(&mut self) -> Result<(), ConfigError> {
+        let projects = self.projects.get_mut();
+
+        for file in self.global.iter_mut().chain(projects.values_mut().flatten()) {
+            file.modified = modified_time(&file.path);
+        }
+
+        let global = self
+            .global
+            .as_ref()
+            .map(|x| WatchedFile::load(x.path.clone()))
+            .transpose()?;
+
+        let mut reloaded = HashMap::new();
+
+        for directory in projects.keys() {
+            let project = find_project_config(directory)
+                .map(WatchedFile::load)
+                .transpose()?;
+
+            reloaded.insert(directory.clone(), project);
+        }
+
+        self.global = global;
+        *projects = reloaded;
+
+        Ok(())
+    }
+
+    // looks the project file up once per directory, later changes are found by reload_if_changed
+    fn get_project(&self, file_path: &Path) -> Option<WatchedFile> {
+        let directory = get_directory(file_path);
+
+        let mut projects = self.projects.borrow_mut();
+
+        let project = projects.entry(directory).or_insert_with_key(|directory| {
+            let path = find_project_config(directory)?;
+
+            match WatchedFile::load(path.clone()) {
+                Ok(x) => Some(x),
+                Err(e) => {
+                    self.errors.borrow_mut().push(e);
+
+                    Some(WatchedFile {
+                        modified: modified_time(&path),
+                        path,
+                        content: ConfigFile::default(),
+                    })
+                }
+            }
+        });
+
+        project.clone()
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+        .map(|x| x.join("meowcro"))
+}
+
+fn global_config_path() -> Option<PathBuf> {
+    config_dir().map(|x| x.join("config.toml"))
+}
+
+// a scratch buffer has no path and uses the working directory
+fn get_directory(file_path: &Path) -> PathBuf {
+    std::path::absolute(file_path)
+        .ok()
+        .and_then(|x| x.parent().map(Path::to_path_buf))
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+fn find_project_config(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|x| x.join(PROJECT_CONFIG_NAME))
+        .find(|x| x.is_file())
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|x| x.modified()).ok()
+}
+
+fn parse(path: &Path, content: &str) -> Result<ConfigFile, ConfigError> {
+    toml::from_str(content).map_err(|e| {
+        let (line, column) = e
+            .span()
+            .map(|x| line_and_column(content, x.start))
+            .unwrap_or((1, 1));
+
+        ConfigError {
+            path: path.to_path_buf(),
+            message: format!("{line}:{column}: {}", e.message().trim()),
+        }
+    })
+}
+
+fn line_and_column(content: &str, byte: usize) -> (usize, usize) {
+    let before = &content[..byte.min(content.len())];
+
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+    (line, column)
+}
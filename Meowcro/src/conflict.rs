@@ -0,0 +1,133 @@
+use ropey::RopeSlice;
+use std::ops::Range;
+
+const OURS_MARKER: &str = "<<<<<<<";
+
+const BASE_MARKER: &str = "|||||||";
+
+const SEPARATOR: &str = "=======";
+
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Resolution {
+    Ours,
+
+    Theirs,
+
+    Both,
+
+    Base,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConflictPart {
+    Marker,
+
+    Ours,
+
+    Base,
+
+    Theirs,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Conflict {
+    pub lines: Range<usize>,
+
+    pub ours: Range<usize>,
+
+    pub base: Option<Range<usize>>,
+
+    pub theirs: Range<usize>,
+}
+
+fn is_marker(line: RopeSlice, marker: &str) -> bool {
+    let mut chars = line.chars();
+
+    marker.chars().all(|x| chars.next() == Some(x)) && chars.next().is_none_or(char::is_whitespace)
+}
+
+fn lines_text(text: RopeSlice, lines: &Range<usize>) -> String {
+    text.slice(text.line_to_char(lines.start)..text.line_to_char(lines.end))
+        .to_string()
+}
+
+impl Conflict {
+    pub fn get_regions(&self) -> Vec<(Range<usize>, ConflictPart)> {
+        let mut regions = vec![
+            (self.lines.start..self.lines.start + 1, ConflictPart::Marker),
+            (self.ours.clone(), ConflictPart::Ours),
+        ];
+
+        if let Some(base) = &self.base {
+            regions.push((base.start - 1..base.start, ConflictPart::Marker));
+            regions.push((base.clone(), ConflictPart::Base));
+        }
+
+        regions.push((self.theirs.start - 1..self.theirs.start, ConflictPart::Marker));
+        regions.push((self.theirs.clone(), ConflictPart::Theirs));
+        regions.push((self.lines.end - 1..self.lines.end, ConflictPart::Marker));
+
+        regions
+    }
+
+    pub fn resolve(&self, text: RopeSlice, resolution: Resolution) -> Result<String, String> {
+        match resolution {
+            Resolution::Ours => Ok(lines_text(text, &self.ours)),
+            Resolution::Theirs => Ok(lines_text(text, &self.theirs)),
+            Resolution::Both => Ok(lines_text(text, &self.ours) + &lines_text(text, &self.theirs)),
+            Resolution::Base => match &self.base {
+                Some(x) => Ok(lines_text(text, x)),
+                None => Err("this conflict has no base section".to_string()),
+            },
+        }
+    }
+}
+
+pub fn find_conflicts(text: RopeSlice) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+
+    let (mut start, mut base, mut separator) = (None, None, None);
+
+    for (index, line) in text.lines().enumerate() {
+        if is_marker(line, OURS_MARKER) {
+            (start, base, separator) = (Some(index), None, None);
+        } else if is_marker(line, BASE_MARKER) && start.is_some() && separator.is_none() {
+            base = Some(index);
+        } else if is_marker(line, SEPARATOR) && start.is_some() && separator.is_none() {
+            separator = Some(index);
+        } else if let (true, Some(first), Some(middle)) =
+            (is_marker(line, THEIRS_MARKER), start, separator)
+        {
+            conflicts.push(Conflict {
+                lines: first..index + 1,
+                ours: first + 1..base.unwrap_or(middle),
+                base: base.map(|x| x + 1..middle),
+                theirs: middle + 1..index,
+            });
+
+            start = None;
+        }
+    }
+
+    conflicts
+}
+
+pub fn get_conflict_at(conflicts: &[Conflict], line: usize) -> Option<&Conflict> {
+    conflicts.iter().find(|x| x.lines.contains(&line))
+}
+
+pub fn get_next_conflict(conflicts: &[Conflict], line: usize) -> Option<usize> {
+    conflicts
+        .iter()
+        .position(|x| x.lines.start > line)
+        .or_else(|| (!conflicts.is_empty()).then_some(0))
+}
+
+pub fn get_previous_conflict(conflicts: &[Conflict], line: usize) -> Option<usize> {
+    conflicts
+        .iter()
+        .rposition(|x| x.lines.start < line)
+        .or_else(|| conflicts.len().checked_sub(1))
+}
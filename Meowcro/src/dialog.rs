@@ -0,0 +1,86 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DialogResult {
+    Pressed(usize),
+
+    Cancelled,
+
+    Pending,
+}
+
+pub struct Dialog<A> {
+    action: A,
+
+    message: String,
+
+    buttons: &'static [&'static str],
+
+    selected: usize,
+}
+
+impl<A> Dialog<A> {
+    pub fn new(action: A, message: &str, buttons: &'static [&'static str]) -> Self {
+        Self {
+            action,
+            message: message.to_string(),
+            buttons,
+            selected: 0,
+        }
+    }
+
+    pub fn into_action(self) -> A {
+        self.action
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_buttons(&self) -> &'static [&'static str] {
+        self.buttons
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.buttons.len() {
+            self.selected = index;
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> DialogResult {
+        let count = self.buttons.len();
+
+        match code {
+            KeyCode::Enter => DialogResult::Pressed(self.selected),
+            KeyCode::Esc => DialogResult::Cancelled,
+            KeyCode::Right | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % count;
+                DialogResult::Pending
+            }
+            KeyCode::Left | KeyCode::BackTab => {
+                self.selected = (self.selected + count - 1) % count;
+                DialogResult::Pending
+            }
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                let c = c.to_ascii_lowercase();
+
+                match self
+                    .buttons
+                    .iter()
+                    .position(|x| x.to_ascii_lowercase().starts_with(c))
+                {
+                    Some(x) => {
+                        self.selected = x;
+                        DialogResult::Pressed(x)
+                    }
+                    None => DialogResult::Pending,
+                }
+            }
+            _ => DialogResult::Pending,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+    pub old: Range<usize>,
+
+    pub new: Range<usize>,
+}
+
+pub fn diff_lines<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Hunk> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let matches = match_lines(old_middle, new_middle);
+
+    let mut hunks = Vec::new();
+
+    let (mut old_line, mut new_line) = (0, 0);
+
+    for (old_match, new_match) in matches.into_iter().chain([(old_middle.len(), new_middle.len())]) {
+        if old_match > old_line || new_match > new_line {
+            hunks.push(Hunk {
+                old: prefix + old_line..prefix + old_match,
+                new: prefix + new_line..prefix + new_match,
+            });
+        }
+
+        old_line = old_match + 1;
+        new_line = new_match + 1;
+    }
+
+    hunks
+}
+
+// Myers' algorithm, returning the pairs of matching lines in order
+fn match_lines<T: PartialEq>(old: &[T], new: &[T]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+
+    let max = ((n + m) as usize).min(MAX_EDIT_DISTANCE + 1);
+    let offset = max as isize;
+
+    let mut v = vec![0_isize; 2 * max + 2];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=max as isize {
+        // too different to be worth matching up, so it's all one change
+        if d as usize > MAX_EDIT_DISTANCE {
+            return Vec::new();
+        }
+
+        trace.push(v.clone());
+
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[index] = x;
+
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut matches = Vec::new();
+
+    let (mut x, mut y) = (n, m);
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let index = (k + offset) as usize;
+
+        let previous_k = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+
+        let previous_x = v[(previous_k + offset) as usize];
+        let previous_y = previous_x - previous_k;
+
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            matches.push((x as usize, y as usize));
+        }
+
+        if d > 0 {
+            x = previous_x;
+            y = previous_y;
+        }
+    }
+
+    matches.reverse();
+
+    matches
+}
@@ -0,0 +1,221 @@
+use crate::diff::{diff_lines, Hunk};
+use std::ops::Range;
+
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Left,
+
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RowKind {
+    Equal,
+
+    Changed,
+
+    Removed,
+
+    Added,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DiffRow {
+    pub left: Option<usize>,
+
+    pub right: Option<usize>,
+
+    pub kind: RowKind,
+
+    pub hunk: Option<usize>,
+}
+
+pub struct DiffView {
+    titles: [String; 2],
+
+    left: Vec<String>,
+
+    right: Vec<String>,
+
+    hunks: Vec<Hunk>,
+
+    rows: Vec<DiffRow>,
+
+    selected: usize,
+
+    scroll: usize,
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(str::to_string).collect()
+}
+
+fn trim_line(line: &str) -> &str {
+    line.trim_end_matches(['\r', '\n'])
+}
+
+fn push_equal_rows(rows: &mut Vec<DiffRow>, left: Range<usize>, right: usize) {
+    rows.extend(left.enumerate().map(|(offset, x)| DiffRow {
+        left: Some(x),
+        right: Some(right + offset),
+        kind: RowKind::Equal,
+        hunk: None,
+    }));
+}
+
+fn get_rows(hunks: &[Hunk], left_len: usize) -> Vec<DiffRow> {
+    let mut rows = Vec::new();
+
+    let (mut left, mut right) = (0, 0);
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        push_equal_rows(&mut rows, left..hunk.old.start, right);
+
+        for offset in 0..hunk.old.len().max(hunk.new.len()) {
+            let (old, new) = (hunk.old.start + offset, hunk.new.start + offset);
+
+            let (left, right, kind) = match (hunk.old.contains(&old), hunk.new.contains(&new)) {
+                (true, true) => (Some(old), Some(new), RowKind::Changed),
+                (true, false) => (Some(old), None, RowKind::Removed),
+                _ => (None, Some(new), RowKind::Added),
+            };
+
+            rows.push(DiffRow {
+                left,
+                right,
+                kind,
+                hunk: Some(index),
+            });
+        }
+
+        left = hunk.old.end;
+        right = hunk.new.end;
+    }
+
+    push_equal_rows(&mut rows, left..left_len, right);
+
+    rows
+}
+
+impl DiffView {
+    pub fn new(left_title: &str, left: &str, right_title: &str, right: &str) -> Self {
+        let mut view = Self {
+            titles: [left_title.to_string(), right_title.to_string()],
+            left: Vec::new(),
+            right: Vec::new(),
+            hunks: Vec::new(),
+            rows: Vec::new(),
+            selected: 0,
+            scroll: 0,
+        };
+
+        view.set_text(left, right);
+        view.scroll_to_selected();
+
+        view
+    }
+
+    pub fn set_text(&mut self, left: &str, right: &str) {
+        self.left = split_lines(left);
+        self.right = split_lines(right);
+
+        self.hunks = diff_lines(&self.left, &self.right);
+        self.rows = get_rows(&self.hunks, self.left.len());
+
+        self.selected = self.selected.min(self.hunks.len().saturating_sub(1));
+        self.scroll = self.scroll.min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn get_title(&self, side: Side) -> &str {
+        match side {
+            Side::Left => &self.titles[0],
+            Side::Right => &self.titles[1],
+        }
+    }
+
+    pub fn get_line(&self, side: Side, index: usize) -> &str {
+        let lines = match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        };
+
+        lines.get(index).map(|x| trim_line(x)).unwrap_or_default()
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.left.len().max(self.right.len())
+    }
+
+    pub fn get_rows(&self) -> &[DiffRow] {
+        &self.rows
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        (!self.hunks.is_empty()).then_some(self.selected)
+    }
+
+    pub fn get_scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn get_status(&self) -> String {
+        match self.hunks.len() {
+            0 => "no differences".to_string(),
+            count => format!("change {} of {count}", self.selected + 1),
+        }
+    }
+
+    pub fn scroll_by(&mut self, amount: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(amount)
+            .min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.hunks.is_empty() {
+            self.selected = (self.selected + 1) % self.hunks.len();
+            self.scroll_to_selected();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.hunks.is_empty() {
+            self.selected = self.selected.checked_sub(1).unwrap_or(self.hunks.len() - 1);
+            self.scroll_to_selected();
+        }
+    }
+
+    fn scroll_to_selected(&mut self) {
+        if let Some(row) = self.rows.iter().position(|x| x.hunk == Some(self.selected)) {
+            self.scroll = row.saturating_sub(CONTEXT_LINES);
+        }
+    }
+
+    // the lines to replace on the `to` side and the text from the other side that replaces them
+    pub fn get_copy(&self, to: Side) -> Option<(Range<usize>, String)> {
+        let hunk = self.hunks.get(self.selected)?;
+
+        match to {
+            Side::Left => Some((hunk.old.clone(), self.right[hunk.new.clone()].concat())),
+            Side::Right => Some((hunk.new.clone(), self.left[hunk.old.clone()].concat())),
+        }
+    }
+
+    // the changed characters of both sides of a changed row
+    pub fn get_inline_changes(&self, row: &DiffRow) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+        let (Some(left), Some(right)) = (row.left, row.right) else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let left = self.get_line(Side::Left, left).chars().collect::<Vec<_>>();
+        let right = self.get_line(Side::Right, right).chars().collect::<Vec<_>>();
+
+        diff_lines(&left, &right)
+            .into_iter()
+            .map(|x| (x.old, x.new))
+            .unzip()
+    }
+}
@@ -25,6 +25,46 @@ pub enum EditorAction {
     Delete(usize, String),
 
     Insert(usize, String),
+
+    Replace(usize, String, String),
+
+    Group(Vec<EditorAction>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TextChange {
+
+    pub start: usize,
+
+    pub old_end: usize,
+
+    pub new_end: usize,
+
+    pub start_line: usize,
+
+    pub old_end_line: usize,
+
+    pub new_end_line: usize,
+
+    pub start_position: (usize, usize),
+
+    pub old_end_position: (usize, usize),
+
+    pub text: String,
+}
+
+#[derive(Clone, Default)]
+struct ViewState {
+
+    cursor: usize,
+
+    target_column: usize,
+
+    scroll_lines: usize,
+
+    scroll_columns: usize,
+
+    selection_anchor: Option<usize>,
 }
 
 pub struct TextEditor<L: LineLayout> {
@@ -52,6 +92,20 @@ pub struct TextEditor<L: LineLayout> {
     history_size: usize,
 
     tab_width: usize,
+
+    changes: Vec<TextChange>,
+
+    view: usize,
+
+    next_view: usize,
+
+    views: Vec<(usize, ViewState)>,
+}
+
+impl<L: LineLayout> std::fmt::Display for TextEditor<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
 }
 
 impl<L: LineLayout> TextEditor<L> {
@@ -70,10 +124,77 @@ impl<L: LineLayout> TextEditor<L> {
             current_history: 0,
             history_size: 16384,
             tab_width,
+            changes: Vec::new(),
+            view: 0,
+            next_view: 1,
+            views: Vec::new(),
+        }
+    }
+
+    pub fn get_view(&self) -> usize {
+        self.view
+    }
+
+    pub fn add_view(&mut self) -> usize {
+
+        let view = self.next_view;
+
+        self.next_view += 1;
+
+        self.views.push((view, self.store_view()));
+
+        view
+    }
+
+    pub fn remove_view(&mut self, view: usize) {
+
+        if view == self.view {
+
+            if let Some((next, state)) = self.views.pop() {
+                self.view = next;
+                self.load_view(state);
+            }
+        } else {
+            self.views.retain(|(x, _)| *x != view);
         }
     }
 
-    pub fn get_text(&self) -> RopeSlice {
+    pub fn set_view(&mut self, view: usize) {
+
+        let Some(index) = self.views.iter().position(|(x, _)| *x == view) else {
+            return;
+        };
+
+        let current = (self.view, self.store_view());
+
+        let (_, state) = std::mem::replace(&mut self.views[index], current);
+
+        self.view = view;
+        self.load_view(state);
+    }
+
+    fn store_view(&self) -> ViewState {
+        ViewState {
+            cursor: self.cursor,
+            target_column: self.target_column,
+            scroll_lines: self.scroll_lines,
+            scroll_columns: self.scroll_columns,
+            selection_anchor: self.selection_anchor,
+        }
+    }
+
+    fn load_view(&mut self, state: ViewState) {
+
+        let length = self.text.len_bytes();
+
+        self.cursor = state.cursor.min(length);
+        self.target_column = state.target_column;
+        self.scroll_lines = state.scroll_lines;
+        self.scroll_columns = state.scroll_columns;
+        self.selection_anchor = state.selection_anchor.map(|x| x.min(length));
+    }
+
+    pub fn get_text(&self) -> RopeSlice<'_> {
         self.text.slice(..)
     }
 
@@ -89,8 +210,10 @@ impl<L: LineLayout> TextEditor<L> {
         self.tab_width
     }
 
-    pub fn to_string(&self) -> String {
-        self.text.to_string()
+    pub fn set_layout_settings(&mut self, layout_settings: L, tab_width: usize) {
+        self.layout_settings = layout_settings;
+        self.tab_width = tab_width;
+        self.target_column = self.get_cursor_column();
     }
 
     pub fn len_lines(&self) -> usize {
@@ -105,6 +228,47 @@ impl<L: LineLayout> TextEditor<L> {
         self.text.byte_to_line(self.cursor)
     }
 
+    pub fn byte_to_position(&self, byte: usize) -> (usize, usize) {
+
+        let char_index = self.text.byte_to_char(byte.min(self.text.len_bytes()));
+
+        let line = self.text.char_to_line(char_index);
+
+        let character = self.text.char_to_utf16_cu(char_index)
+            - self.text.char_to_utf16_cu(self.text.line_to_char(line));
+
+        (line, character)
+    }
+
+    pub fn position_to_byte(&self, line: usize, character: usize) -> usize {
+
+        if line >= self.text.len_lines() {
+            return self.text.len_bytes();
+        }
+
+        let line_slice = self.text.line(line);
+
+        let mut line_end = line_slice.len_chars();
+
+        while line_end > 0 && is_newline(line_slice.char(line_end - 1)) {
+            line_end -= 1;
+        }
+
+        let character = character.min(line_slice.slice(..line_end).len_utf16_cu());
+
+        let line_start = self.text.line_to_char(line);
+
+        let char_index = self
+            .text
+            .utf16_cu_to_char(self.text.char_to_utf16_cu(line_start) + character);
+
+        self.text.char_to_byte(char_index)
+    }
+
+    pub fn take_changes(&mut self) -> Vec<TextChange> {
+        std::mem::take(&mut self.changes)
+    }
+
     pub fn set_saved(&mut self) {
         self.save_anchor = Some(self.current_history);
     }
@@ -140,14 +304,29 @@ impl<L: LineLayout> TextEditor<L> {
 
             let change = self.history[self.current_history].clone();
 
-            match change {
-                EditorAction::Delete(cursor, string) => {
+            self.undo_action(change);
+        }
+    }
+
+    fn undo_action(&mut self, change: EditorAction) {
+        match change {
+            EditorAction::Delete(cursor, string) => {
 
-                    self.insert_string(cursor, &string, false, true, true);
-                }
-                EditorAction::Insert(cursor, string) => {
+                self.insert_string(cursor, &string, false, true, true);
+            }
+            EditorAction::Insert(cursor, string) => {
 
-                    self.remove_range(cursor, cursor + string.len(), false, true, true);
+                self.remove_range(cursor, cursor + string.len(), false, true, true);
+            }
+            EditorAction::Replace(cursor, old, new) => {
+
+                self.remove_range(cursor, cursor + new.len(), false, false, true);
+                self.insert_string(cursor, &old, false, true, true);
+            }
+            EditorAction::Group(actions) => {
+
+                for action in actions.into_iter().rev() {
+                    self.undo_action(action);
                 }
             }
         }
@@ -161,19 +340,68 @@ impl<L: LineLayout> TextEditor<L> {
 
             self.current_history += 1;
 
-            match change {
-                EditorAction::Insert(cursor, string) => {
+            self.redo_action(change);
+        }
+    }
 
-                    self.insert_string(cursor, &string, false, true, true);
-                }
-                EditorAction::Delete(cursor, string) => {
+    fn redo_action(&mut self, change: EditorAction) {
+        match change {
+            EditorAction::Insert(cursor, string) => {
+
+                self.insert_string(cursor, &string, false, true, true);
+            }
+            EditorAction::Delete(cursor, string) => {
+
+                self.remove_range(cursor, cursor + string.len(), false, true, true);
+            }
+            EditorAction::Replace(cursor, old, new) => {
+
+                self.remove_range(cursor, cursor + old.len(), false, false, true);
+                self.insert_string(cursor, &new, false, true, true);
+            }
+            EditorAction::Group(actions) => {
 
-                    self.remove_range(cursor, cursor + string.len(), false, true, true);
+                for action in actions {
+                    self.redo_action(action);
                 }
             }
         }
     }
 
+    pub fn can_redo(&self) -> bool {
+        self.history.len() > self.current_history
+    }
+
+    pub fn get_history_position(&self) -> usize {
+        self.current_history
+    }
+
+    pub fn group_changes_since(&mut self, position: usize) {
+
+        let position = position.min(self.current_history);
+
+        if self.current_history - position < 2 {
+            return;
+        }
+
+        let actions = self
+            .history
+            .drain(position..self.current_history)
+            .collect::<Vec<_>>();
+
+        let removed = actions.len() - 1;
+
+        self.history.insert(position, EditorAction::Group(actions));
+
+        self.save_anchor = match self.save_anchor {
+            Some(x) if x <= position => Some(x),
+            Some(x) if x >= self.current_history => Some(x - removed),
+            _ => None,
+        };
+
+        self.current_history = position + 1;
+    }
+
     pub fn do_change(&mut self, change: EditorAction) {
 
         while self.history.len() > self.current_history {
@@ -424,8 +652,24 @@ impl<L: LineLayout> TextEditor<L> {
 
         let start_char = self.text.byte_to_char(start);
 
+        let start_line = self.text.byte_to_line(start);
+
+        let start_position = self.byte_to_position(start);
+
         self.text.insert(start_char, string);
 
+        self.changes.push(TextChange {
+            start,
+            old_end: start,
+            new_end: start + string.len(),
+            start_line,
+            old_end_line: start_line,
+            new_end_line: self.text.byte_to_line(start + string.len()),
+            start_position,
+            old_end_position: start_position,
+            text: string.to_string(),
+        });
+
         if record && !string.is_empty() {
             self.do_change(EditorAction::Insert(start, string.to_string()));
         }
@@ -435,6 +679,14 @@ impl<L: LineLayout> TextEditor<L> {
             self.cursor += string.len();
         }
 
+        for (_, view) in self.views.iter_mut() {
+            for byte in std::iter::once(&mut view.cursor).chain(view.selection_anchor.as_mut()) {
+                if *byte > start {
+                    *byte += string.len();
+                }
+            }
+        }
+
         if move_cursor_after {
 
             self.cursor = start + string.len();
@@ -461,8 +713,26 @@ impl<L: LineLayout> TextEditor<L> {
 
         let string = self.text.slice(start_char..end_char).to_string();
 
+        let start_line = self.text.byte_to_line(start);
+        let old_end_line = self.text.byte_to_line(end);
+
+        let start_position = self.byte_to_position(start);
+        let old_end_position = self.byte_to_position(end);
+
         self.text.remove(start_char..end_char);
 
+        self.changes.push(TextChange {
+            start,
+            old_end: end,
+            new_end: start,
+            start_line,
+            old_end_line,
+            new_end_line: start_line,
+            start_position,
+            old_end_position,
+            text: String::new(),
+        });
+
         if record && !string.is_empty() {
             self.do_change(EditorAction::Delete(start, string));
         }
@@ -475,6 +745,16 @@ impl<L: LineLayout> TextEditor<L> {
             self.cursor -= end - start;
         }
 
+        for (_, view) in self.views.iter_mut() {
+            for byte in std::iter::once(&mut view.cursor).chain(view.selection_anchor.as_mut()) {
+                if *byte >= start && *byte < end {
+                    *byte = start;
+                } else if *byte > start {
+                    *byte -= end - start;
+                }
+            }
+        }
+
         if move_cursor_after {
             self.cursor = start;
         }
@@ -549,6 +829,76 @@ impl<L: LineLayout> TextEditor<L> {
         }
     }
 
+    pub fn replace_range(&mut self, start: usize, end: usize, string: &str, move_cursor_after: bool) {
+
+        let old = self
+            .text
+            .slice(self.text.byte_to_char(start)..self.text.byte_to_char(end))
+            .to_string();
+
+        self.remove_range(start, end, false, false, move_cursor_after);
+
+        self.insert_string(start, string, false, true, move_cursor_after);
+
+        if old != string {
+            self.do_change(EditorAction::Replace(start, old, string.to_string()));
+        }
+    }
+
+    // swaps in new content, keeping each view's cursor on its line and column as far as they still exist
+    pub fn replace_text(&mut self, content: &str) {
+
+        let positions = std::iter::once(self.cursor)
+            .chain(self.views.iter().map(|(_, x)| x.cursor))
+            .map(|x| self.byte_to_position(x))
+            .collect::<Vec<_>>();
+
+        let length = self.text.len_bytes();
+
+        self.replace_range(0, length, content, false);
+
+        let mut cursors = positions
+            .into_iter()
+            .map(|(line, character)| self.position_to_byte(line, character))
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let last_line = self.text.len_lines() - 1;
+
+        self.set_cursor(cursors.next().unwrap_or(0));
+        self.scroll_lines = self.scroll_lines.min(last_line);
+
+        for ((_, view), cursor) in self.views.iter_mut().zip(cursors) {
+            view.cursor = cursor;
+            view.selection_anchor = None;
+            view.scroll_lines = view.scroll_lines.min(last_line);
+        }
+    }
+
+    pub fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, cursor: usize) {
+
+        self.clear_selection();
+
+        self.cursor = cursor.min(self.text.len_bytes());
+
+        self.target_column = self.get_cursor_column();
+    }
+
+    pub fn set_selection(&mut self, range: Range<usize>) {
+
+        let length = self.text.len_bytes();
+
+        self.set_cursor(range.end.min(length));
+
+        if range.start != range.end {
+            self.selection_anchor = Some(range.start.min(length));
+        }
+    }
+
     pub fn get_cursor_column(&self) -> usize {
 
         let line_num = self.text.byte_to_line(self.cursor);
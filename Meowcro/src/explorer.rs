@@ -0,0 +1,324 @@
+use crate::gitignore::Gitignore;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub path: PathBuf,
+
+    pub depth: usize,
+
+    pub is_dir: bool,
+
+    pub expanded: bool,
+}
+
+impl Entry {
+    pub fn get_label(&self) -> String {
+        let name = self
+            .path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let marker = match (self.is_dir, self.expanded) {
+            (true, true) => "- ",
+            (true, false) => "+ ",
+            (false, _) => "  ",
+        };
+
+        let suffix = if self.is_dir { "/" } else { "" };
+
+        format!("{}{marker}{name}{suffix}", "  ".repeat(self.depth))
+    }
+}
+
+pub struct Explorer {
+    root: PathBuf,
+
+    expanded: HashSet<PathBuf>,
+
+    entries: Vec<Entry>,
+
+    selected: usize,
+
+    scroll: usize,
+}
+
+impl Explorer {
+    pub fn new(root: PathBuf) -> Self {
+        let mut explorer = Self {
+            root,
+            expanded: HashSet::new(),
+            entries: Vec::new(),
+            selected: 0,
+            scroll: 0,
+        };
+
+        explorer.refresh();
+
+        explorer
+    }
+
+    pub fn get_entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_selected_entry(&self) -> Option<&Entry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn get_scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn refresh(&mut self) {
+        let selected = self.get_selected_entry().map(|x| x.path.clone());
+
+        self.expanded.retain(|x| x.is_dir());
+
+        self.entries.clear();
+
+        let ignore = Gitignore::default().for_directory(&self.root);
+
+        let root = self.root.clone();
+
+        self.collect_entries(&root, 0, &ignore);
+
+        match selected {
+            Some(x) => self.select_path(&x),
+            None => self.selected = 0,
+        }
+
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.selected = index;
+        }
+    }
+
+    pub fn select_path(&mut self, path: &Path) {
+        if let Some(index) = self.entries.iter().position(|x| x.path == path) {
+            self.selected = index;
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.entries.len().saturating_sub(1) as isize;
+
+        self.selected = (self.selected as isize + delta).clamp(0, last) as usize;
+    }
+
+    pub fn scroll_to_selected(&mut self, height: usize) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if height > 0 && self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+    }
+
+    pub fn activate(&mut self) -> Option<PathBuf> {
+        let entry = self.get_selected_entry()?.clone();
+
+        if !entry.is_dir {
+            return Some(entry.path);
+        }
+
+        if entry.expanded {
+            self.expanded.remove(&entry.path);
+        } else {
+            self.expanded.insert(entry.path);
+        }
+
+        self.refresh();
+
+        None
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(entry) = self.get_selected_entry().filter(|x| x.is_dir && !x.expanded) {
+            self.expanded.insert(entry.path.clone());
+            self.refresh();
+        }
+    }
+
+    pub fn collapse(&mut self) {
+        let Some(entry) = self.get_selected_entry().cloned() else {
+            return;
+        };
+
+        if entry.expanded {
+            self.expanded.remove(&entry.path);
+            self.refresh();
+        } else if let Some(parent) = entry.path.parent() {
+            self.select_path(parent);
+        }
+    }
+
+    pub fn get_relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<PathBuf, String> {
+        let directory = self.target_directory();
+
+        let path = directory.join(name.trim_end_matches('/'));
+
+        if path.exists() {
+            return Err(format!("{} already exists", self.get_relative_path(&path)));
+        }
+
+        if name.ends_with('/') {
+            std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+
+            std::fs::File::create_new(&path).map_err(|e| e.to_string())?;
+        }
+
+        self.reveal(&path);
+
+        Ok(path)
+    }
+
+    pub fn get_rename_target(&self, name: &str) -> Result<PathBuf, String> {
+        Ok(self.selected_path()?.with_file_name(name))
+    }
+
+    pub fn get_move_target(&self, destination: &str) -> Result<PathBuf, String> {
+        let from = self.selected_path()?;
+
+        let to = self.root.join(destination);
+
+        if to.is_dir() {
+            Ok(to.join(from.file_name().unwrap_or_default()))
+        } else {
+            Ok(to)
+        }
+    }
+
+    pub fn rename(&mut self, name: &str, overwrite: bool) -> Result<(PathBuf, PathBuf), String> {
+        let to = self.get_rename_target(name)?;
+
+        self.move_path(self.selected_path()?, to, overwrite)
+    }
+
+    pub fn move_to(&mut self, destination: &str, overwrite: bool) -> Result<(PathBuf, PathBuf), String> {
+        let to = self.get_move_target(destination)?;
+
+        self.move_path(self.selected_path()?, to, overwrite)
+    }
+
+    pub fn delete(&mut self) -> Result<PathBuf, String> {
+        let path = self.selected_path()?;
+
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path).map_err(|e| e.to_string())?;
+        } else {
+            std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+
+        self.refresh();
+
+        Ok(path)
+    }
+
+    fn selected_path(&self) -> Result<PathBuf, String> {
+        self.get_selected_entry()
+            .map(|x| x.path.clone())
+            .ok_or_else(|| "no file selected".to_string())
+    }
+
+    fn target_directory(&self) -> PathBuf {
+        match self.get_selected_entry() {
+            Some(x) if x.is_dir => x.path.clone(),
+            Some(x) => x.path.parent().unwrap_or(&self.root).to_path_buf(),
+            None => self.root.clone(),
+        }
+    }
+
+    fn move_path(&mut self, from: PathBuf, to: PathBuf, overwrite: bool) -> Result<(PathBuf, PathBuf), String> {
+        if to.exists() && (!overwrite || to.is_dir()) {
+            return Err(format!("{} already exists", self.get_relative_path(&to)));
+        }
+
+        if to.starts_with(&from) {
+            return Err(format!(
+                "can't move {} into itself",
+                self.get_relative_path(&from)
+            ));
+        }
+
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        std::fs::rename(&from, &to).map_err(|e| e.to_string())?;
+
+        self.expanded = self
+            .expanded
+            .drain()
+            .map(|x| match x.strip_prefix(&from) {
+                Ok(rest) => to.join(rest),
+                Err(_) => x,
+            })
+            .collect();
+
+        self.reveal(&to);
+
+        Ok((from, to))
+    }
+
+    fn reveal(&mut self, path: &Path) {
+        for ancestor in path.ancestors().skip(1) {
+            if !ancestor.starts_with(&self.root) || ancestor == self.root {
+                break;
+            }
+
+            self.expanded.insert(ancestor.to_path_buf());
+        }
+
+        self.refresh();
+        self.select_path(path);
+    }
+
+    fn collect_entries(&mut self, directory: &Path, depth: usize, ignore: &Gitignore) {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return;
+        };
+
+        let mut entries = entries
+            .filter_map(Result::ok)
+            .map(|x| (x.path(), x.path().is_dir()))
+            .filter(|(path, is_dir)| !ignore.is_ignored(path, *is_dir))
+            .collect::<Vec<_>>();
+
+        entries.sort_by(|(a, a_dir), (b, b_dir)| b_dir.cmp(a_dir).then_with(|| a.cmp(b)));
+
+        for (path, is_dir) in entries {
+            let expanded = is_dir && self.expanded.contains(&path);
+
+            self.entries.push(Entry {
+                path: path.clone(),
+                depth,
+                is_dir,
+                expanded,
+            });
+
+            if expanded {
+                self.collect_entries(&path, depth + 1, &ignore.for_directory(&path));
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+const EXTENSIONS: &[(&str, &[&str])] = &[
+    ("rust", &["rs"]),
+    ("python", &["py", "pyw"]),
+    ("shell", &["sh", "bash", "zsh"]),
+    ("markdown", &["md", "markdown"]),
+    ("json", &["json"]),
+    ("toml", &["toml"]),
+    ("yaml", &["yaml", "yml"]),
+    ("meowscript", &["meow"]),
+    ("c", &["c", "h"]),
+    ("cpp", &["cpp", "cc", "cxx", "hpp", "hh"]),
+    ("javascript", &["js", "mjs", "cjs"]),
+    ("typescript", &["ts", "tsx"]),
+    ("html", &["html", "htm"]),
+    ("css", &["css"]),
+    ("go", &["go"]),
+    ("text", &["txt"]),
+];
+
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("Cargo.lock", "toml"),
+    ("Makefile", "make"),
+    (".bashrc", "shell"),
+    (".zshrc", "shell"),
+    (".profile", "shell"),
+];
+
+pub fn detect(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+
+    if let Some((_, filetype)) = FILE_NAMES.iter().find(|(x, _)| *x == name) {
+        return Some(filetype);
+    }
+
+    let extension = path.extension()?.to_str()?.to_lowercase();
+
+    EXTENSIONS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map(|(filetype, _)| *filetype)
+}
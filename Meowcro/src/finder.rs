@@ -0,0 +1,203 @@
+use crate::fuzzy::path_score;
+use crate::gitignore::{is_binary, walk};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+
+const BATCH_SIZE: usize = 256;
+
+const PREVIEW_LENGTH: u64 = 64 * 1024;
+
+const MAX_PREVIEW_LINES: usize = 200;
+
+pub struct FileFinder {
+    root: PathBuf,
+
+    files: Vec<String>,
+
+    receiver: Option<Receiver<Vec<String>>>,
+
+    query: String,
+
+    matches: Vec<usize>,
+
+    selected: usize,
+
+    preview: Vec<String>,
+}
+
+impl FileFinder {
+    pub fn new(root: PathBuf) -> Self {
+        let (sender, receiver) = channel();
+
+        let walk_root = root.clone();
+
+        std::thread::spawn(move || {
+            let mut batch = Vec::new();
+
+            walk(&walk_root, &mut |path| {
+                if is_binary(&path) {
+                    return;
+                }
+
+                let relative = path.strip_prefix(&walk_root).unwrap_or(&path);
+
+                batch.push(relative.to_string_lossy().replace('\\', "/"));
+
+                if batch.len() >= BATCH_SIZE {
+                    let _ = sender.send(std::mem::take(&mut batch));
+                }
+            });
+
+            let _ = sender.send(batch);
+        });
+
+        Self {
+            root,
+            files: Vec::new(),
+            receiver: Some(receiver),
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            preview: Vec::new(),
+        }
+    }
+
+    pub fn poll(&mut self) -> bool {
+        let Some(receiver) = self.receiver.as_ref() else {
+            return false;
+        };
+
+        let mut changed = false;
+
+        loop {
+            match receiver.try_recv() {
+                Ok(x) => {
+                    self.files.extend(x);
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.receiver = None;
+                    changed = true;
+                    break;
+                }
+            }
+        }
+
+        if changed {
+            self.update_matches(true);
+        }
+
+        changed
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.update_matches(false);
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.update_matches(false);
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+
+        self.selected = (self.selected as isize + delta).rem_euclid(self.matches.len() as isize) as usize;
+        self.update_preview();
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_selected_path(&self) -> Option<PathBuf> {
+        self.matches
+            .get(self.selected)
+            .map(|x| self.root.join(&self.files[*x]))
+    }
+
+    pub fn get_matches(&self) -> Vec<&str> {
+        self.matches.iter().map(|x| self.files[*x].as_str()).collect()
+    }
+
+    pub fn get_status(&self) -> String {
+        let status = format!("{}/{}", self.matches.len(), self.files.len());
+
+        if self.is_loading() {
+            format!("{status}...")
+        } else {
+            status
+        }
+    }
+
+    pub fn get_preview(&self) -> &[String] {
+        &self.preview
+    }
+
+    fn update_matches(&mut self, keep_selection: bool) {
+        let selected = self
+            .matches
+            .get(self.selected)
+            .copied()
+            .filter(|_| keep_selection);
+
+        let mut matches = self
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(index, x)| Some((path_score(&self.query, x)?, index)))
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| self.files[*a].len().cmp(&self.files[*b].len()))
+                .then_with(|| self.files[*a].cmp(&self.files[*b]))
+        });
+
+        self.matches = matches.into_iter().map(|(_, x)| x).collect();
+
+        self.selected = selected
+            .and_then(|x| self.matches.iter().position(|y| *y == x))
+            .unwrap_or(0);
+
+        self.update_preview();
+    }
+
+    fn update_preview(&mut self) {
+        self.preview = self
+            .get_selected_path()
+            .map(|x| read_preview(&x))
+            .unwrap_or_default();
+    }
+}
+
+fn read_preview(path: &Path) -> Vec<String> {
+    let mut content = Vec::new();
+
+    if std::fs::File::open(path)
+        .and_then(|x| x.take(PREVIEW_LENGTH).read_to_end(&mut content))
+        .is_err()
+    {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&content)
+        .lines()
+        .take(MAX_PREVIEW_LINES)
+        .map(|x| x.replace('\t', "    "))
+        .collect()
+}
@@ -0,0 +1,70 @@
+const MATCH_BONUS: i64 = 1;
+
+const START_BONUS: i64 = 8;
+
+const BOUNDARY_BONUS: i64 = 6;
+
+const CONSECUTIVE_BONUS: i64 = 5;
+
+const CASE_BONUS: i64 = 1;
+
+const GAP_PENALTY: i64 = 1;
+
+const MAX_GAP_PENALTY: i64 = 5;
+
+const BASENAME_BONUS: i64 = 10;
+
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    let candidate = candidate.chars().collect::<Vec<_>>();
+
+    let mut score = 0;
+    let mut index = 0;
+    let mut last_match: Option<usize> = None;
+
+    for p in pattern.chars() {
+        let found = (index..candidate.len())
+            .find(|x| candidate[*x].to_lowercase().eq(p.to_lowercase()))?;
+
+        score += MATCH_BONUS;
+
+        let gap = match last_match {
+            Some(x) => found - x - 1,
+            None => found,
+        };
+
+        if found == 0 {
+            score += START_BONUS;
+        } else if gap == 0 && last_match.is_some() {
+            score += CONSECUTIVE_BONUS;
+        } else {
+            let previous = candidate[found - 1];
+
+            if !previous.is_alphanumeric()
+                || (previous.is_lowercase() && candidate[found].is_uppercase())
+            {
+                score += BOUNDARY_BONUS;
+            }
+
+            score -= (GAP_PENALTY * gap as i64).min(MAX_GAP_PENALTY);
+        }
+
+        if candidate[found] == p {
+            score += CASE_BONUS;
+        }
+
+        last_match = Some(found);
+        index = found + 1;
+    }
+
+    Some(score)
+}
+
+pub fn path_score(pattern: &str, path: &str) -> Option<i64> {
+    let score = fuzzy_score(pattern, path)?;
+
+    let name = path.rsplit('/').next().unwrap_or(path);
+
+    let name_score = fuzzy_score(pattern, name).map(|x| x + BASENAME_BONUS);
+
+    Some(name_score.map_or(score, |x| x.max(score)))
+}
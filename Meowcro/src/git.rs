@@ -0,0 +1,143 @@
+use crate::diff::{diff_lines, Hunk};
+use ropey::RopeSlice;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChangeKind {
+    Added,
+
+    Modified,
+
+    Deleted,
+}
+
+fn get_anchor(hunk: &Hunk) -> usize {
+    if hunk.new.is_empty() {
+        hunk.new.start.saturating_sub(1)
+    } else {
+        hunk.new.start
+    }
+}
+
+pub struct GitChanges {
+    base: Vec<String>,
+
+    hunks: Vec<Hunk>,
+
+    outdated: bool,
+}
+
+impl GitChanges {
+    pub fn new(base: &str) -> Self {
+        Self {
+            base: base.split_inclusive('\n').map(str::to_string).collect(),
+            hunks: Vec::new(),
+            outdated: true,
+        }
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        let path = std::path::absolute(path).ok()?;
+
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path.parent()?)
+            .arg("show")
+            .arg(format!("HEAD:./{}", path.file_name()?.to_str()?))
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        Some(Self::new(&String::from_utf8(output.stdout).ok()?))
+    }
+
+    pub fn mark_outdated(&mut self) {
+        self.outdated = true;
+    }
+
+    pub fn update(&mut self, text: RopeSlice) {
+        if !self.outdated {
+            return;
+        }
+
+        let lines = text
+            .lines()
+            .map(|x| x.to_string())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>();
+
+        self.hunks = diff_lines(&self.base, &lines);
+        self.outdated = false;
+    }
+
+    pub fn get_hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+
+    pub fn get_markers(&self) -> Vec<(usize, ChangeKind)> {
+        let mut markers = Vec::new();
+
+        for hunk in &self.hunks {
+            if hunk.new.is_empty() {
+                markers.push((get_anchor(hunk), ChangeKind::Deleted));
+            } else {
+                let kind = if hunk.old.is_empty() {
+                    ChangeKind::Added
+                } else {
+                    ChangeKind::Modified
+                };
+
+                markers.extend(hunk.new.clone().map(|x| (x, kind)));
+            }
+        }
+
+        markers
+    }
+
+    pub fn get_hunk_at(&self, line: usize) -> Option<&Hunk> {
+        self.hunks
+            .iter()
+            .find(|x| x.new.contains(&line) || (x.new.is_empty() && get_anchor(x) == line))
+    }
+
+    pub fn get_next_hunk(&self, line: usize) -> Option<usize> {
+        self.hunks
+            .iter()
+            .map(get_anchor)
+            .find(|x| *x > line)
+            .or_else(|| self.hunks.first().map(get_anchor))
+    }
+
+    pub fn get_previous_hunk(&self, line: usize) -> Option<usize> {
+        self.hunks
+            .iter()
+            .map(get_anchor)
+            .rfind(|x| *x < line)
+            .or_else(|| self.hunks.last().map(get_anchor))
+    }
+
+    pub fn get_base_text(&self, hunk: &Hunk) -> String {
+        self.base[hunk.old.clone()].concat()
+    }
+
+    pub fn get_preview(&self, hunk: &Hunk, text: RopeSlice) -> Vec<String> {
+        let line_text = |x: &str| x.trim_end_matches(['\r', '\n']).to_string();
+
+        let removed = self.base[hunk.old.clone()]
+            .iter()
+            .map(|x| format!("- {}", line_text(x)));
+
+        let added = hunk
+            .new
+            .clone()
+            .map(|x| format!("+ {}", line_text(&text.line(x).to_string())));
+
+        removed.chain(added).collect()
+    }
+}
@@ -0,0 +1,186 @@
+use regex::Regex;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const BINARY_CHECK_LENGTH: u64 = 8000;
+
+#[derive(Clone)]
+struct Rule {
+    base: PathBuf,
+
+    regex: Regex,
+
+    negated: bool,
+
+    directory_only: bool,
+}
+
+#[derive(Clone)]
+pub struct Glob {
+    regex: Regex,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        pattern_regex(pattern)
+            .map(|regex| Self { regex })
+            .ok_or_else(|| format!("invalid glob `{pattern}`"))
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.regex.is_match(path)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Gitignore {
+    rules: Vec<Rule>,
+}
+
+impl Gitignore {
+    pub fn for_directory(&self, directory: &Path) -> Self {
+        match std::fs::read_to_string(directory.join(".gitignore")) {
+            Ok(x) => self.with_rules(directory, &x),
+            Err(_) => self.clone(),
+        }
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if path.file_name().is_some_and(|x| x == ".git") {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .rev()
+            .filter(|x| is_dir || !x.directory_only)
+            .find(|x| {
+                path.strip_prefix(&x.base).is_ok_and(|relative| {
+                    x.regex
+                        .is_match(&relative.to_string_lossy().replace('\\', "/"))
+                })
+            })
+            .is_some_and(|x| !x.negated)
+    }
+
+    fn with_rules(&self, base: &Path, content: &str) -> Self {
+        let mut rules = self.rules.clone();
+
+        rules.extend(content.lines().filter_map(|x| parse_rule(base, x)));
+
+        Self { rules }
+    }
+}
+
+fn parse_rule(base: &Path, line: &str) -> Option<Rule> {
+    let line = line.trim_end();
+
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(x) => (true, x),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+
+    let (directory_only, pattern) = match pattern.strip_suffix('/') {
+        Some(x) => (true, x),
+        None => (false, pattern),
+    };
+
+    pattern_regex(pattern).map(|regex| Rule {
+        base: base.to_path_buf(),
+        regex,
+        negated,
+        directory_only,
+    })
+}
+
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+
+    Regex::new(&format!("{prefix}{}$", glob_to_regex(pattern))).ok()
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let class = chars.by_ref().take_while(|x| *x != ']').collect::<String>();
+
+                let class = match class.strip_prefix('!') {
+                    Some(x) => format!("^{}", x.replace('\\', "\\\\")),
+                    None => class.replace('\\', "\\\\"),
+                };
+
+                regex.push_str(&format!("[{class}]"));
+            }
+            '\\' => {
+                if let Some(x) = chars.next() {
+                    regex.push_str(&regex::escape(&x.to_string()));
+                }
+            }
+            x => regex.push_str(&regex::escape(&x.to_string())),
+        }
+    }
+
+    regex
+}
+
+pub fn walk(root: &Path, visit: &mut impl FnMut(PathBuf)) {
+    walk_directory(root, &Gitignore::default().for_directory(root), visit);
+}
+
+fn walk_directory(directory: &Path, ignore: &Gitignore, visit: &mut impl FnMut(PathBuf)) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    let mut entries = entries
+        .filter_map(Result::ok)
+        .map(|x| (x.path(), x.file_type().is_ok_and(|x| x.is_dir())))
+        .filter(|(path, is_dir)| !ignore.is_ignored(path, *is_dir))
+        .collect::<Vec<_>>();
+
+    entries.sort();
+
+    for (path, is_dir) in entries {
+        if is_dir {
+            walk_directory(&path, &ignore.for_directory(&path), visit);
+        } else {
+            visit(path);
+        }
+    }
+}
+
+pub fn is_binary(path: &Path) -> bool {
+    let mut content = Vec::new();
+
+    std::fs::File::open(path)
+        .and_then(|x| x.take(BINARY_CHECK_LENGTH).read_to_end(&mut content))
+        .map_or(true, |_| content.contains(&0))
+}
@@ -0,0 +1,330 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::path::Path;
+
+const MAX_HISTORY: usize = 50;
+
+const MAX_SHOWN_COMPLETIONS: usize = 8;
+
+pub type Completer = fn(&str) -> Vec<String>;
+
+pub type Validator = fn(&str) -> Result<(), String>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputResult {
+    Confirmed(String),
+
+    Cancelled,
+
+    Edited,
+
+    Ignored,
+}
+
+pub struct InputLine {
+    text: String,
+
+    cursor: usize,
+
+    history: Vec<String>,
+
+    history_index: Option<usize>,
+
+    draft: String,
+
+    completer: Option<Completer>,
+
+    validator: Option<Validator>,
+
+    error: Option<String>,
+
+    hint: Option<String>,
+}
+
+impl InputLine {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            completer: None,
+            validator: None,
+            error: None,
+            hint: None,
+        }
+    }
+
+    pub fn with_history(mut self, history: &[String]) -> Self {
+        self.history = history.to_vec();
+        self
+    }
+
+    pub fn with_completion(mut self, completer: Completer) -> Self {
+        self.completer = Some(completer);
+        self
+    }
+
+    pub fn with_validation(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self.validate();
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn get_hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.text.chars().count());
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> InputResult {
+        let words = modifiers.contains(KeyModifiers::CONTROL);
+
+        self.hint = None;
+
+        match code {
+            KeyCode::Enter if self.error.is_none() => {
+                return InputResult::Confirmed(self.text.clone());
+            }
+            KeyCode::Enter => return InputResult::Ignored,
+            KeyCode::Esc => return InputResult::Cancelled,
+            KeyCode::Char('u') if words => {
+                let end = self.byte_index(self.cursor);
+
+                self.text.replace_range(..end, "");
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) if !words => {
+                let index = self.byte_index(self.cursor);
+
+                self.text.insert(index, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                let start = if words { self.word_start() } else { self.cursor - 1 };
+
+                let range = self.byte_index(start)..self.byte_index(self.cursor);
+
+                self.text.replace_range(range, "");
+                self.cursor = start;
+            }
+            KeyCode::Delete if self.cursor < self.text.chars().count() => {
+                let range = self.byte_index(self.cursor)..self.byte_index(self.cursor + 1);
+
+                self.text.replace_range(range, "");
+            }
+            KeyCode::Left if words => {
+                self.cursor = self.word_start();
+                return InputResult::Ignored;
+            }
+            KeyCode::Right if words => {
+                self.cursor = self.word_end();
+                return InputResult::Ignored;
+            }
+            KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                return InputResult::Ignored;
+            }
+            KeyCode::Right => {
+                self.set_cursor(self.cursor + 1);
+                return InputResult::Ignored;
+            }
+            KeyCode::Home => {
+                self.cursor = 0;
+                return InputResult::Ignored;
+            }
+            KeyCode::End => {
+                self.set_cursor(usize::MAX);
+                return InputResult::Ignored;
+            }
+            KeyCode::Up => self.recall(-1),
+            KeyCode::Down => self.recall(1),
+            KeyCode::Tab => self.complete(),
+            _ => return InputResult::Ignored,
+        }
+
+        self.validate();
+
+        InputResult::Edited
+    }
+
+    fn recall(&mut self, delta: isize) {
+        let index = match self.history_index {
+            Some(x) => x as isize + delta,
+            None if delta < 0 => self.history.len() as isize - 1,
+            None => return,
+        };
+
+        if index < 0 {
+            return;
+        }
+
+        if self.history_index.is_none() {
+            self.draft = self.text.clone();
+        }
+
+        match self.history.get(index as usize) {
+            Some(x) => {
+                self.history_index = Some(index as usize);
+                self.text = x.clone();
+            }
+            None => {
+                self.history_index = None;
+                self.text = std::mem::take(&mut self.draft);
+            }
+        }
+
+        self.cursor = self.text.chars().count();
+    }
+
+    fn complete(&mut self) {
+        let Some(completer) = self.completer else {
+            return;
+        };
+
+        let candidates = completer(&self.text);
+
+        let Some(first) = candidates.first() else {
+            self.hint = Some("no completions".to_string());
+            return;
+        };
+
+        let prefix = candidates.iter().skip(1).fold(first.as_str(), |prefix, x| {
+            let length = prefix
+                .char_indices()
+                .zip(x.chars())
+                .find(|((_, a), b)| a != b)
+                .map(|((index, _), _)| index)
+                .unwrap_or(prefix.len().min(x.len()));
+
+            &prefix[..length]
+        });
+
+        if prefix.len() > self.text.len() {
+            self.text = prefix.to_string();
+            self.cursor = self.text.chars().count();
+        }
+
+        if candidates.len() > 1 {
+            let names = candidates
+                .iter()
+                .take(MAX_SHOWN_COMPLETIONS)
+                .map(|x| x.trim_end_matches('/').rsplit('/').next().unwrap_or(x))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            self.hint = Some(if candidates.len() > MAX_SHOWN_COMPLETIONS {
+                format!("{names} ...")
+            } else {
+                names
+            });
+        }
+    }
+
+    fn validate(&mut self) {
+        self.error = self.validator.and_then(|x| x(&self.text).err());
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(cursor)
+            .map(|(x, _)| x)
+            .unwrap_or(self.text.len())
+    }
+
+    fn word_start(&self) -> usize {
+        let chars = self.text.chars().collect::<Vec<_>>();
+
+        let mut index = self.cursor;
+
+        while index > 0 && !chars[index - 1].is_alphanumeric() {
+            index -= 1;
+        }
+
+        while index > 0 && chars[index - 1].is_alphanumeric() {
+            index -= 1;
+        }
+
+        index
+    }
+
+    fn word_end(&self) -> usize {
+        let chars = self.text.chars().collect::<Vec<_>>();
+
+        let mut index = self.cursor;
+
+        while index < chars.len() && !chars[index].is_alphanumeric() {
+            index += 1;
+        }
+
+        while index < chars.len() && chars[index].is_alphanumeric() {
+            index += 1;
+        }
+
+        index
+    }
+}
+
+pub fn add_to_history(history: &mut Vec<String>, entry: &str) {
+    if entry.is_empty() {
+        return;
+    }
+
+    history.retain(|x| x != entry);
+    history.push(entry.to_string());
+
+    if history.len() > MAX_HISTORY {
+        history.remove(0);
+    }
+}
+
+pub fn complete_path(input: &str) -> Vec<String> {
+    let (directory, prefix) = match input.rfind('/') {
+        Some(x) => (&input[..x + 1], &input[x + 1..]),
+        None => ("", input),
+    };
+
+    let path = if directory.is_empty() {
+        Path::new(".")
+    } else {
+        Path::new(directory)
+    };
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    let mut candidates = entries
+        .filter_map(Result::ok)
+        .filter_map(|x| {
+            let name = x.file_name().to_string_lossy().to_string();
+
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+
+            let suffix = if x.path().is_dir() { "/" } else { "" };
+
+            Some(format!("{directory}{name}{suffix}"))
+        })
+        .collect::<Vec<_>>();
+
+    candidates.sort();
+
+    candidates
+}
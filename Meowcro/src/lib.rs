@@ -0,0 +1,33 @@
+pub mod buffer;
+pub mod clipboard;
+pub mod commands;
+pub mod completion;
+pub mod config;
+pub mod conflict;
+pub mod diff;
+pub mod diffview;
+pub mod dialog;
+pub mod editor;
+pub mod explorer;
+pub mod filetype;
+pub mod git;
+pub mod finder;
+pub mod fuzzy;
+pub mod gitignore;
+pub mod input;
+pub mod lsp;
+pub mod panes;
+pub mod replace;
+pub mod save;
+pub mod search;
+pub mod snippets;
+pub mod statusline;
+pub mod syntax;
+pub mod terminal;
+pub mod theme;
+pub mod ui;
+pub mod unicode;
+pub mod watcher;
+pub mod widgets;
+pub mod widgets_impl;
+pub mod words;
@@ -0,0 +1,800 @@
+use ropey::RopeSlice;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::{Duration, Instant};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+
+    pub character: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, character: usize) -> Self {
+        Self { line, character }
+    }
+
+    fn from_json(value: &Value) -> Self {
+        Self {
+            line: value["line"].as_u64().unwrap_or(0) as usize,
+            character: value["character"].as_u64().unwrap_or(0) as usize,
+        }
+    }
+
+    fn to_json(self) -> Value {
+        json!({ "line": self.line, "character": self.character })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentChange {
+    pub start: Position,
+
+    pub end: Position,
+
+    pub text: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub start: Position,
+
+    pub end: Position,
+
+    pub severity: Severity,
+
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: Position,
+
+    pub end: Position,
+
+    pub text: String,
+}
+
+impl TextEdit {
+    fn from_json(value: &Value) -> Self {
+        Self {
+            start: Position::from_json(&value["range"]["start"]),
+            end: Position::from_json(&value["range"]["end"]),
+            text: value["newText"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CompletionItem {
+    pub label: String,
+
+    pub detail: Option<String>,
+
+    pub insert_text: String,
+
+    pub edit: Option<TextEdit>,
+}
+
+#[derive(Clone, Debug)]
+pub enum LspEvent {
+    Diagnostics(String),
+    Completion(String, Vec<CompletionItem>),
+    Hover(String, String),
+    Definition(String, String, Position),
+    Rename(HashMap<String, Vec<TextEdit>>),
+    Message(String),
+    Log(String),
+}
+
+// answers are handed back with the document they were asked for
+enum Request {
+    Initialize,
+    Completion(String),
+    Hover(String),
+    Definition(String),
+    Rename,
+    Shutdown,
+}
+
+// how the server wants `didChange` to describe an edit
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum SyncKind {
+    None,
+    Full,
+    Incremental,
+}
+
+impl SyncKind {
+    fn from_json(value: &Value) -> Self {
+        // a server that doesn't say wants no changes at all
+        match value.as_u64().or_else(|| value["change"].as_u64()) {
+            Some(1) => Self::Full,
+            Some(2) => Self::Incremental,
+            _ => Self::None,
+        }
+    }
+}
+
+pub struct LanguageServer {
+    command: String,
+
+    child: Child,
+
+    stdin: ChildStdin,
+
+    messages: Receiver<Value>,
+
+    next_id: i64,
+
+    pending: HashMap<i64, Request>,
+
+    initialized: bool,
+
+    queued: Vec<Value>,
+
+    sync_kind: SyncKind,
+
+    trigger_characters: Vec<char>,
+
+    diagnostics: HashMap<String, Vec<Diagnostic>>,
+
+    running: bool,
+}
+
+impl LanguageServer {
+    pub fn start(command: &str, args: &[String], root: &Path) -> Result<Self, String> {
+        let mut child = Command::new(command)
+            .args(args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("failed to start language server `{command}`: {e}"))?;
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        let (sender, messages) = channel();
+
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+
+            while let Ok(Some(message)) = read_message(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut server = Self {
+            command: command.to_string(),
+            child,
+            stdin,
+            messages,
+            next_id: 1,
+            pending: HashMap::new(),
+            initialized: false,
+            queued: Vec::new(),
+            sync_kind: SyncKind::None,
+            trigger_characters: Vec::new(),
+            diagnostics: HashMap::new(),
+            running: true,
+        };
+
+        let root_uri = path_to_uri(root);
+
+        let params = json!({
+            "processId": std::process::id(),
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": root.to_string_lossy() }],
+            "capabilities": {
+                "general": { "positionEncodings": ["utf-16"] },
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "publishDiagnostics": {},
+                    "completion": { "completionItem": { "snippetSupport": false } },
+                    "hover": { "contentFormat": ["plaintext", "markdown"] },
+                    "definition": {},
+                    "rename": {},
+                },
+            },
+        });
+
+        server.send_request("initialize", params, Request::Initialize, true)?;
+
+        Ok(server)
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn trigger_characters(&self) -> &[char] {
+        &self.trigger_characters
+    }
+
+    pub fn diagnostics(&self, uri: &str) -> &[Diagnostic] {
+        self.diagnostics.get(uri).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn write(&mut self, message: &Value) -> Result<(), String> {
+        let content = message.to_string();
+
+        write!(
+            self.stdin,
+            "Content-Length: {}\r\n\r\n{content}",
+            content.len()
+        )
+        .and_then(|_| self.stdin.flush())
+        .map_err(|e| {
+            self.running = false;
+            format!("language server `{}` stopped: {e}", self.command)
+        })
+    }
+
+    fn send(&mut self, message: Value, before_initialized: bool) -> Result<(), String> {
+        if !self.running {
+            Err(format!("language server `{}` isn't running", self.command))
+        } else if self.initialized || before_initialized {
+            self.write(&message)
+        } else {
+            self.queued.push(message);
+            Ok(())
+        }
+    }
+
+    fn send_request(
+        &mut self,
+        method: &str,
+        params: Value,
+        request: Request,
+        before_initialized: bool,
+    ) -> Result<i64, String> {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.pending.insert(id, request);
+
+        self.send(
+            json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+            before_initialized,
+        )?;
+
+        Ok(id)
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<(), String> {
+        self.send(
+            json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+            false,
+        )
+    }
+
+    pub fn did_open(
+        &mut self,
+        uri: &str,
+        language_id: &str,
+        version: i64,
+        text: &str,
+    ) -> Result<(), String> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": uri,
+                    "languageId": language_id,
+                    "version": version,
+                    "text": text,
+                },
+            }),
+        )
+    }
+
+    pub fn did_change(
+        &mut self,
+        uri: &str,
+        version: i64,
+        changes: &[ContentChange],
+        text: RopeSlice,
+    ) -> Result<(), String> {
+        // until the server says how it wants changes, the queued `didOpen` just gets the new text
+        if !self.initialized {
+            let open = self.queued.iter_mut().find(|x| {
+                x["method"] == "textDocument/didOpen" && x["params"]["textDocument"]["uri"] == uri
+            });
+
+            if let Some(open) = open {
+                open["params"]["textDocument"]["version"] = json!(version);
+                open["params"]["textDocument"]["text"] = json!(text.to_string());
+
+                return Ok(());
+            }
+        }
+
+        let changes = match self.sync_kind {
+            SyncKind::None => return Ok(()),
+            SyncKind::Full => vec![json!({ "text": text.to_string() })],
+            SyncKind::Incremental => changes
+                .iter()
+                .map(|x| {
+                    json!({
+                        "range": { "start": x.start.to_json(), "end": x.end.to_json() },
+                        "text": x.text,
+                    })
+                })
+                .collect(),
+        };
+
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri, "version": version },
+                "contentChanges": changes,
+            }),
+        )
+    }
+
+    pub fn did_save(&mut self, uri: &str) -> Result<(), String> {
+        self.notify(
+            "textDocument/didSave",
+            json!({ "textDocument": { "uri": uri } }),
+        )
+    }
+
+    pub fn did_close(&mut self, uri: &str) -> Result<(), String> {
+        self.diagnostics.remove(uri);
+
+        self.notify(
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": uri } }),
+        )
+    }
+
+    fn position_params(uri: &str, position: Position) -> Value {
+        json!({ "textDocument": { "uri": uri }, "position": position.to_json() })
+    }
+
+    pub fn completion(&mut self, uri: &str, position: Position) -> Result<(), String> {
+        let params = Self::position_params(uri, position);
+
+        self.send_request(
+            "textDocument/completion",
+            params,
+            Request::Completion(uri.to_string()),
+            false,
+        )
+        .map(|_| ())
+    }
+
+    pub fn hover(&mut self, uri: &str, position: Position) -> Result<(), String> {
+        let params = Self::position_params(uri, position);
+
+        self.send_request("textDocument/hover", params, Request::Hover(uri.to_string()), false)
+            .map(|_| ())
+    }
+
+    pub fn definition(&mut self, uri: &str, position: Position) -> Result<(), String> {
+        let params = Self::position_params(uri, position);
+
+        self.send_request(
+            "textDocument/definition",
+            params,
+            Request::Definition(uri.to_string()),
+            false,
+        )
+        .map(|_| ())
+    }
+
+    pub fn rename(&mut self, uri: &str, position: Position, new_name: &str) -> Result<(), String> {
+        let mut params = Self::position_params(uri, position);
+        params["newName"] = json!(new_name);
+
+        self.send_request("textDocument/rename", params, Request::Rename, false)
+            .map(|_| ())
+    }
+
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let mut events = Vec::new();
+
+        loop {
+            match self.messages.try_recv() {
+                Ok(message) => events.extend(self.handle(message)),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.running {
+                        self.running = false;
+                        events.push(LspEvent::Message(format!(
+                            "language server `{}` exited",
+                            self.command
+                        )));
+                    }
+
+                    break;
+                }
+            }
+        }
+
+        events
+    }
+
+    fn handle(&mut self, message: Value) -> Option<LspEvent> {
+        if let Some(method) = message["method"].as_str() {
+            let params = &message["params"];
+
+            if let Some(id) = message.get("id") {
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": Value::Null,
+                });
+
+                self.write(&response).ok();
+            }
+
+            return match method {
+                "textDocument/publishDiagnostics" => {
+                    let uri = params["uri"].as_str()?.to_string();
+
+                    let diagnostics = params["diagnostics"]
+                        .as_array()?
+                        .iter()
+                        .map(parse_diagnostic)
+                        .collect();
+
+                    self.diagnostics.insert(uri.clone(), diagnostics);
+
+                    Some(LspEvent::Diagnostics(uri))
+                }
+                "window/showMessage" => Some(LspEvent::Message(
+                    params["message"].as_str()?.to_string(),
+                )),
+                "window/logMessage" => {
+                    Some(LspEvent::Log(params["message"].as_str()?.to_string()))
+                }
+                _ => None,
+            };
+        }
+
+        let id = message["id"].as_i64()?;
+        let request = self.pending.remove(&id)?;
+
+        if let Some(error) = message.get("error") {
+            let text = error["message"].as_str().unwrap_or("request failed");
+
+            // nothing queued can ever be sent, so give up on the server instead
+            if let Request::Initialize = request {
+                self.queued.clear();
+                self.running = false;
+                self.child.kill().ok();
+                self.child.wait().ok();
+
+                return Some(LspEvent::Message(format!(
+                    "language server `{}` failed to initialize: {text}",
+                    self.command
+                )));
+            }
+
+            return Some(LspEvent::Message(format!("{}: {text}", self.command)));
+        }
+
+        let result = &message["result"];
+
+        match request {
+            Request::Initialize => {
+                self.trigger_characters = result["capabilities"]["completionProvider"]
+                    ["triggerCharacters"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|x| x.as_str()?.chars().next())
+                    .collect();
+
+                self.sync_kind = SyncKind::from_json(&result["capabilities"]["textDocumentSync"]);
+
+                self.initialized = true;
+                self.write(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }))
+                    .ok();
+
+                for message in std::mem::take(&mut self.queued) {
+                    self.write(&message).ok();
+                }
+
+                None
+            }
+            Request::Completion(uri) => {
+                let items = result
+                    .as_array()
+                    .or_else(|| result["items"].as_array())
+                    .into_iter()
+                    .flatten()
+                    .map(parse_completion_item)
+                    .collect();
+
+                Some(LspEvent::Completion(uri, items))
+            }
+            Request::Hover(uri) => {
+                let text = hover_text(&result["contents"]);
+
+                (!text.trim().is_empty()).then(|| LspEvent::Hover(uri, text.trim().to_string()))
+            }
+            Request::Definition(document) => {
+                let location = match result {
+                    Value::Array(x) => x.first()?,
+                    x => x,
+                };
+
+                let target = location["uri"]
+                    .as_str()
+                    .or_else(|| location["targetUri"].as_str())?;
+
+                let range = location
+                    .get("targetSelectionRange")
+                    .unwrap_or(&location["range"]);
+
+                Some(LspEvent::Definition(
+                    document,
+                    target.to_string(),
+                    Position::from_json(&range["start"]),
+                ))
+            }
+            Request::Rename => {
+                let mut edits: HashMap<String, Vec<TextEdit>> = HashMap::new();
+
+                for (uri, changes) in result["changes"].as_object().into_iter().flatten() {
+                    edits
+                        .entry(uri.clone())
+                        .or_default()
+                        .extend(changes.as_array()?.iter().map(TextEdit::from_json));
+                }
+
+                for change in result["documentChanges"].as_array().into_iter().flatten() {
+                    let Some(uri) = change["textDocument"]["uri"].as_str() else {
+                        continue;
+                    };
+
+                    edits
+                        .entry(uri.to_string())
+                        .or_default()
+                        .extend(change["edits"].as_array()?.iter().map(TextEdit::from_json));
+                }
+
+                Some(LspEvent::Rename(edits))
+            }
+            Request::Shutdown => None,
+        }
+    }
+
+    pub fn shutdown(&mut self) {
+        if self.running && self.initialized {
+            if let Ok(id) = self.send_request("shutdown", Value::Null, Request::Shutdown, true) {
+                let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+                while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+                    match self.messages.recv_timeout(timeout) {
+                        Ok(message) if message["id"].as_i64() == Some(id) => break,
+                        Ok(_) => (),
+                        Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            }
+
+            self.write(&json!({ "jsonrpc": "2.0", "method": "exit" })).ok();
+        }
+
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                self.running = false;
+                return;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        self.child.kill().ok();
+        self.child.wait().ok();
+        self.running = false;
+    }
+}
+
+impl Drop for LanguageServer {
+    fn drop(&mut self) {
+        if self.running {
+            self.shutdown();
+        }
+    }
+}
+
+fn parse_diagnostic(value: &Value) -> Diagnostic {
+    Diagnostic {
+        start: Position::from_json(&value["range"]["start"]),
+        end: Position::from_json(&value["range"]["end"]),
+        severity: match value["severity"].as_u64() {
+            Some(2) => Severity::Warning,
+            Some(3) => Severity::Information,
+            Some(4) => Severity::Hint,
+            _ => Severity::Error,
+        },
+        message: value["message"].as_str().unwrap_or_default().to_string(),
+    }
+}
+
+fn parse_completion_item(value: &Value) -> CompletionItem {
+    let label = value["label"].as_str().unwrap_or_default().to_string();
+
+    let edit = value
+        .get("textEdit")
+        .filter(|x| x.get("range").is_some())
+        .map(TextEdit::from_json);
+
+    CompletionItem {
+        insert_text: value["insertText"]
+            .as_str()
+            .unwrap_or(&label)
+            .to_string(),
+        detail: value["detail"].as_str().map(str::to_string),
+        label,
+        edit,
+    }
+}
+
+fn hover_text(contents: &Value) -> String {
+    match contents {
+        Value::String(x) => x.clone(),
+        Value::Array(x) => x.iter().map(hover_text).collect::<Vec<_>>().join("\n\n"),
+        Value::Object(x) => x
+            .get("value")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+pub fn read_message(reader: &mut impl BufRead) -> std::io::Result<Option<Value>> {
+    let mut length = None;
+
+    loop {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(length) = length else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "message without a Content-Length header",
+        ));
+    };
+
+    let mut content = vec![0; length];
+    reader.read_exact(&mut content)?;
+
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+
+    let mut uri = String::from("file://");
+
+    for byte in absolute.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    uri
+}
+
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?.as_bytes();
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut i = 0;
+
+    while i < path.len() {
+        if path[i] == b'%' {
+            let byte = std::str::from_utf8(path.get(i + 1..i + 3)?)
+                .ok()
+                .and_then(|x| u8::from_str_radix(x, 16).ok())?;
+
+            bytes.push(byte);
+            i += 3;
+        } else {
+            bytes.push(path[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+#[derive(Default)]
+pub struct LanguageServers {
+    servers: Vec<(String, Vec<String>, Rc<RefCell<LanguageServer>>)>,
+}
+
+impl LanguageServers {
+    pub fn get_or_start(
+        &mut self,
+        command: &str,
+        args: &[String],
+        root: &Path,
+    ) -> Result<Rc<RefCell<LanguageServer>>, String> {
+        if let Some((_, _, server)) = self
+            .servers
+            .iter()
+            .find(|(x, y, server)| x == command && y == args && server.borrow().is_running())
+        {
+            return Ok(server.clone());
+        }
+
+        let server = Rc::new(RefCell::new(LanguageServer::start(command, args, root)?));
+
+        self.servers
+            .push((command.to_string(), args.to_vec(), server.clone()));
+
+        Ok(server)
+    }
+
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let events = self
+            .servers
+            .iter()
+            .flat_map(|(_, _, x)| x.borrow_mut().poll())
+            .collect();
+
+        self.servers.retain(|(_, _, x)| x.borrow().is_running());
+
+        events
+    }
+
+    pub fn shutdown(&mut self) {
+        for (_, _, server) in self.servers.drain(..) {
+            server.borrow_mut().shutdown();
+        }
+    }
+}
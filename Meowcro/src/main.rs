@@ -1,18 +1,33 @@
-mod clipboard;
-mod editor;
-mod terminal;
-mod ui;
-mod unicode;
-mod widgets;
-mod widgets_impl;
-
 // clipboard
-use clipboard::Clipboard;
-use editor::*;
-use terminal::*;
-use ui::*;
-use widgets::*;
-use widgets_impl::*;
+use meowcro::buffer::{apply_workspace_edit, reload_changed_buffers, Buffer, SCRATCH_NAME};
+use meowcro::clipboard::Clipboard;
+use meowcro::commands::{find_command, remember_command, Action, CommandPalette, Context, COMMANDS};
+use meowcro::completion::CompletionMenu;
+use meowcro::config::{Config, ConfigFile};
+use meowcro::conflict::{get_conflict_at, get_next_conflict, get_previous_conflict, ConflictPart};
+use meowcro::dialog::{Dialog, DialogResult};
+use meowcro::diffview::{DiffView, Side};
+use meowcro::editor::*;
+use meowcro::explorer::Explorer;
+use meowcro::filetype;
+use meowcro::finder::FileFinder;
+use meowcro::git::GitChanges;
+use meowcro::input::{add_to_history, complete_path, InputLine, InputResult};
+use meowcro::lsp::{uri_to_path, LanguageServers, LspEvent, Position, Severity};
+use meowcro::panes::{Pane, Panes, Rect};
+use meowcro::replace::{AppliedReplace, ProjectReplace, RowKind};
+use meowcro::save::{save_file, Backup};
+use meowcro::search::{ProjectSearch, SearchQuery};
+use meowcro::snippets::SnippetSet;
+use meowcro::statusline::{detect_indent, uses_crlf, Segment, StatusInfo};
+use meowcro::syntax::GrammarSet;
+use meowcro::terminal::*;
+use meowcro::theme::{ColorSupport, Theme};
+use meowcro::ui::*;
+use meowcro::unicode::{word_end, word_start};
+use meowcro::watcher::FileWatcher;
+use meowcro::widgets::*;
+use meowcro::widgets_impl::*;
 
 // terminal deps
 use crossterm::{
@@ -23,41 +38,571 @@ use crossterm::{
 };
 
 // path saving
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 // arg parsing
 use clap::Parser;
 
-pub fn update_and_render_to_buffer(
-    editor: &mut TextEditor<TermLineLayoutSettings>,
+// the panels and overlays drawn around the panes, borrowed from `AppState` for one frame
+struct FrameWidgets<'a> {
+    message: Option<&'a str>,
+
+    prompt: Option<&'a Prompt<'a>>,
+
+    popup: Option<&'a Popup<'a>>,
+
+    explorer: Option<(&'a Explorer, bool)>,
+
+    results: Option<&'a ResultsPanel<'a>>,
+
+    diff: Option<&'a DiffPanel<'a>>,
+
+    dialog: Option<&'a dyn Widget<TerminalBuffer, UiEvent, Vec<UiReaction>>>,
+}
+
+fn update_and_render_to_buffer(
+    buffers: &mut [Buffer],
+    panes: &mut Panes,
     width: usize,
     height: usize,
-    filepath: &Path,
-    relative_line_numbers: bool,
+    widgets: &FrameWidgets,
+    event: UiEvent,
+) -> TerminalBuffer {
+    for buffer in buffers.iter_mut() {
+        buffer.sync_changes();
+    }
+
+    if let Some(diff) = widgets.diff {
+        let tabs = get_tab_labels(buffers);
+
+        let result = TabBar::new(&tabs, panes.get_focused().buffer)
+            .draw(width as u32, 1)
+            .combine_vertical(
+                diff.draw(width as u32, height.saturating_sub(1) as u32),
+                width as u32,
+                1,
+                height as u32,
+            );
+
+        return match widgets.dialog {
+            Some(dialog) => overlay_modal(result, dialog, width as u32, height as u32),
+            None => result,
+        };
+    }
+
+    let areas = get_areas(
+        widgets.explorer.map(|(x, _)| x),
+        widgets.results.is_some(),
+        width,
+        height,
+    );
+
+    let area = areas.panes;
+
+    let tabs = get_tab_labels(buffers);
+
+    let selected_tab = Layout::new_at(0, 0, width as u32, 1)
+        .add_item(
+            &TabBar::new(&tabs, panes.get_focused().buffer),
+            Align::Top,
+            Restriction::Shrink,
+        )
+        .interact(&event)
+        .into_iter()
+        .find_map(|x| match x {
+            UiReaction::SelectTab(x) => Some(x),
+            _ => None,
+        });
+
+    if let Some(index) = selected_tab {
+        show_buffer(buffers, panes, panes.get_focused().id, index);
+    }
+
+    if let UiEvent::Clicked(_, _, false) = event {
+        for (pane, rect) in panes.layout(area) {
+            let buffer = &mut buffers[pane.buffer];
+
+            buffer.editor.set_view(pane.view);
+
+            let editor = &buffer.editor;
+
+            let lines = LineNumbers::new(
+                editor.get_first_visible_line(),
+                editor.len_lines(),
+                editor.get_current_line() + 1,
+                buffer.settings.relative_line_numbers,
+                &[],
+            );
+            let status_bar = TextLine::new("");
+            let view = EditorView::new(editor, None, &[], &[]);
+
+            let clicked = Layout::new_at(rect.x, rect.y, rect.width, rect.height)
+                .add_item(&status_bar, Align::Bottom, Restriction::Shrink)
+                .add_item(&lines, Align::Left, Restriction::Shrink)
+                .add_item(&view, Align::Left, Restriction::Grow)
+                .interact(&event)
+                .iter()
+                .any(|x| matches!(x, UiReaction::SetRelativeCursorPos(..)));
+
+            if clicked {
+                panes.focus(pane.id);
+            }
+        }
+    }
+
+    let focused = panes.get_focused();
+
+    let tabs = get_tab_labels(buffers);
+    let tab_bar = TabBar::new(&tabs, focused.buffer).draw(width as u32, 1);
+
+    let result = panes.draw(
+        area,
+        &mut |pane, rect| {
+            if pane.id == focused.id {
+                update_and_render_pane(
+                    &mut buffers[pane.buffer],
+                    pane.view,
+                    rect,
+                    widgets.message,
+                    widgets.prompt,
+                    widgets.popup,
+                    event,
+                )
+            } else {
+                let (chars, _) = update_and_render_pane(
+                    &mut buffers[pane.buffer],
+                    pane.view,
+                    rect,
+                    None,
+                    None,
+                    None,
+                    UiEvent::Nothing,
+                );
+
+                (chars, None)
+            }
+        },
+        &|height| Separator.draw(1, height),
+    );
+
+    buffers[focused.buffer].editor.set_view(focused.view);
+
+    let result = match (widgets.explorer, areas.tree) {
+        (Some((explorer, explorer_focused)), Some(tree_area)) => FileTree::new(
+            explorer.get_entries(),
+            explorer.get_selected(),
+            explorer.get_scroll(),
+            explorer_focused,
+        )
+        .draw(tree_area.width, tree_area.height)
+        .combine_horizontal(
+            Separator.draw(1, area.height),
+            tree_area.width + 1,
+            tree_area.width,
+            area.height,
+        )
+        .combine_horizontal(result, width as u32, tree_area.width + 1, area.height),
+        _ => result,
+    };
+
+    let result = match (widgets.results, areas.results) {
+        (Some(results), Some(results_area)) => result.combine_vertical(
+            results.draw(results_area.width, results_area.height),
+            width as u32,
+            area.height,
+            area.height + results_area.height,
+        ),
+        _ => result,
+    };
+
+    let result = tab_bar.combine_vertical(result, width as u32, 1, height as u32);
+
+    match widgets.dialog {
+        Some(dialog) => overlay_modal(result, dialog, width as u32, height as u32),
+        None => result,
+    }
+}
+
+// draws everything for one event and writes what changed since `screen` to the terminal
+fn render_frame(
+    app: &mut AppState,
+    (width, height): (u16, u16),
+    event: UiEvent,
+    screen: &mut Vec<Char>,
+    theme: &Theme,
+) {
+    let prompt = app.prompt.as_ref().map(|(kind, x)| get_prompt(*kind, x));
+    let popup_lines = get_popup_lines(
+        app.switcher.map(|x| (get_tab_labels(&app.buffers), x)),
+        app.completion.as_ref(),
+        app.hover.as_deref(),
+    );
+    let popup = popup_lines.as_ref().map(|(x, y)| Popup::new(x, *y));
+
+    let picker_data = get_picker_data(app.finder.as_ref(), app.palette.as_ref());
+    let picker = picker_data.as_ref().map(PickerData::get_picker);
+    let dialog_box = app.dialog.as_ref().map(get_dialog_box);
+    let results = get_results(app.search.as_ref(), app.search_focused, app.preview.as_ref());
+    let results_panel = results.as_ref().map(Results::get_panel);
+    let diff_panel = app
+        .diff_view
+        .as_ref()
+        .map(|x| DiffPanel::new(&x.view, app.message.as_deref()));
+
+    let widgets = FrameWidgets {
+        message: app.message.as_deref(),
+        prompt: prompt.as_ref(),
+        popup: popup.as_ref(),
+        explorer: app.explorer.as_ref().map(|x| (x, app.explorer_focused)),
+        results: results_panel.as_ref(),
+        diff: diff_panel.as_ref(),
+        dialog: get_modal(dialog_box.as_ref(), picker.as_ref()),
+    };
+
+    let (next_screen, cursor_position) = update_and_render_to_buffer(
+        &mut app.buffers,
+        &mut app.panes,
+        width as usize,
+        height as usize,
+        &widgets,
+        event,
+    );
+
+    render(width as usize, cursor_position, &next_screen, screen, theme);
+
+    *screen = next_screen;
+}
+
+struct Areas {
+    tree: Option<Rect>,
+
+    panes: Rect,
+
+    results: Option<Rect>,
+}
+
+fn get_areas(
+    explorer: Option<&Explorer>,
+    has_results: bool,
+    width: usize,
+    height: usize,
+) -> Areas {
+    let (width, height) = (width as u32, height.saturating_sub(1) as u32);
+
+    // a terminal shorter than the panel's minimum gives it every row there is
+    let results_height = if has_results {
+        ResultsPanel::new("", &[], 0, 0, false)
+            .minimum_size(width, height)
+            .1
+            .min(height)
+    } else {
+        0
+    };
+
+    let area = Rect {
+        x: 0,
+        y: 1,
+        width,
+        height: height.saturating_sub(results_height),
+    };
+
+    let results = has_results.then(|| Rect {
+        x: 0,
+        y: 1 + area.height,
+        width,
+        height: results_height,
+    });
+
+    let Some(explorer) = explorer else {
+        return Areas {
+            tree: None,
+            panes: area,
+            results,
+        };
+    };
+
+    let (tree_width, _) = FileTree::new(explorer.get_entries(), 0, 0, false)
+        .minimum_size(area.width, area.height);
+
+    Areas {
+        tree: Some(Rect {
+            width: tree_width,
+            ..area
+        }),
+        panes: Rect {
+            x: tree_width + 1,
+            width: area.width.saturating_sub(tree_width + 1),
+            ..area
+        },
+        results,
+    }
+}
+
+struct PickerData<'a> {
+    prompt: &'static str,
+
+    query: &'a str,
+
+    items: Vec<&'a str>,
+
+    selected: usize,
+
+    status: String,
+
+    preview: Option<&'a [String]>,
+}
+
+impl<'a> PickerData<'a> {
+    fn get_picker(&self) -> Picker<'_> {
+        Picker::new(
+            self.prompt,
+            self.query,
+            &self.items,
+            self.selected,
+            &self.status,
+            self.preview,
+        )
+    }
+}
+
+fn get_picker_data<'a>(
+    finder: Option<&'a FileFinder>,
+    palette: Option<&'a CommandPalette>,
+) -> Option<PickerData<'a>> {
+    if let Some(x) = finder {
+        return Some(PickerData {
+            prompt: "Find file: ",
+            query: x.get_query(),
+            items: x.get_matches(),
+            selected: x.get_selected(),
+            status: x.get_status(),
+            preview: Some(x.get_preview()),
+        });
+    }
+
+    palette.map(|x| PickerData {
+        prompt: "Command: ",
+        query: x.get_query(),
+        items: x.get_matches(),
+        selected: x.get_selected(),
+        status: x.get_status(),
+        preview: None,
+    })
+}
+
+struct Results {
+    title: String,
+
+    rows: Vec<(String, Highlight)>,
+
+    selected: usize,
+
+    scroll: usize,
+
+    focused: bool,
+}
+
+impl Results {
+    fn get_panel(&self) -> ResultsPanel<'_> {
+        ResultsPanel::new(&self.title, &self.rows, self.selected, self.scroll, self.focused)
+    }
+}
+
+fn get_results(
+    search: Option<&ProjectSearch>,
+    search_focused: bool,
+    preview: Option<&ProjectReplace>,
+) -> Option<Results> {
+    if let Some(preview) = preview {
+        let rows = preview
+            .get_rows()
+            .into_iter()
+            .map(|(text, kind)| {
+                let highlight = match kind {
+                    RowKind::File => Highlight::Heading,
+                    RowKind::Removed => Highlight::Removed,
+                    RowKind::Added => Highlight::Added,
+                };
+
+                (text, highlight)
+            })
+            .collect();
+
+        return Some(Results {
+            title: preview.get_title(),
+            rows,
+            selected: preview.get_selected(),
+            scroll: preview.get_scroll(),
+            focused: true,
+        });
+    }
+
+    search.map(|search| Results {
+        title: search.get_title(),
+        rows: search
+            .get_rows()
+            .into_iter()
+            .map(|(text, header)| (text, if header { Highlight::Heading } else { Highlight::Text }))
+            .collect(),
+        selected: search.get_selected(),
+        scroll: search.get_scroll(),
+        focused: search_focused,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_and_render_pane(
+    buffer: &mut Buffer,
+    view: usize,
+    rect: Rect,
+    message: Option<&str>,
+    prompt: Option<&Prompt>,
+    popup: Option<&Popup>,
     event: UiEvent,
 ) -> TerminalBuffer {
-    let (pos_x, pos_y) = {
-        let (x, y) = editor.get_row_and_column();
-        (x + 1, y + 1)
+    buffer.editor.set_view(view);
+
+    let relative_line_numbers = buffer.settings.relative_line_numbers;
+
+    let mut diagnostics = buffer.get_diagnostics();
+    diagnostics.sort_by_key(|x| x.severity);
+
+    let diagnostic_ranges = diagnostics
+        .iter()
+        .map(|x| {
+            (
+                buffer.position_to_byte(x.start)..buffer.position_to_byte(x.end),
+                x.severity,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let conflict_regions = buffer
+        .conflicts
+        .iter()
+        .flat_map(|x| x.get_regions())
+        .map(|(lines, part)| {
+            let highlight = match part {
+                ConflictPart::Marker => Highlight::ConflictMarker,
+                ConflictPart::Ours => Highlight::ConflictOurs,
+                ConflictPart::Base => Highlight::ConflictBase,
+                ConflictPart::Theirs => Highlight::ConflictTheirs,
+            };
+
+            (lines, highlight)
+        })
+        .collect::<Vec<_>>();
+
+    let mut signs: Vec<(usize, Severity)> = Vec::new();
+
+    for diagnostic in &diagnostics {
+        if !signs.iter().any(|(x, _)| *x == diagnostic.start.line) {
+            signs.push((diagnostic.start.line, diagnostic.severity));
+        }
+    }
+
+    let diagnostic_message = diagnostics
+        .iter()
+        .find(|x| x.start.line == buffer.editor.get_current_line())
+        .map(|x| x.message.replace('\n', " "));
+
+    let title = buffer.get_title();
+
+    buffer.update_git();
+
+    let markers = buffer.git.as_ref().map(GitChanges::get_markers);
+
+    let mode = if buffer.snippet.is_some() {
+        "SNIPPET"
+    } else if buffer.editor.get_selection_range().is_some_and(|x| !x.is_empty()) {
+        "SELECT"
+    } else {
+        "EDIT"
     };
 
+    let Buffer {
+        editor,
+        highlighter,
+        path,
+        settings,
+        ..
+    } = buffer;
+
     let lines = LineNumbers::new(
         editor.get_first_visible_line(),
         editor.len_lines(),
         editor.get_current_line() + 1,
         relative_line_numbers,
+        &signs,
     );
-    let status_bar_text = format!(
-        " {}{} {pos_x}:{pos_y}",
-        filepath.to_string_lossy(),
-        if editor.has_changed_since_save() {"*" } else { "" }
+
+    let text = editor.get_text();
+
+    let selection = editor.get_selection_range().filter(|x| !x.is_empty()).map(|x| {
+        (
+            text.byte_to_char(x.end) - text.byte_to_char(x.start),
+            text.byte_to_line(x.end) - text.byte_to_line(x.start) + 1,
+        )
+    });
+
+    let (row, column) = editor.get_row_and_column();
+
+    let info = StatusInfo {
+        mode,
+        title: &title,
+        modified: editor.has_changed_since_save(),
+        message: message.or(diagnostic_message.as_deref()),
+        filetype: filetype::detect(path),
+        crlf: uses_crlf(text),
+        indent: detect_indent(text),
+        selection,
+        position: (row + 1, column + 1),
+        lines: editor.len_lines(),
+    };
+
+    let status_segments = |segments: &[Segment]| {
+        info.get_segments(segments)
+            .into_iter()
+            .map(|(segment, text)| {
+                let highlight = if segment.is_accent() {
+                    Highlight::StatusAccent
+                } else {
+                    Highlight::Status
+                };
+
+                (text, highlight)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let (left, center, right) = (
+        status_segments(&settings.status_line.left),
+        status_segments(&settings.status_line.center),
+        status_segments(&settings.status_line.right),
     );
-    let status_bar = TextLine::new(&status_bar_text);
 
-    let events = Layout::new(width as u32, height as u32)
-        .add_item(&status_bar, Align::Bottom, Restriction::Shrink)
-        .add_item(&lines, Align::Left, Restriction::Shrink)
-        .add_item(editor, Align::Left, Restriction::Grow)
+    let status_line = StatusLine::new(&left, &center, &right);
+    let status_bar: &dyn Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> = match prompt {
+        Some(x) => x,
+        None => &status_line,
+    };
+
+    let view = EditorView::new(editor, highlighter.as_ref(), &diagnostic_ranges, &conflict_regions);
+    let change_markers = markers
+        .as_ref()
+        .map(|x| ChangeMarkers::new(editor.get_first_visible_line(), x));
+
+    let mut layout = Layout::new_at(rect.x, rect.y, rect.width, rect.height)
+        .add_item(status_bar, Align::Bottom, Restriction::Shrink)
+        .add_item(&lines, Align::Left, Restriction::Shrink);
+
+    if let Some(x) = &change_markers {
+        layout = layout.add_item(x, Align::Left, Restriction::Shrink);
+    }
+
+    let events = layout
+        .add_item(&view, Align::Left, Restriction::Grow)
         .interact(&event);
 
     for event in events.into_iter().rev() {
@@ -67,6 +612,11 @@ pub fn update_and_render_to_buffer(
             UiReaction::SetRelativeCursorPos(x, y, select) => {
                 editor.set_relative_cursor_pos(x, y, select)
             }
+            UiReaction::SelectTab(_)
+            | UiReaction::SelectEntry(_)
+            | UiReaction::SelectResult(_)
+            | UiReaction::SetPromptCursor(_)
+            | UiReaction::PressButton(_) => (),
         }
     }
 
@@ -75,262 +625,2338 @@ pub fn update_and_render_to_buffer(
         editor.len_lines(),
         editor.get_current_line() + 1,
         relative_line_numbers,
+        &signs,
     );
 
-    let buffer = Layout::new(width as u32, height as u32)
-        .add_item(&status_bar, Align::Bottom, Restriction::Shrink)
-        .add_item(&lines, Align::Left, Restriction::Shrink)
-        .add_item(editor, Align::Left, Restriction::Grow)
-        .draw();
+    buffer.update_highlighting(buffer.editor.get_first_visible_line() + rect.height as usize);
 
-    buffer
-}
+    let view = EditorView::new(
+        &buffer.editor,
+        buffer.highlighter.as_ref(),
+        &diagnostic_ranges,
+        &conflict_regions,
+    );
+    let change_markers = markers
+        .as_ref()
+        .map(|x| ChangeMarkers::new(buffer.editor.get_first_visible_line(), x));
 
-fn terminal_main(
-    file_content: String,
-    newly_loaded: bool,
-    save_path: PathBuf,
-    relative_line_numbers: bool,
-    tab_width: usize,
-    disable_mouse_interaction: bool,
-) {
-    setup_terminal(disable_mouse_interaction);
+    let mut layout = Layout::new_at(rect.x, rect.y, rect.width, rect.height)
+        .add_item(status_bar, Align::Bottom, Restriction::Shrink)
+        .add_item(&lines, Align::Left, Restriction::Shrink);
 
-    let (mut width, mut height) = size().unwrap();
+    if let Some(x) = &change_markers {
+        layout = layout.add_item(x, Align::Left, Restriction::Shrink);
+    }
 
-    let mut editor = TextEditor::new(
-        &file_content,
-        TermLineLayoutSettings::new(tab_width),
-        tab_width,
-        newly_loaded,
-    );
+    layout = layout.add_item(&view, Align::Left, Restriction::Grow);
 
-    let mut clip = String::new();
+    if let Some(x) = popup {
+        layout = layout.add_popup(x);
+    }
 
-    let mut system_clip = Clipboard::new().ok();
+    let (chars, cursor) = layout.draw();
 
-    let (mut current_buffer, cursor_position) = update_and_render_to_buffer(
-        &mut editor,
-        width as usize,
-        height as usize,
-        &save_path,
-        relative_line_numbers,
-        UiEvent::Nothing,
-    );
+    match prompt {
+        Some(x) => {
+            let cursor = x
+                .draw(rect.width, 1)
+                .1
+                .map(|(x, _)| (x, rect.height.saturating_sub(1) as usize));
 
-    render(width as usize, cursor_position, &current_buffer, &[]);
+            (chars, cursor)
+        }
+        None => (chars, cursor),
+    }
+}
 
-    loop {
-        if poll(std::time::Duration::from_millis(100)).unwrap() {
-            match read().unwrap() {
-                Event::Mouse(MouseEvent {
-                    row, column, kind, ..
-                }) if !disable_mouse_interaction
-                    && (kind == MouseEventKind::Down(MouseButton::Left)
-                        || kind == MouseEventKind::Drag(MouseButton::Left)) =>
-                {
-                    let (next_buffer, cursor_position) = update_and_render_to_buffer(
-                        &mut editor,
-                        width as usize,
-                        height as usize,
-                        &save_path,
-                        relative_line_numbers,
-                        UiEvent::Clicked(
-                            column as usize,
-                            row as usize,
-                            kind == MouseEventKind::Drag(MouseButton::Left),
-                        ),
-                    );
+fn start_language_server(
+    buffer: &mut Buffer,
+    config: &Config,
+    servers: &mut LanguageServers,
+) -> Result<(), String> {
+    let (Some(server), Some(filetype)) = (
+        buffer.settings.language_server.clone(),
+        filetype::detect(&buffer.path),
+    ) else {
+        return Ok(());
+    };
 
-                    render(
-                        width as usize,
-                        cursor_position,
-                        &next_buffer,
-                        &current_buffer,
-                    );
+    let root = config
+        .project_root(&buffer.path)
+        .or_else(|| {
+            std::path::absolute(&buffer.path)
+                .ok()?
+                .parent()
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_else(|| PathBuf::from("."));
 
-                    current_buffer = next_buffer;
-                }
-                Event::Key(KeyEvent {
-                    code, modifiers, ..
-                }) => {
-                    if code == KeyCode::Char('q') && modifiers == KeyModifiers::ALT {
-                        break;
-                    }
+    let server = servers.get_or_start(&server.command, &server.args, &root)?;
 
-                    let mut ui_event = UiEvent::Nothing;
+    buffer.attach_language_server(server, filetype)
+}
 
-                    if code == KeyCode::Char('s') && modifiers == KeyModifiers::CONTROL {
-                        let string = editor.to_string();
+// drops answers for buffers that lost focus and returns definitions in files that aren't open
+fn handle_lsp_event(
+    buffers: &mut [Buffer],
+    panes: &mut Panes,
+    event: LspEvent,
+    completion: &mut Option<CompletionMenu>,
+    hover: &mut Option<Vec<String>>,
+    message: &mut Option<String>,
+) -> Option<(PathBuf, Position)> {
+    let focused = panes.get_focused();
+    let focused_uri = buffers[focused.buffer].get_uri();
 
-                        if std::fs::create_dir_all(save_path.as_path().parent().unwrap()).is_ok()
-                            && std::fs::write(save_path.as_path(), string).is_ok()
-                        {
-                            editor.set_saved();
-                        }
-                    }
-                    else if code == KeyCode::Char('d') && modifiers == KeyModifiers::ALT {
-                        editor.discard_changes();
-                    }
-                    else if code == KeyCode::Char('z') && modifiers == KeyModifiers::CONTROL {
-                        editor.undo();
-                    } else if code == KeyCode::Char('y') && modifiers == KeyModifiers::CONTROL {
-                        editor.redo();
-                    } else if code == KeyCode::Char('c') && modifiers == KeyModifiers::CONTROL {
-                        if let Some(x) = editor.get_selection() {
-                            clip = x;
-                        }
-                    } else if code == KeyCode::Char('v') && modifiers == KeyModifiers::CONTROL {
-                        if !clip.is_empty() {
-                            editor.insert_string_at_cursor(&clip);
-                        }
-                    } else if code == KeyCode::Char('x') && modifiers == KeyModifiers::CONTROL {
-                        if let Some(x) = editor.cut_selection() {
-                            clip = x;
-                        }
-                    }
-                    else if code == KeyCode::Char('c') && modifiers == KeyModifiers::ALT {
-                        if let Some(x) = editor.get_selection() {
-                            system_clip.as_mut().map(|y| y.set_text(x));
-                        }
-                    } else if code == KeyCode::Char('v') && modifiers == KeyModifiers::ALT {
-                        if let Some(x) = system_clip.as_mut() {
-                            if let Ok(y) = x.get_text() {
-                                if !y.is_empty() {
-                                    editor.insert_string_at_cursor(&y);
-                                }
-                            }
-                        }
-                    } else if code == KeyCode::Char('x') && modifiers == KeyModifiers::ALT {
-                        if let Some(x) = editor.cut_selection() {
-                            system_clip.as_mut().map(|y| y.set_text(x));
-                        }
-                    } else if code == KeyCode::Up {
-                        editor.move_cursor_vertical(-1, modifiers == KeyModifiers::SHIFT, false);
-                    } else if code == KeyCode::Down {
-                        editor.move_cursor_vertical(1, modifiers == KeyModifiers::SHIFT, false);
-                    } else if code == KeyCode::Left && modifiers.contains(KeyModifiers::CONTROL) {
-                        editor.move_cursor_horizontal_words(
-                            -1,
-                            modifiers.contains(KeyModifiers::SHIFT),
-                            true,
-                        );
-                    } else if code == KeyCode::Right && modifiers.contains(KeyModifiers::CONTROL) {
-                        editor.move_cursor_horizontal_words(
-                            1,
-                            modifiers.contains(KeyModifiers::SHIFT),
-                            true,
-                        );
-                    } else if code == KeyCode::Left {
-                        editor.move_cursor_horizontal(-1, modifiers == KeyModifiers::SHIFT, true);
-                    } else if code == KeyCode::Right {
-                        editor.move_cursor_horizontal(1, modifiers == KeyModifiers::SHIFT, true);
-                    } else if code == KeyCode::Home {
-                        editor.move_cursor_to_start_of_line(modifiers == KeyModifiers::SHIFT, true);
-                    } else if code == KeyCode::End {
-                        editor.move_cursor_to_end_of_line(modifiers == KeyModifiers::SHIFT, true);
-                    } else if code == KeyCode::PageUp {
-                        ui_event = UiEvent::ScrollPage(true);
-                    } else if code == KeyCode::PageDown {
-                        ui_event = UiEvent::ScrollPage(false);
-                    } else if let KeyCode::Char(c) = code {
-                        editor.insert_character_at_cursor(c);
-                    } else if code == KeyCode::Enter {
-                        editor.insert_newline_at_cursor();
-                    } else if code == KeyCode::Tab {
-                        editor.insert_tab_at_cursor();
-                    } else if code == KeyCode::Backspace {
-                        editor.remove_character_or_selection_at_cursor(true);
-                    } else if code == KeyCode::Delete {
-                        editor.remove_character_or_selection_at_cursor(false);
-                    }
-                    let (next_buffer, cursor_position) = update_and_render_to_buffer(
-                        &mut editor,
-                        width as usize,
-                        height as usize,
-                        &save_path,
-                        relative_line_numbers,
-                        ui_event,
-                    );
-                    render(
-                        width as usize,
-                        cursor_position,
-                        &next_buffer,
-                        &current_buffer,
-                    );
+    match event {
+        LspEvent::Completion(uri, items) if focused_uri.as_ref() == Some(&uri) => {
+            *completion = CompletionMenu::new(items, &buffers[focused.buffer]);
 
-                    current_buffer = next_buffer;
+            if completion.is_none() {
+                *message = Some("no completions".to_string());
+            }
+        }
+        LspEvent::Hover(uri, text) if focused_uri.as_ref() == Some(&uri) => {
+            *hover = Some(
+                text.lines()
+                    .filter(|x| !x.starts_with("```"))
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+        LspEvent::Definition(uri, target, position) if focused_uri.as_ref() == Some(&uri) => {
+            let Some(index) = buffers
+                .iter()
+                .position(|x| x.get_uri().as_ref() == Some(&target))
+            else {
+                match uri_to_path(&target) {
+                    Some(path) => return Some((path, position)),
+                    None => *message = Some(format!("can't open `{target}`")),
                 }
 
-                Event::Resize(..) => {
-                    width = size().unwrap().0;
-                    height = size().unwrap().1;
+                return None;
+            };
 
-                    let (next_buffer, cursor_position) = update_and_render_to_buffer(
-                        &mut editor,
-                        width as usize,
-                        height as usize,
-                        &save_path,
-                        relative_line_numbers,
-                        UiEvent::Nothing,
-                    );
-                    render(width as usize, cursor_position, &next_buffer, &[]);
+            show_buffer(buffers, panes, focused.id, index);
 
-                    current_buffer = next_buffer;
-                }
-                _ => (),
-            }
-        } else {
-            let (next_buffer, cursor_position) = update_and_render_to_buffer(
-                &mut editor,
-                width as usize,
-                height as usize,
-                &save_path,
-                relative_line_numbers,
-                UiEvent::Nothing,
-            );
-            render(width as usize, cursor_position, &next_buffer, &current_buffer);
+            let cursor = buffers[index].position_to_byte(position);
 
-            current_buffer = next_buffer;
+            buffers[index].editor.set_cursor(cursor);
         }
+        LspEvent::Rename(edits) => {
+            let backup = buffers[focused.buffer].settings.backup;
+
+            *message = Some(match apply_workspace_edit(buffers, &edits, backup) {
+                Ok(1) => "renamed in 1 file".to_string(),
+                Ok(x) => format!("renamed in {x} files"),
+                Err(e) => e,
+            });
+        }
+        LspEvent::Message(x) => *message = Some(x),
+        _ => (),
     }
 
-    cleanup_terminal("Done");
+    None
 }
 
-#[derive(Parser)]
-struct Args {
-    #[arg()]
-    file_path: PathBuf,
+// what the editor shows, the main loop changes it and `render_frame` draws it
+struct AppState {
+    buffers: Vec<Buffer>,
 
-    #[arg(long, short, default_value_t = false)]
-    disable_mouse_interaction: bool,
+    panes: Panes,
 
-    #[arg(long, short, default_value_t = 4)]
-    tab_width: usize,
+    message: Option<String>,
 
-    #[arg(long, short)]
-    relative_line_numbers: bool,
-}
+    completion: Option<CompletionMenu>,
 
-fn main() {
-    let args = Args::parse();
+    hover: Option<Vec<String>>,
 
-    let (file_content, newly_loaded) = match std::fs::read_to_string(&args.file_path) {
-        Ok(x) => (x, false),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (String::new(), true),
-        Err(e) => {
-            println!("Failed to read file: {:?}", e);
-            return;
-        }
-    };
+    prompt: Option<(PromptKind, InputLine)>,
 
-    terminal_main(
-        file_content,
-        newly_loaded,
-        args.file_path,
-        args.relative_line_numbers,
-        args.tab_width,
-        args.disable_mouse_interaction,
+    switcher: Option<usize>,
+
+    explorer: Option<Explorer>,
+
+    explorer_focused: bool,
+
+    finder: Option<FileFinder>,
+
+    search: Option<ProjectSearch>,
+
+    search_focused: bool,
+
+    preview: Option<ProjectReplace>,
+
+    palette: Option<CommandPalette>,
+
+    dialog: Option<Dialog<DialogAction>>,
+
+    diff_view: Option<DiffSession>,
+}
+
+fn terminal_main(files: Vec<(PathBuf, String, bool)>, mut config: Config, mut theme: Theme, compare: bool) {
+    let mut settings = config.settings_for(&files[0].0);
+
+    let color_support = ColorSupport::detect();
+
+    let (grammars, grammar_errors) = GrammarSet::load();
+
+    let (snippets, snippet_errors) = SnippetSet::load();
+
+    let mut errors = [grammar_errors, snippet_errors].concat();
+
+    setup_terminal(settings.disable_mouse_interaction);
+
+    let (mut width, mut height) = size().unwrap();
+
+    let mut servers = LanguageServers::default();
+
+    let mut buffers = Vec::new();
+
+    for (path, content, newly_loaded) in files {
+        let mut buffer = load_buffer(path, &content, newly_loaded, &config, &grammars);
+
+        if let Err(e) = start_language_server(&mut buffer, &config, &mut servers) {
+            errors.push(e);
+        }
+
+        buffers.push(buffer);
+    }
+
+    let panes = Panes::new(0, buffers[0].editor.get_view());
+
+    let mut app = AppState {
+        buffers,
+        panes,
+        message: (!errors.is_empty()).then(|| errors.join(", ")),
+        completion: None,
+        hover: None,
+        prompt: None,
+        switcher: None,
+        explorer: None,
+        explorer_focused: false,
+        finder: None,
+        search: None,
+        search_focused: false,
+        preview: None,
+        palette: None,
+        dialog: None,
+        diff_view: None,
+    };
+
+    let mut shown_message: Option<String> = None;
+
+    let mut message_time = std::time::Instant::now();
+
+    let mut clip = String::new();
+
+    let mut system_clip = Clipboard::new().ok();
+
+    let mut prompt_history: HashMap<PromptKind, Vec<String>> = HashMap::new();
+
+    let mut replace_query: Option<SearchQuery> = None;
+
+    let mut last_replace: Option<AppliedReplace> = None;
+
+    let mut recent_commands: Vec<usize> = Vec::new();
+
+    let mut queued_key: Option<(KeyCode, KeyModifiers)> = None;
+
+    let mut watcher = FileWatcher::default();
+
+    let mut check_disk = false;
+
+    let mut quitting: Option<usize> = None;
+
+    if compare {
+        match open_diff(&app.buffers, [DiffSource::Buffer(0), DiffSource::Buffer(1)]) {
+            Ok(x) => app.diff_view = Some(x),
+            Err(e) => app.message = Some(e),
+        }
+    }
+
+    let mut current_buffer = Vec::new();
+
+    render_frame(&mut app, (width, height), UiEvent::Nothing, &mut current_buffer, &theme);
+
+    loop {
+        for event in servers.poll() {
+            let definition = handle_lsp_event(
+                &mut app.buffers,
+                &mut app.panes,
+                event,
+                &mut app.completion,
+                &mut app.hover,
+                &mut app.message,
+            );
+
+            let Some((path, position)) = definition else {
+                continue;
+            };
+
+            match open_buffer(
+                &mut app.buffers,
+                &mut app.panes,
+                path,
+                &config,
+                &grammars,
+                &mut servers,
+            ) {
+                Ok(()) => {
+                    let buffer = &mut app.buffers[app.panes.get_focused().buffer];
+                    let cursor = buffer.position_to_byte(position);
+
+                    buffer.editor.set_cursor(cursor);
+                }
+                Err(e) => app.message = Some(e),
+            }
+        }
+
+        let timeout = settings.status_line.message_timeout;
+
+        if app.message != shown_message {
+            shown_message = app.message.clone();
+            message_time = std::time::Instant::now();
+        } else if app.message.is_some()
+            && timeout > 0
+            && message_time.elapsed().as_secs() >= timeout
+        {
+            app.message = None;
+        }
+
+        let event = match queued_key.take() {
+            Some((code, modifiers)) => Some(Event::Key(KeyEvent::new(code, modifiers))),
+            None => poll(std::time::Duration::from_millis(100))
+                .unwrap()
+                .then(|| read().unwrap()),
+        };
+
+        if let Some(event) = event {
+            match event {
+                Event::Mouse(MouseEvent {
+                    row,
+                    column,
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    ..
+                }) if !settings.disable_mouse_interaction && app.dialog.is_some() => {
+                    let pressed = app.dialog.as_ref().and_then(|x| {
+                        Layout::new_at(0, 0, width as u32, height as u32)
+                            .add_modal(&get_dialog_box(x))
+                            .interact(&UiEvent::Clicked(column as usize, row as usize, false))
+                            .into_iter()
+                            .find_map(|x| match x {
+                                UiReaction::PressButton(x) => Some(x),
+                                _ => None,
+                            })
+                    });
+
+                    if let Some((x, index)) = app.dialog.as_mut().zip(pressed) {
+                        x.select(index);
+                        queued_key = Some((KeyCode::Enter, KeyModifiers::NONE));
+                    }
+                }
+                Event::Mouse(..) if app.dialog.is_some() || app.diff_view.is_some() => (),
+                Event::Mouse(MouseEvent {
+                    row, column, kind, ..
+                }) if !settings.disable_mouse_interaction
+                    && (kind == MouseEventKind::Down(MouseButton::Left)
+                        || kind == MouseEventKind::Drag(MouseButton::Left)) =>
+                {
+                    app.completion = None;
+                    app.hover = None;
+
+                    let event = UiEvent::Clicked(
+                        column as usize,
+                        row as usize,
+                        kind == MouseEventKind::Drag(MouseButton::Left),
+                    );
+
+                    let areas = get_areas(
+                        app.explorer.as_ref(),
+                        app.search.is_some() || app.preview.is_some(),
+                        width as usize,
+                        height as usize,
+                    );
+
+                    let clicked_tree = areas.tree.filter(|x| x.contains(column as u32, row as u32));
+                    let clicked_results =
+                        areas.results.filter(|x| x.contains(column as u32, row as u32));
+
+                    if kind == MouseEventKind::Down(MouseButton::Left) {
+                        app.explorer_focused = clicked_tree.is_some();
+                        app.search_focused = clicked_results.is_some();
+                    }
+
+                    if let Some((tree, tree_area)) = app.explorer.as_mut().zip(clicked_tree) {
+                        let selected = Layout::new_at(
+                            tree_area.x,
+                            tree_area.y,
+                            tree_area.width,
+                            tree_area.height,
+                        )
+                        .add_item(
+                            &FileTree::new(tree.get_entries(), tree.get_selected(), tree.get_scroll(), true),
+                            Align::Left,
+                            Restriction::Shrink,
+                        )
+                        .interact(&event)
+                        .into_iter()
+                        .find_map(|x| match x {
+                            UiReaction::SelectEntry(x) => Some(x),
+                            _ => None,
+                        });
+
+                        if let Some(index) = selected {
+                            tree.select(index);
+
+                            if let Some(path) = tree.activate() {
+                                app.explorer_focused = false;
+
+                                if let Err(e) = open_buffer(
+                                    &mut app.buffers,
+                                    &mut app.panes,
+                                    path,
+                                    &config,
+                                    &grammars,
+                                    &mut servers,
+                                ) {
+                                    app.message = Some(e);
+                                }
+                            }
+                        }
+                    }
+
+                    let results = get_results(app.search.as_ref(), true, app.preview.as_ref());
+
+                    let selected = results.as_ref().zip(clicked_results).and_then(|(results, results_area)| {
+                        Layout::new_at(
+                            results_area.x,
+                            results_area.y,
+                            results_area.width,
+                            results_area.height,
+                        )
+                        .add_item(&results.get_panel(), Align::Top, Restriction::Grow)
+                        .interact(&event)
+                        .into_iter()
+                        .find_map(|x| match x {
+                            UiReaction::SelectResult(x) => Some(x),
+                            _ => None,
+                        })
+                    });
+
+                    if let Some(index) = selected {
+                        if let Some(x) = app.preview.as_mut() {
+                            x.select(index);
+                        } else if let Some(results) = app.search.as_mut() {
+                            results.select(index);
+                            app.search_focused = false;
+
+                            if let Err(e) = jump_to_match(
+                                &mut app.buffers,
+                                &mut app.panes,
+                                results,
+                                &config,
+                                &grammars,
+                                &mut servers,
+                            ) {
+                                app.message = Some(e);
+                            }
+                        }
+                    }
+
+                    if let Some((kind, line)) = app.prompt.as_mut() {
+                        let focused = app.panes.get_focused();
+
+                        let cursor = app.panes
+                            .layout(areas.panes)
+                            .into_iter()
+                            .find(|(x, _)| x.id == focused.id)
+                            .and_then(|(_, rect)| {
+                                Layout::new_at(rect.x, rect.y + rect.height.saturating_sub(1), rect.width, 1)
+                                    .add_item(&get_prompt(*kind, line), Align::Bottom, Restriction::Grow)
+                                    .interact(&event)
+                                    .into_iter()
+                                    .find_map(|x| match x {
+                                        UiReaction::SetPromptCursor(x) => Some(x),
+                                        _ => None,
+                                    })
+                            });
+
+                        if let Some(x) = cursor {
+                            line.set_cursor(x);
+                        }
+                    }
+
+                    render_frame(&mut app, (width, height), event, &mut current_buffer, &theme);
+                }
+                Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) => {
+                    let mut ui_event = UiEvent::Nothing;
+
+                    let focused = app.panes.get_focused();
+
+                    let buffer = &mut app.buffers[focused.buffer];
+
+                    let mut command = None;
+
+                    // the palette offers every command that can run here, keys only reach the focused panel
+                    let mut contexts = vec![Context::Global];
+
+                    if app.diff_view.is_some() {
+                        contexts.push(Context::Diff);
+                    } else {
+                        contexts.extend([Context::Workspace, Context::Editor]);
+
+                        if app.explorer_focused {
+                            contexts.push(Context::Explorer);
+                        }
+
+                        if buffer.snippet.is_some() {
+                            contexts.push(Context::Snippet);
+                        }
+                    }
+
+                    let key_contexts = if app.diff_view.is_some() {
+                        contexts.clone()
+                    } else if app.search_focused {
+                        vec![Context::Global, Context::Workspace]
+                    } else if app.explorer_focused {
+                        vec![Context::Global, Context::Workspace, Context::Explorer]
+                    } else {
+                        contexts.clone()
+                    };
+
+                    app.hover = None;
+
+                    if let Some(mut current) = app.dialog.take() {
+                        match current.handle_key(code, modifiers) {
+                            DialogResult::Pressed(index) => match (current.into_action(), index) {
+                                (DialogAction::Quit, 0) => {
+                                    let errors = app.buffers
+                                        .iter_mut()
+                                        .filter(|x| x.is_modified() && !x.is_scratch())
+                                        .filter_map(|x| save_buffer(x, false).err())
+                                        .collect::<Vec<_>>();
+
+                                    if !errors.is_empty() {
+                                        app.dialog = Some(Dialog::new(DialogAction::Notice, &errors.join("\n"), OK_BUTTONS));
+                                    } else {
+                                        quitting = prompt_for_scratch(&mut app.buffers, &mut app.panes, &mut app.prompt, &prompt_history);
+
+                                        if quitting.is_none() {
+                                            break;
+                                        }
+                                    }
+                                }
+                                (DialogAction::Quit, 1) => break,
+                                (DialogAction::CloseBuffer(x), 0 | 1) => {
+                                    let saved = if index == 0 { save_buffer(&mut app.buffers[x], false) } else { Ok(()) };
+
+                                    match saved {
+                                        Err(e) => app.dialog = Some(Dialog::new(DialogAction::Notice, &e, OK_BUTTONS)),
+                                        Ok(()) if app.buffers.len() == 1 => break,
+                                        Ok(()) => {
+                                            close_buffer(&mut app.buffers, &mut app.panes, x);
+                                            app.completion = None;
+                                        }
+                                    }
+                                }
+                                (DialogAction::DeleteFile, 0) => match app.explorer.as_mut().map(Explorer::delete) {
+                                    Some(Ok(x)) => app.message = Some(format!("deleted {}", x.to_string_lossy())),
+                                    Some(Err(e)) => app.message = Some(e),
+                                    None => (),
+                                },
+                                (DialogAction::Overwrite(kind, input), 0) => {
+                                    let result = match kind {
+                                        PromptKind::RenameFile | PromptKind::MoveFile => move_explorer_file(
+                                            &mut app.buffers,
+                                            app.explorer.as_mut(),
+                                            kind,
+                                            &input,
+                                            true,
+                                            &config,
+                                            &mut servers,
+                                        )
+                                        .map(|_| None),
+                                        _ => write_buffer_to(
+                                            kind,
+                                            &input,
+                                            &mut app.buffers[focused.buffer],
+                                            &config,
+                                            &grammars,
+                                            &mut servers,
+                                        ),
+                                    };
+
+                                    match result {
+                                        Ok(Some(x)) | Err(x) => app.message = Some(x),
+                                        Ok(None) => (),
+                                    }
+
+                                    if let Some(x) = app.explorer.as_mut() {
+                                        x.refresh();
+                                    }
+                                }
+                                (DialogAction::Reload(x), 0) => {
+                                    if let Err(e) = app.buffers[x].reload() {
+                                        app.message = Some(e);
+                                    }
+
+                                    check_disk = true;
+                                }
+                                (DialogAction::Reload(x), 1) => {
+                                    app.buffers[x].update_stamp();
+                                    check_disk = true;
+                                }
+                                (DialogAction::Reload(x), 2) | (DialogAction::SaveChanged(x), 1) => {
+                                    app.buffers[x].update_stamp();
+
+                                    let sources = [DiffSource::Saved(app.buffers[x].path.clone()), DiffSource::Buffer(x)];
+
+                                    match open_diff(&app.buffers, sources) {
+                                        Ok(session) => app.diff_view = Some(session),
+                                        Err(e) => app.message = Some(e),
+                                    }
+
+                                    check_disk = true;
+                                }
+                                (DialogAction::SaveChanged(x), 0) => {
+                                    if let Err(e) = save_buffer(&mut app.buffers[x], true) {
+                                        app.dialog = Some(Dialog::new(DialogAction::Notice, &e, OK_BUTTONS));
+                                    }
+                                }
+                                _ => (),
+                            },
+                            DialogResult::Cancelled => (),
+                            DialogResult::Pending => app.dialog = Some(current),
+                        }
+                    } else if find_command(code, modifiers, &[Context::Global]) == Some(Action::Quit) {
+                        command = Some(Action::Quit);
+                    } else if let Some(commands) = app.palette.as_mut() {
+                        match code {
+                            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => commands.push(c),
+                            KeyCode::Backspace => commands.pop(),
+                            KeyCode::Up => commands.move_selection(-1),
+                            KeyCode::Down => commands.move_selection(1),
+                            KeyCode::Enter => {
+                                if let Some(index) = commands.get_selected_command() {
+                                    remember_command(&mut recent_commands, index);
+                                    command = Some(COMMANDS[index].action);
+                                }
+
+                                app.palette = None;
+                            }
+                            KeyCode::Esc => app.palette = None,
+                            _ => (),
+                        }
+                    } else if let Some(session) = app.diff_view.as_mut() {
+                        let page = height.saturating_sub(3) as isize;
+
+                        match code {
+                            KeyCode::Up => session.view.scroll_by(-1),
+                            KeyCode::Down => session.view.scroll_by(1),
+                            KeyCode::PageUp => session.view.scroll_by(-page),
+                            KeyCode::PageDown => session.view.scroll_by(page),
+                            _ => command = find_command(code, modifiers, &key_contexts),
+                        }
+                    } else if let Some((kind, line)) = app.prompt.as_mut() {
+                        match line.handle_key(code, modifiers) {
+                            InputResult::Confirmed(input) => {
+                                let kind = *kind;
+
+                                add_to_history(prompt_history.entry(kind).or_default(), &input);
+
+                                app.prompt = None;
+
+                                match kind {
+                                    PromptKind::Rename => {
+                                        let position = buffer.get_cursor_position();
+
+                                        if let Some(x) = buffer.lsp.as_ref().filter(|_| !input.is_empty()) {
+                                            if let Err(e) = x.server.borrow_mut().rename(&x.uri, position, &input) {
+                                                app.message = Some(e);
+                                            }
+                                        }
+                                    }
+                                    PromptKind::Open if !input.is_empty() => {
+                                        if let Err(e) = open_buffer(
+                                            &mut app.buffers,
+                                            &mut app.panes,
+                                            PathBuf::from(input),
+                                            &config,
+                                            &grammars,
+                                            &mut servers,
+                                        ) {
+                                            app.message = Some(e);
+                                        }
+                                    }
+                                    PromptKind::Open => (),
+                                    PromptKind::CreateFile if !input.is_empty() => {
+                                        let created = app.explorer
+                                            .as_mut()
+                                            .map(|x| x.create(&input))
+                                            .transpose()
+                                            .and_then(|path| match path.filter(|x| x.is_file()) {
+                                                Some(x) => open_buffer(
+                                                    &mut app.buffers,
+                                                    &mut app.panes,
+                                                    x,
+                                                    &config,
+                                                    &grammars,
+                                                    &mut servers,
+                                                ),
+                                                None => Ok(()),
+                                            });
+
+                                        if let Err(e) = created {
+                                            app.message = Some(e);
+                                        }
+                                    }
+                                    PromptKind::RenameFile | PromptKind::MoveFile
+                                        if !input.is_empty() =>
+                                    {
+                                        let target = app.explorer.as_ref().map(|x| {
+                                            if kind == PromptKind::RenameFile {
+                                                x.get_rename_target(&input)
+                                            } else {
+                                                x.get_move_target(&input)
+                                            }
+                                        });
+
+                                        match target {
+                                            Some(Ok(x)) if x.is_file() => {
+                                                app.dialog = Some(Dialog::new(
+                                                    DialogAction::Overwrite(kind, input),
+                                                    &format!("{} already exists. Overwrite it?", x.to_string_lossy()),
+                                                    OVERWRITE_BUTTONS,
+                                                ));
+                                            }
+                                            _ => {
+                                                if let Err(e) = move_explorer_file(
+                                                    &mut app.buffers,
+                                                    app.explorer.as_mut(),
+                                                    kind,
+                                                    &input,
+                                                    false,
+                                                    &config,
+                                                    &mut servers,
+                                                ) {
+                                                    app.message = Some(e);
+                                                }
+                                            }
+                                        }
+                                    }
+                                    PromptKind::SaveAs | PromptKind::MoveBuffer | PromptKind::WriteSelection
+                                        if !input.is_empty() =>
+                                    {
+                                        let target = PathBuf::from(&input);
+
+                                        let same_file = kind != PromptKind::WriteSelection
+                                            && std::path::absolute(&target)
+                                                .is_ok_and(|x| std::path::absolute(&buffer.path).is_ok_and(|y| x == y));
+
+                                        if target.is_file() && !same_file {
+                                            app.dialog = Some(Dialog::new(
+                                                DialogAction::Overwrite(kind, input),
+                                                &format!("{} already exists. Overwrite it?", target.to_string_lossy()),
+                                                OVERWRITE_BUTTONS,
+                                            ));
+                                        } else {
+                                            match write_buffer_to(kind, &input, buffer, &config, &grammars, &mut servers) {
+                                                Ok(Some(x)) | Err(x) => app.message = Some(x),
+                                                Ok(None) => (),
+                                            }
+
+                                            if let Some(x) = app.explorer.as_mut() {
+                                                x.refresh();
+                                            }
+                                        }
+                                    }
+                                    PromptKind::Search => {
+                                        let started = std::env::current_dir()
+                                            .map_err(|e| e.to_string())
+                                            .and_then(|root| {
+                                                ProjectSearch::new(root, SearchQuery::parse(&input)?)
+                                            });
+
+                                        match started {
+                                            Ok(x) => {
+                                                app.search = Some(x);
+                                                app.search_focused = true;
+                                            }
+                                            Err(e) => app.message = Some(e),
+                                        }
+                                    }
+                                    PromptKind::ReplaceSearch => {
+                                        match SearchQuery::parse(&input) {
+                                            Ok(x) => {
+                                                replace_query = Some(x);
+                                                app.prompt = Some(PromptKind::ReplaceWith.open("", &prompt_history));
+                                            }
+                                            Err(e) => app.message = Some(e),
+                                        }
+                                    }
+                                    PromptKind::ReplaceWith => {
+                                        if let Some(query) = replace_query.take() {
+                                            let built = std::env::current_dir()
+                                                .map_err(|e| e.to_string())
+                                                .and_then(|root| ProjectReplace::new(root, &query, &input));
+
+                                            match built {
+                                                Ok(x) if x.is_empty() => {
+                                                    app.message = Some("nothing to replace".to_string())
+                                                }
+                                                Ok(x) => {
+                                                    app.preview = Some(x);
+                                                    app.search_focused = false;
+                                                }
+                                                Err(e) => app.message = Some(e),
+                                            }
+                                        }
+                                    }
+                                    PromptKind::CreateFile
+                                    | PromptKind::RenameFile
+                                    | PromptKind::MoveFile
+                                    | PromptKind::SaveAs
+                                    | PromptKind::MoveBuffer
+                                    | PromptKind::WriteSelection => (),
+                                }
+                            }
+                            InputResult::Cancelled => app.prompt = None,
+                            InputResult::Edited | InputResult::Ignored => (),
+                        }
+                    } else if let Some(selected) = app.switcher.as_mut() {
+                        match code {
+                            KeyCode::Up => *selected = selected.checked_sub(1).unwrap_or(app.buffers.len() - 1),
+                            KeyCode::Down => *selected = (*selected + 1) % app.buffers.len(),
+                            KeyCode::Enter => {
+                                show_buffer(
+                                    &mut app.buffers,
+                                    &mut app.panes,
+                                    focused.id,
+                                    *selected,
+                                );
+                                app.switcher = None;
+                            }
+                            _ => app.switcher = None,
+                        }
+                    } else if let Some(picker) = app.finder.as_mut() {
+                        match code {
+                            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => picker.push(c),
+                            KeyCode::Backspace => picker.pop(),
+                            KeyCode::Up => picker.move_selection(-1),
+                            KeyCode::Down => picker.move_selection(1),
+                            KeyCode::Enter => {
+                                if let Some(path) = picker.get_selected_path() {
+                                    if let Err(e) = open_buffer(
+                                        &mut app.buffers,
+                                        &mut app.panes,
+                                        path,
+                                        &config,
+                                        &grammars,
+                                        &mut servers,
+                                    ) {
+                                        app.message = Some(e);
+                                    }
+                                }
+
+                                app.finder = None;
+                            }
+                            KeyCode::Esc => app.finder = None,
+                            _ => (),
+                        }
+                    } else if let Some(changes) = app.preview.as_mut() {
+                        let page = (height / 3) as isize;
+
+                        match code {
+                            KeyCode::Up => changes.move_selection(-1),
+                            KeyCode::Down => changes.move_selection(1),
+                            KeyCode::PageUp => changes.move_selection(-page),
+                            KeyCode::PageDown => changes.move_selection(page),
+                            KeyCode::Char(' ') => changes.toggle(),
+                            KeyCode::Enter => match apply_replace(&mut app.buffers, changes) {
+                                Ok(x) => {
+                                    app.message = Some(x.get_summary());
+                                    last_replace = Some(x);
+                                    app.preview = None;
+                                }
+                                Err(e) => app.message = Some(e),
+                            },
+                            KeyCode::Esc => app.preview = None,
+                            _ => (),
+                        }
+                    } else if let Some(menu) = app.completion.as_mut().filter(|_| {
+                        !app.explorer_focused
+                            && !app.search_focused
+                            && matches!(code, KeyCode::Up | KeyCode::Down | KeyCode::Enter | KeyCode::Tab | KeyCode::Esc)
+                    }) {
+                        match code {
+                            KeyCode::Up => menu.move_selection(-1),
+                            KeyCode::Down => menu.move_selection(1),
+                            KeyCode::Enter | KeyCode::Tab => {
+                                menu.accept(buffer);
+                                app.completion = None;
+                            }
+                            _ => app.completion = None,
+                        }
+                    } else if let Some(action) = find_command(code, modifiers, &key_contexts) {
+                        command = Some(action);
+                    } else if let Some(results) =
+                        app.search.as_mut().filter(|_| app.search_focused)
+                    {
+                        let page = (height / 3) as isize;
+
+                        match code {
+                            KeyCode::Up => results.move_selection(-1),
+                            KeyCode::Down => results.move_selection(1),
+                            KeyCode::PageUp => results.move_selection(-page),
+                            KeyCode::PageDown => results.move_selection(page),
+                            KeyCode::Enter => {
+                                app.search_focused = false;
+
+                                if let Err(e) = jump_to_match(
+                                    &mut app.buffers,
+                                    &mut app.panes,
+                                    results,
+                                    &config,
+                                    &grammars,
+                                    &mut servers,
+                                ) {
+                                    app.message = Some(e);
+                                }
+                            }
+                            KeyCode::Esc => {
+                                app.search = None;
+                                app.search_focused = false;
+                            }
+                            _ => (),
+                        }
+                    } else if let Some(tree) =
+                        app.explorer.as_mut().filter(|_| app.explorer_focused)
+                    {
+                        let page = height.saturating_sub(1) as isize;
+
+                        match code {
+                            KeyCode::Up => tree.move_selection(-1),
+                            KeyCode::Down => tree.move_selection(1),
+                            KeyCode::PageUp => tree.move_selection(-page),
+                            KeyCode::PageDown => tree.move_selection(page),
+                            KeyCode::Left => tree.collapse(),
+                            KeyCode::Right => tree.expand(),
+                            KeyCode::Enter => {
+                                if let Some(path) = tree.activate() {
+                                    app.explorer_focused = false;
+
+                                    if let Err(e) = open_buffer(
+                                        &mut app.buffers,
+                                        &mut app.panes,
+                                        path,
+                                        &config,
+                                        &grammars,
+                                        &mut servers,
+                                    ) {
+                                        app.message = Some(e);
+                                    }
+                                }
+                            }
+                            KeyCode::Esc => app.explorer_focused = false,
+                            _ => (),
+                        }
+                    } else if buffer.snippet.is_some() && code == KeyCode::Esc {
+                        buffer.snippet = None;
+                    } else if code == KeyCode::Up {
+                        buffer.editor.move_cursor_vertical(-1, modifiers == KeyModifiers::SHIFT, false);
+                    } else if code == KeyCode::Down {
+                        buffer.editor.move_cursor_vertical(1, modifiers == KeyModifiers::SHIFT, false);
+                    } else if code == KeyCode::Left && modifiers.contains(KeyModifiers::CONTROL) {
+                        buffer.editor.move_cursor_horizontal_words(
+                            -1,
+                            modifiers.contains(KeyModifiers::SHIFT),
+                            true,
+                        );
+                    } else if code == KeyCode::Right && modifiers.contains(KeyModifiers::CONTROL) {
+                        buffer.editor.move_cursor_horizontal_words(
+                            1,
+                            modifiers.contains(KeyModifiers::SHIFT),
+                            true,
+                        );
+                    } else if code == KeyCode::Left {
+                        buffer.editor.move_cursor_horizontal(-1, modifiers == KeyModifiers::SHIFT, true);
+                    } else if code == KeyCode::Right {
+                        buffer.editor.move_cursor_horizontal(1, modifiers == KeyModifiers::SHIFT, true);
+                    } else if code == KeyCode::Home {
+                        buffer.editor.move_cursor_to_start_of_line(modifiers == KeyModifiers::SHIFT, true);
+                    } else if code == KeyCode::End {
+                        buffer.editor.move_cursor_to_end_of_line(modifiers == KeyModifiers::SHIFT, true);
+                    } else if code == KeyCode::PageUp {
+                        ui_event = UiEvent::ScrollPage(true);
+                    } else if code == KeyCode::PageDown {
+                        ui_event = UiEvent::ScrollPage(false);
+                    } else if let KeyCode::Char(c) = code {
+                        buffer.editor.insert_character_at_cursor(c);
+
+                        if let Some(x) = buffer
+                            .lsp
+                            .as_ref()
+                            .filter(|x| x.server.borrow().trigger_characters().contains(&c))
+                        {
+                            let position = buffer.get_cursor_position();
+
+                            x.server.borrow_mut().completion(&x.uri, position).ok();
+                        }
+                    } else if code == KeyCode::Enter {
+                        buffer.editor.insert_newline_at_cursor();
+                    } else if code == KeyCode::Tab {
+                        if !buffer.expand_snippet(&snippets) {
+                            buffer.editor.insert_tab_at_cursor();
+                        }
+                    } else if code == KeyCode::Backspace {
+                        buffer.editor.remove_character_or_selection_at_cursor(true);
+                    } else if code == KeyCode::Delete {
+                        buffer.editor.remove_character_or_selection_at_cursor(false);
+                    }
+
+                    if let Some(action) = command {
+                        let focused = app.panes.get_focused();
+                        let buffer = &mut app.buffers[focused.buffer];
+
+                        // the palette runs editor commands while a panel has focus, they take it back
+                        if matches!(action.get_context(), Context::Editor | Context::Snippet) {
+                            app.explorer_focused = false;
+                            app.search_focused = false;
+                        }
+
+                        match action {
+                            Action::Save => {
+                                if buffer.is_scratch() {
+                                    app.prompt = Some(PromptKind::SaveAs.open("", &prompt_history));
+                                } else if buffer.has_changed_on_disk() {
+                                    app.dialog = Some(Dialog::new(
+                                        DialogAction::SaveChanged(focused.buffer),
+                                        &format!("{} changed on disk since it was opened. Overwrite it?", buffer.get_name()),
+                                        SAVE_CHANGED_BUTTONS,
+                                    ));
+                                } else if let Err(e) = save_buffer(buffer, false) {
+                                    app.dialog = Some(Dialog::new(DialogAction::Notice, &e, OK_BUTTONS));
+                                }
+                            }
+                            Action::Undo => buffer.editor.undo(),
+                            Action::Redo => buffer.editor.redo(),
+                            Action::DiscardChanges => buffer.editor.discard_changes(),
+                            Action::SaveAs => {
+                                let path = buffer.path.to_string_lossy().to_string();
+
+                                app.prompt = Some(PromptKind::SaveAs.open(&path, &prompt_history));
+                                app.completion = None;
+                            }
+                            Action::MoveBuffer => {
+                                if buffer.is_scratch() {
+                                    app.message = Some(format!("{SCRATCH_NAME} has no file to rename yet"));
+                                } else {
+                                    let path = buffer.path.to_string_lossy().to_string();
+
+                                    app.prompt = Some(PromptKind::MoveBuffer.open(&path, &prompt_history));
+                                    app.completion = None;
+                                }
+                            }
+                            Action::WriteSelection => {
+                                if buffer.editor.get_selection_range().is_some() {
+                                    app.prompt = Some(PromptKind::WriteSelection.open("", &prompt_history));
+                                    app.completion = None;
+                                } else {
+                                    app.message = Some("nothing selected".to_string());
+                                }
+                            }
+                            Action::NextChange | Action::PreviousChange => {
+                                buffer.sync_changes();
+                                buffer.update_git();
+
+                                let line = buffer.editor.get_current_line();
+
+                                let target = buffer.git.as_ref().and_then(|x| {
+                                    if action == Action::NextChange {
+                                        x.get_next_hunk(line)
+                                    } else {
+                                        x.get_previous_hunk(line)
+                                    }
+                                });
+
+                                match target {
+                                    Some(x) => {
+                                        let cursor = buffer.editor.position_to_byte(x, 0);
+
+                                        buffer.editor.set_cursor(cursor);
+                                    }
+                                    None => app.message = Some("no changes".to_string()),
+                                }
+                            }
+                            Action::PreviewChange => {
+                                buffer.sync_changes();
+                                buffer.update_git();
+
+                                let line = buffer.editor.get_current_line();
+
+                                let preview = buffer.git.as_ref().and_then(|x| {
+                                    x.get_hunk_at(line)
+                                        .map(|y| x.get_preview(y, buffer.editor.get_text()))
+                                });
+
+                                match preview {
+                                    Some(x) => app.hover = Some(x),
+                                    None => {
+                                        app.message = Some("no change on this line".to_string())
+                                    }
+                                }
+                            }
+                            Action::RevertChange => {
+                                buffer.sync_changes();
+                                buffer.update_git();
+
+                                let line = buffer.editor.get_current_line();
+
+                                let revert = buffer.git.as_ref().and_then(|x| {
+                                    x.get_hunk_at(line)
+                                        .map(|y| (y.new.clone(), x.get_base_text(y)))
+                                });
+
+                                match revert {
+                                    Some((lines, text)) => {
+                                        let start = buffer.editor.position_to_byte(lines.start, 0);
+                                        let end = buffer.editor.position_to_byte(lines.end, 0);
+
+                                        buffer.editor.replace_range(start, end, &text, false);
+                                        buffer.editor.set_cursor(start);
+                                    }
+                                    None => {
+                                        app.message = Some("no change on this line".to_string())
+                                    }
+                                }
+                            }
+                            Action::DiffSaved => {
+                                if buffer.is_scratch() {
+                                    app.message = Some(format!("{SCRATCH_NAME} has no saved file to compare with"));
+                                } else {
+                                    let sources = [DiffSource::Saved(buffer.path.clone()), DiffSource::Buffer(focused.buffer)];
+
+                                    match open_diff(&app.buffers, sources) {
+                                        Ok(x) => {
+                                            app.diff_view = Some(x);
+                                            app.completion = None;
+                                        }
+                                        Err(e) => app.message = Some(e),
+                                    }
+                                }
+                            }
+                            Action::NextConflict | Action::PreviousConflict => {
+                                buffer.sync_changes();
+
+                                let line = buffer.editor.get_current_line();
+
+                                let target = if action == Action::PreviousConflict {
+                                    get_previous_conflict(&buffer.conflicts, line)
+                                } else {
+                                    get_next_conflict(&buffer.conflicts, line)
+                                };
+
+                                match target {
+                                    Some(x) => {
+                                        let start = buffer.conflicts[x].lines.start;
+                                        let cursor = buffer.editor.position_to_byte(start, 0);
+
+                                        buffer.editor.set_cursor(cursor);
+                                        app.message =
+                                            Some(format!("conflict {} of {}", x + 1, buffer.conflicts.len()));
+                                    }
+                                    None => app.message = Some("no conflicts".to_string()),
+                                }
+                            }
+                            Action::KeepInConflict(resolution) => {
+                                buffer.sync_changes();
+
+                                let line = buffer.editor.get_current_line();
+
+                                let resolved = match get_conflict_at(&buffer.conflicts, line) {
+                                    Some(x) => x
+                                        .resolve(buffer.editor.get_text(), resolution)
+                                        .map(|y| (x.lines.clone(), y)),
+                                    None => Err("no conflict at the cursor".to_string()),
+                                };
+
+                                match resolved {
+                                    Ok((lines, text)) => {
+                                        let start = buffer.editor.position_to_byte(lines.start, 0);
+                                        let end = buffer.editor.position_to_byte(lines.end, 0);
+
+                                        buffer.editor.replace_range(start, end, &text, false);
+                                        buffer.editor.set_cursor(start);
+                                    }
+                                    Err(e) => app.message = Some(e),
+                                }
+                            }
+                            Action::Copy => {
+                                if let Some(x) = buffer.editor.get_selection() {
+                                    clip = x;
+                                }
+                            }
+                            Action::Cut => {
+                                if let Some(x) = buffer.editor.cut_selection() {
+                                    clip = x;
+                                }
+                            }
+                            Action::Paste => {
+                                if !clip.is_empty() {
+                                    buffer.editor.insert_string_at_cursor(&clip);
+                                }
+                            }
+                            Action::CopySystem => {
+                                if let Some(x) = buffer.editor.get_selection() {
+                                    system_clip.as_mut().map(|y| y.set_text(x));
+                                }
+                            }
+                            Action::CutSystem => {
+                                if let Some(x) = buffer.editor.cut_selection() {
+                                    system_clip.as_mut().map(|y| y.set_text(x));
+                                }
+                            }
+                            Action::PasteSystem => {
+                                if let Some(x) = system_clip.as_mut() {
+                                    if let Ok(y) = x.get_text() {
+                                        if !y.is_empty() {
+                                            buffer.editor.insert_string_at_cursor(&y);
+                                        }
+                                    }
+                                }
+                            }
+                            Action::NewBuffer => {
+                                let index = app.buffers.len();
+
+                                app.buffers.push(load_buffer(PathBuf::new(), "", true, &config, &grammars));
+
+                                show_buffer(&mut app.buffers, &mut app.panes, focused.id, index);
+                                app.completion = None;
+                            }
+                            Action::OpenFile => {
+                                let directory = buffer
+                                    .path
+                                    .parent()
+                                    .map(|x| x.to_string_lossy().to_string())
+                                    .filter(|x| !x.is_empty())
+                                    .map(|x| format!("{x}/"))
+                                    .unwrap_or_default();
+
+                                app.prompt = Some(PromptKind::Open.open(
+                                    &directory, &prompt_history,
+                                ));
+                                app.completion = None;
+                            }
+                            Action::FindFile => {
+                                match std::env::current_dir() {
+                                    Ok(x) => app.finder = Some(FileFinder::new(x)),
+                                    Err(e) => app.message = Some(e.to_string()),
+                                }
+
+                                app.completion = None;
+                            }
+                            Action::CloseBuffer => {
+                                if buffer.is_modified() {
+                                    app.dialog = Some(Dialog::new(
+                                        DialogAction::CloseBuffer(focused.buffer),
+                                        &format!("Save changes to {} before closing?", buffer.get_name()),
+                                        SAVE_BUTTONS,
+                                    ));
+                                } else if app.buffers.len() == 1 {
+                                    break;
+                                } else {
+                                    close_buffer(&mut app.buffers, &mut app.panes, focused.buffer);
+                                    app.completion = None;
+                                }
+                            }
+                            Action::NextBuffer => {
+                                let next = (focused.buffer + 1) % app.buffers.len();
+
+                                show_buffer(&mut app.buffers, &mut app.panes, focused.id, next);
+                                app.completion = None;
+                            }
+                            Action::PreviousBuffer => {
+                                let previous = focused.buffer.checked_sub(1).unwrap_or(app.buffers.len() - 1);
+
+                                show_buffer(&mut app.buffers, &mut app.panes, focused.id, previous);
+                                app.completion = None;
+                            }
+                            Action::SwitchBuffer => {
+                                app.switcher = Some(focused.buffer);
+                                app.completion = None;
+                            }
+                            Action::Search => {
+                                app.prompt = Some(PromptKind::Search.open("", &prompt_history));
+                                app.completion = None;
+                            }
+                            Action::NextSearchResult | Action::PreviousSearchResult => {
+                                if let Some(results) = app.search.as_mut() {
+                                    results.move_selection(if action == Action::PreviousSearchResult { -1 } else { 1 });
+
+                                    if let Err(e) = jump_to_match(
+                                        &mut app.buffers,
+                                        &mut app.panes,
+                                        results,
+                                        &config,
+                                        &grammars,
+                                        &mut servers,
+                                    ) {
+                                        app.message = Some(e);
+                                    }
+
+                                    app.completion = None;
+                                }
+                            }
+                            Action::Replace => {
+                                app.prompt = Some(PromptKind::ReplaceSearch.open(
+                                    "", &prompt_history,
+                                ));
+                                app.completion = None;
+                            }
+                            Action::UndoReplace => {
+                                match last_replace
+                                    .as_ref()
+                                    .map(|x| undo_replace(&mut app.buffers, x))
+                                {
+                                    Some(Ok(x)) => {
+                                        app.message = Some(x);
+                                        last_replace = None;
+                                    }
+                                    Some(Err(e)) => app.message = Some(e),
+                                    None => app.message = Some("no project replace to undo".to_string()),
+                                }
+
+                                app.completion = None;
+                            }
+                            Action::ToggleExplorer => {
+                                match app.explorer.as_mut() {
+                                    Some(_) if app.explorer_focused => {
+                                        app.explorer = None;
+                                        app.explorer_focused = false;
+                                    }
+                                    Some(x) => {
+                                        x.refresh();
+                                        app.explorer_focused = true;
+                                    }
+                                    None => match std::env::current_dir() {
+                                        Ok(x) => {
+                                            app.explorer = Some(Explorer::new(x));
+                                            app.explorer_focused = true;
+                                        }
+                                        Err(e) => app.message = Some(e.to_string()),
+                                    },
+                                }
+
+                                app.completion = None;
+                            }
+                            Action::CreateFile => app.prompt = Some(PromptKind::CreateFile.open("", &prompt_history)),
+                            Action::RenameFile => {
+                                if let Some(x) = app.explorer.as_ref().and_then(Explorer::get_selected_entry) {
+                                    let name = x
+                                        .path
+                                        .file_name()
+                                        .map(|x| x.to_string_lossy().to_string())
+                                        .unwrap_or_default();
+
+                                    app.prompt = Some(PromptKind::RenameFile.open(&name, &prompt_history));
+                                }
+                            }
+                            Action::MoveFile => {
+                                if let Some(tree) = app.explorer.as_ref() {
+                                    if let Some(x) = tree.get_selected_entry() {
+                                        let path = tree.get_relative_path(&x.path);
+
+                                        app.prompt = Some(PromptKind::MoveFile.open(&path, &prompt_history));
+                                    }
+                                }
+                            }
+                            Action::DeleteFile => {
+                                if let Some(tree) = app.explorer.as_ref() {
+                                    if let Some(x) = tree.get_selected_entry() {
+                                        app.dialog = Some(Dialog::new(
+                                            DialogAction::DeleteFile,
+                                            &format!("Delete {}? This can't be undone.", tree.get_relative_path(&x.path)),
+                                            DELETE_BUTTONS,
+                                        ));
+                                    }
+                                }
+                            }
+                            Action::Split(orientation) => {
+                                app.panes.split(orientation, buffer.editor.add_view());
+                                app.completion = None;
+                            }
+                            Action::ClosePane => {
+                                if let Some(pane) = app.panes.close() {
+                                    buffer.editor.remove_view(pane.view);
+                                    app.completion = None;
+                                }
+                            }
+                            Action::FocusPane(towards) => {
+                                let areas = get_areas(
+                                    app.explorer.as_ref(),
+                                    app.search.is_some() || app.preview.is_some(),
+                                    width as usize,
+                                    height as usize,
+                                );
+
+                                if app.panes.focus_towards(towards, areas.panes) {
+                                    app.completion = None;
+                                }
+                            }
+                            Action::ResizePane(towards) => {
+                                app.panes.resize(towards);
+                            }
+                            Action::CompleteWord => {
+                                buffer.sync_changes();
+
+                                match buffer.lsp.as_ref() {
+                                    Some(x) => {
+                                        let position = buffer.get_cursor_position();
+
+                                        if let Err(e) = x.server.borrow_mut().completion(&x.uri, position) {
+                                            app.message = Some(e);
+                                        }
+                                    }
+                                    None => {
+                                        let indexes = app.buffers.iter().map(|x| &x.words).collect::<Vec<_>>();
+
+                                        app.completion = CompletionMenu::from_words(&indexes, &app.buffers[focused.buffer]);
+
+                                        if app.completion.is_none() {
+                                            app.message = Some("no completions".to_string());
+                                        }
+                                    }
+                                }
+                            }
+                            Action::NextSnippetField | Action::PreviousSnippetField => {
+                                buffer.move_snippet_stop(action == Action::NextSnippetField);
+                            }
+                            Action::ShowHover => {
+                                if let Some(x) = buffer.lsp.as_ref() {
+                                    let position = buffer.get_cursor_position();
+
+                                    if let Err(e) = x.server.borrow_mut().hover(&x.uri, position) {
+                                        app.message = Some(e);
+                                    }
+                                }
+                            }
+                            Action::GoToDefinition => {
+                                if let Some(x) = buffer.lsp.as_ref() {
+                                    let position = buffer.get_cursor_position();
+
+                                    if let Err(e) = x.server.borrow_mut().definition(&x.uri, position) {
+                                        app.message = Some(e);
+                                    }
+                                }
+                            }
+                            Action::RenameSymbol => {
+                                if buffer.lsp.is_some() {
+                                    let text = buffer.editor.get_text();
+                                    let cursor = buffer.editor.get_cursor();
+
+                                    let word = text
+                                        .byte_slice(word_start(text, cursor)..word_end(text, cursor))
+                                        .to_string();
+
+                                    app.prompt = Some(PromptKind::Rename.open(
+                                        &word, &prompt_history,
+                                    ));
+                                }
+                            }
+                            Action::NextDiffChange | Action::PreviousDiffChange => {
+                                if let Some(session) = app.diff_view.as_mut() {
+                                    if action == Action::NextDiffChange {
+                                        session.view.select_next();
+                                    } else {
+                                        session.view.select_previous();
+                                    }
+                                }
+                            }
+                            Action::CopyChange(to) => {
+                                if let Some(session) = app.diff_view.as_mut() {
+                                    if let Err(e) =
+                                        copy_diff_change(&mut app.buffers, session, to)
+                                    {
+                                        app.message = Some(e);
+                                    }
+                                }
+                            }
+                            Action::SaveDiff => {
+                                if let Some(session) = app.diff_view.as_mut() {
+                                    if let Err(e) = save_diff(&mut app.buffers, session) {
+                                        app.dialog = Some(Dialog::new(DialogAction::Notice, &e, OK_BUTTONS));
+                                    }
+                                }
+                            }
+                            Action::CloseDiff => app.diff_view = None,
+                            Action::CommandPalette => {
+                                app.palette =
+                                    Some(CommandPalette::new(&recent_commands, &contexts));
+                                app.completion = None;
+                            }
+                            Action::Quit => {
+                                let unsaved = app.buffers
+                                    .iter()
+                                    .filter(|x| x.is_modified())
+                                    .map(Buffer::get_name)
+                                    .collect::<Vec<_>>();
+
+                                if unsaved.is_empty() {
+                                    break;
+                                }
+
+                                app.dialog = Some(Dialog::new(
+                                    DialogAction::Quit,
+                                    &format!("Save changes to {} before quitting?", unsaved.join(", ")),
+                                    SAVE_BUTTONS,
+                                ));
+                            }
+                        }
+                    }
+
+                    // quitting goes on once the scratch buffer is saved, and stops when that was cancelled
+                    if let Some(x) =
+                        quitting.filter(|_| app.prompt.is_none() && app.dialog.is_none())
+                    {
+                        quitting = None;
+
+                        if !app.buffers[x].is_modified() {
+                            quitting = prompt_for_scratch(&mut app.buffers, &mut app.panes, &mut app.prompt, &prompt_history);
+
+                            if quitting.is_none() {
+                                break;
+                            }
+                        }
+                    }
+
+                    if let Some(x) = app.explorer.as_mut() {
+                        x.scroll_to_selected(height.saturating_sub(1) as usize);
+                    }
+
+                    let areas = get_areas(
+                        app.explorer.as_ref(),
+                        app.search.is_some() || app.preview.is_some(),
+                        width as usize,
+                        height as usize,
+                    );
+
+                    if let Some((x, area)) = app.search.as_mut().zip(areas.results) {
+                        x.scroll_to_selected(area.height.saturating_sub(1) as usize);
+                    }
+
+                    if let Some((x, area)) = app.preview.as_mut().zip(areas.results) {
+                        x.scroll_to_selected(area.height.saturating_sub(1) as usize);
+                    }
+
+                    let focused = app.panes.get_focused();
+                    let buffer = &mut app.buffers[focused.buffer];
+
+                    buffer.editor.set_view(focused.view);
+                    buffer.update_snippet();
+
+                    if app.completion.as_mut().is_some_and(|x| !x.update(buffer)) {
+                        app.completion = None;
+                    }
+
+                    render_frame(&mut app, (width, height), ui_event, &mut current_buffer, &theme);
+                }
+
+                Event::Resize(..) => {
+                    width = size().unwrap().0;
+                    height = size().unwrap().1;
+
+                    current_buffer.clear();
+                    render_frame(&mut app, (width, height), UiEvent::Nothing, &mut current_buffer, &theme);
+                }
+                _ => (),
+            }
+        } else {
+            let mut redraw = false;
+
+            if let Some(x) = app.finder.as_mut() {
+                x.poll();
+            }
+
+            let paths = app.buffers
+                .iter()
+                .filter(|x| !x.is_scratch())
+                .map(|x| x.path.as_path())
+                .collect::<Vec<_>>();
+
+            watcher.watch(&paths);
+
+            check_disk |= watcher.poll();
+
+            if check_disk && app.dialog.is_none() {
+                check_disk = false;
+
+                if let Some(x) = reload_changed_buffers(&mut app.buffers, &mut app.message) {
+                    app.dialog = Some(Dialog::new(
+                        DialogAction::Reload(x),
+                        &format!(
+                            "{} changed on disk. Reload it and lose your changes, keep your version or compare them?",
+                            app.buffers[x].get_name()
+                        ),
+                        RELOAD_BUTTONS,
+                    ));
+                }
+            }
+
+            if let Some(x) = app.search.as_mut() {
+                x.poll();
+            }
+
+            match config.reload_if_changed() {
+                Some(Ok(())) => {
+                    app.message = None;
+
+                    for buffer in app.buffers.iter_mut() {
+                        let next_settings = config.settings_for(&buffer.path);
+
+                        if next_settings.tab_width != buffer.settings.tab_width {
+                            buffer.editor.set_layout_settings(
+                                TermLineLayoutSettings::new(next_settings.tab_width),
+                                next_settings.tab_width,
+                            );
+                        }
+
+                        let restart_language_server =
+                            next_settings.language_server != buffer.settings.language_server;
+
+                        buffer.settings = next_settings;
+
+                        if restart_language_server {
+                            buffer.detach_language_server();
+
+                            if let Err(e) = start_language_server(buffer, &config, &mut servers) {
+                                app.message = Some(e);
+                            }
+                        }
+                    }
+
+                    let next_settings = config.settings_for(&app.buffers[0].path);
+
+                    if next_settings.disable_mouse_interaction
+                        != settings.disable_mouse_interaction
+                    {
+                        set_mouse_capture(!next_settings.disable_mouse_interaction);
+                    }
+
+                    if next_settings.theme != settings.theme {
+                        match Theme::load(&next_settings.theme, color_support) {
+                            Ok(x) => {
+                                theme = x;
+                                redraw = true;
+                            }
+                            Err(e) => app.message = Some(e),
+                        }
+                    }
+
+                    settings = next_settings;
+                }
+                Some(Err(e)) => app.message = Some(e.to_string()),
+                None => (),
+            }
+
+            if redraw {
+                current_buffer.clear();
+            }
+
+            render_frame(&mut app, (width, height), UiEvent::Nothing, &mut current_buffer, &theme);
+        }
+    }
+
+    servers.shutdown();
+
+    cleanup_terminal("Done");
+}
+
+fn load_buffer(
+    path: PathBuf,
+    content: &str,
+    newly_loaded: bool,
+    config: &Config,
+    grammars: &GrammarSet,
+) -> Buffer {
+    let settings = config.settings_for(&path);
+
+    Buffer::new(
+        TextEditor::new(
+            content,
+            TermLineLayoutSettings::new(settings.tab_width),
+            settings.tab_width,
+            newly_loaded,
+        ),
+        path,
+        settings,
+        grammars,
+    )
+}
+
+fn read_file(path: &Path) -> std::io::Result<(String, bool)> {
+    match std::fs::read_to_string(path) {
+        Ok(x) => Ok((x, false)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok((String::new(), true)),
+        Err(e) => Err(e),
+    }
+}
+
+fn get_tab_labels(buffers: &[Buffer]) -> Vec<String> {
+    buffers
+        .iter()
+        .map(|buffer| {
+            let name = buffer.get_name();
+
+            let label = if !buffer.is_scratch() && buffers.iter().filter(|x| x.get_name() == name).count() > 1 {
+                buffer.path.to_string_lossy().to_string()
+            } else {
+                name
+            };
+
+            if buffer.is_modified() {
+                format!("{label}*")
+            } else {
+                label
+            }
+        })
+        .collect()
+}
+
+fn pick_view(buffers: &mut [Buffer], panes: &Panes, pane_id: usize, index: usize) -> usize {
+    let shown_elsewhere = panes
+        .panes()
+        .iter()
+        .any(|x| x.id != pane_id && x.buffer == index);
+
+    if shown_elsewhere {
+        buffers[index].editor.add_view()
+    } else {
+        buffers[index].editor.get_view()
+    }
+}
+
+fn show_buffer(buffers: &mut [Buffer], panes: &mut Panes, pane_id: usize, index: usize) {
+    let Some(pane) = panes.panes().into_iter().find(|x| x.id == pane_id) else {
+        return;
+    };
+
+    if pane.buffer == index || index >= buffers.len() {
+        return;
+    }
+
+    let view = pick_view(buffers, panes, pane.id, index);
+
+    if panes
+        .panes()
+        .iter()
+        .any(|x| x.id != pane.id && x.buffer == pane.buffer)
+    {
+        buffers[pane.buffer].editor.remove_view(pane.view);
+    }
+
+    panes.set_pane(Pane {
+        buffer: index,
+        view,
+        ..pane
+    });
+
+    buffers[index].editor.set_view(view);
+}
+
+// shows the next unsaved buffer, a scratch one once the others are saved, and asks where to save it
+fn prompt_for_scratch(
+    buffers: &mut [Buffer],
+    panes: &mut Panes,
+    prompt: &mut Option<(PromptKind, InputLine)>,
+    prompt_history: &HashMap<PromptKind, Vec<String>>,
+) -> Option<usize> {
+    let index = buffers.iter().position(Buffer::is_modified)?;
+
+    show_buffer(buffers, panes, panes.get_focused().id, index);
+
+    *prompt = Some(PromptKind::SaveAs.open("", prompt_history));
+
+    Some(index)
+}
+
+fn close_buffer(buffers: &mut Vec<Buffer>, panes: &mut Panes, index: usize) {
+    buffers.remove(index).detach_language_server();
+
+    let replacement = index.min(buffers.len() - 1);
+
+    let mut closed = Vec::new();
+
+    for pane in panes.panes() {
+        let buffer = match pane.buffer.cmp(&index) {
+            std::cmp::Ordering::Less => continue,
+            std::cmp::Ordering::Equal => {
+                closed.push(pane);
+                usize::MAX
+            }
+            std::cmp::Ordering::Greater => pane.buffer - 1,
+        };
+
+        panes.set_pane(Pane { buffer, ..pane });
+    }
+
+    for pane in closed {
+        let view = pick_view(buffers, panes, pane.id, replacement);
+
+        panes.set_pane(Pane {
+            buffer: replacement,
+            view,
+            ..pane
+        });
+    }
+
+    let focused = panes.get_focused();
+
+    buffers[focused.buffer].editor.set_view(focused.view);
+}
+
+fn get_popup_lines(
+    switcher: Option<(Vec<String>, usize)>,
+    completion: Option<&CompletionMenu>,
+    hover: Option<&[String]>,
+) -> Option<(Vec<String>, Option<usize>)> {
+    match (switcher, completion, hover) {
+        (Some((x, y)), _, _) => Some((x, Some(y))),
+        (None, Some(x), _) => Some((x.get_lines(), Some(x.get_selected()))),
+        (None, None, Some(x)) => Some((x.to_vec(), None)),
+        (None, None, None) => None,
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum PromptKind {
+    Rename,
+    Open,
+    CreateFile,
+    RenameFile,
+    MoveFile,
+    SaveAs,
+    MoveBuffer,
+    WriteSelection,
+    Search,
+    ReplaceSearch,
+    ReplaceWith,
+}
+
+impl PromptKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Rename => "Rename to: ",
+            Self::Open => "Open: ",
+            Self::CreateFile => "New file (end with / for a folder): ",
+            Self::RenameFile => "Rename file to: ",
+            Self::MoveFile => "Move file to: ",
+            Self::SaveAs => "Save as: ",
+            Self::MoveBuffer => "Rename this file to: ",
+            Self::WriteSelection => "Write selection to: ",
+            Self::Search => "Search in files (-r regex, -c match case, -g [!]glob): ",
+            Self::ReplaceSearch => "Replace in files (-r regex, -c match case, -g [!]glob): ",
+            Self::ReplaceWith => "Replace with: ",
+        }
+    }
+
+    fn open(self, text: &str, history: &HashMap<PromptKind, Vec<String>>) -> (Self, InputLine) {
+        let mut line = InputLine::new(text).with_history(history.get(&self).map_or(&[], Vec::as_slice));
+
+        line = match self {
+            Self::Open
+            | Self::MoveFile
+            | Self::SaveAs
+            | Self::MoveBuffer
+            | Self::WriteSelection => line.with_completion(complete_path),
+            Self::Search | Self::ReplaceSearch => line.with_validation(validate_search),
+            _ => line,
+        };
+
+        (self, line)
+    }
+}
+
+const SAVE_BUTTONS: &[&str] = &["Save", "Don't save", "Cancel"];
+
+const DELETE_BUTTONS: &[&str] = &["Delete", "Cancel"];
+
+const OVERWRITE_BUTTONS: &[&str] = &["Overwrite", "Cancel"];
+
+const OK_BUTTONS: &[&str] = &["OK"];
+
+const RELOAD_BUTTONS: &[&str] = &["Reload", "Keep mine", "Diff"];
+
+const SAVE_CHANGED_BUTTONS: &[&str] = &["Overwrite", "Diff", "Cancel"];
+
+enum DialogAction {
+    Quit,
+    CloseBuffer(usize),
+    DeleteFile,
+    Overwrite(PromptKind, String),
+    Notice,
+    Reload(usize),
+    SaveChanged(usize),
+}
+
+fn get_dialog_box(dialog: &Dialog<DialogAction>) -> DialogBox<'_> {
+    DialogBox::new(dialog.get_message(), dialog.get_buttons(), dialog.get_selected())
+}
+
+fn get_modal<'a>(
+    dialog_box: Option<&'a DialogBox>,
+    picker: Option<&'a Picker>,
+) -> Option<&'a dyn Widget<TerminalBuffer, UiEvent, Vec<UiReaction>>> {
+    match dialog_box {
+        Some(x) => Some(x),
+        None => picker.map(|x| x as _),
+    }
+}
+
+fn validate_search(input: &str) -> Result<(), String> {
+    if input.trim().is_empty() {
+        return Ok(());
+    }
+
+    SearchQuery::parse(input)?.build_regex().map(|_| ())
+}
+
+fn get_prompt(kind: PromptKind, line: &InputLine) -> Prompt<'_> {
+    let feedback = match (line.get_error(), line.get_hint()) {
+        (Some(x), _) => Some((x, Highlight::ErrorSign)),
+        (None, Some(x)) => Some((x, Highlight::Comment)),
+        (None, None) => None,
+    };
+
+    Prompt::new(kind.label(), line.get_text(), line.get_cursor(), feedback)
+}
+
+fn open_buffer(
+    buffers: &mut Vec<Buffer>,
+    panes: &mut Panes,
+    path: PathBuf,
+    config: &Config,
+    grammars: &GrammarSet,
+    servers: &mut LanguageServers,
+) -> Result<(), String> {
+    let absolute = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+
+    let existing = buffers
+        .iter()
+        .position(|x| std::path::absolute(&x.path).is_ok_and(|x| x == absolute));
+
+    let index = match existing {
+        Some(x) => x,
+        None => {
+            let (content, newly_loaded) =
+                read_file(&path).map_err(|e| format!("{}: {e}", path.to_string_lossy()))?;
+
+            let mut buffer = load_buffer(path, &content, newly_loaded, config, grammars);
+
+            let result = start_language_server(&mut buffer, config, servers);
+
+            buffers.push(buffer);
+
+            result?;
+
+            buffers.len() - 1
+        }
+    };
+
+    show_buffer(buffers, panes, panes.get_focused().id, index);
+
+    Ok(())
+}
+
+fn jump_to_match(
+    buffers: &mut Vec<Buffer>,
+    panes: &mut Panes,
+    search: &ProjectSearch,
+    config: &Config,
+    grammars: &GrammarSet,
+    servers: &mut LanguageServers,
+) -> Result<(), String> {
+    let Some((path, found)) = search.get_selected_match() else {
+        return Ok(());
+    };
+
+    let (line, range) = (found.line, found.range.clone());
+
+    open_buffer(buffers, panes, path, config, grammars, servers)?;
+
+    let editor = &mut buffers[panes.get_focused().buffer].editor;
+
+    let text = editor.get_text();
+
+    if line < text.len_lines() {
+        let start = text.line_to_byte(line);
+        let length = text.line(line).len_bytes();
+
+        editor.set_selection(start + range.start.min(length)..start + range.end.min(length));
+    }
+
+    Ok(())
+}
+
+fn move_open_buffers(
+    buffers: &mut [Buffer],
+    from: &Path,
+    to: &Path,
+    config: &Config,
+    servers: &mut LanguageServers,
+) -> Result<(), String> {
+    let mut result = Ok(());
+
+    for buffer in buffers.iter_mut() {
+        let Ok(path) = std::path::absolute(&buffer.path) else {
+            continue;
+        };
+
+        let Ok(rest) = path.strip_prefix(from) else {
+            continue;
+        };
+
+        buffer.path = if rest.as_os_str().is_empty() {
+            to.to_path_buf()
+        } else {
+            to.join(rest)
+        };
+
+        buffer.detach_language_server();
+
+        if let Err(e) = start_language_server(buffer, config, servers) {
+            result = Err(e);
+        }
+    }
+
+    result
+}
+
+fn write_file(path: &Path, content: &str, backup: Backup) -> Result<(), String> {
+    save_file(path, content.as_bytes(), backup)
+        .map_err(|e| format!("couldn't save {}: {e}", path.to_string_lossy()))
+}
+
+fn save_buffer(buffer: &mut Buffer, overwrite: bool) -> Result<(), String> {
+    if buffer.is_scratch() {
+        return Err(format!("{SCRATCH_NAME} has no file yet, save it with ctrl+s first"));
+    }
+
+    if !overwrite && buffer.has_changed_on_disk() {
+        return Err(format!("{} changed on disk, save it with ctrl+s to overwrite it", buffer.get_name()));
+    }
+
+    write_file(&buffer.path, &buffer.editor.to_string(), buffer.settings.backup)?;
+
+    buffer.editor.set_saved();
+    buffer.notify_saved();
+    buffer.reload_git();
+    buffer.update_stamp();
+
+    Ok(())
+}
+
+fn set_buffer_path(
+    buffer: &mut Buffer,
+    path: PathBuf,
+    config: &Config,
+    grammars: &GrammarSet,
+    servers: &mut LanguageServers,
+) -> Result<(), String> {
+    buffer.detach_language_server();
+    buffer.set_path(path.clone(), config.settings_for(&path), grammars);
+
+    start_language_server(buffer, config, servers)
+}
+
+fn write_buffer_to(
+    kind: PromptKind,
+    input: &str,
+    buffer: &mut Buffer,
+    config: &Config,
+    grammars: &GrammarSet,
+    servers: &mut LanguageServers,
+) -> Result<Option<String>, String> {
+    let path = PathBuf::from(input);
+
+    match kind {
+        PromptKind::SaveAs => {
+            write_file(&path, &buffer.editor.to_string(), buffer.settings.backup)?;
+
+            buffer.editor.set_saved();
+
+            set_buffer_path(buffer, path, config, grammars, servers)?;
+
+            Ok(None)
+        }
+        PromptKind::MoveBuffer => {
+            if path.is_dir() {
+                return Err(format!("{input} is a folder"));
+            }
+
+            if buffer.path.exists() {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+
+                std::fs::rename(&buffer.path, &path).map_err(|e| {
+                    format!("couldn't rename {} to {input}: {e}", buffer.path.to_string_lossy())
+                })?;
+            }
+
+            set_buffer_path(buffer, path, config, grammars, servers)?;
+
+            Ok(None)
+        }
+        _ => {
+            let Some(text) = buffer.editor.get_selection() else {
+                return Err("nothing selected".to_string());
+            };
+
+            write_file(&path, &text, buffer.settings.backup)?;
+
+            Ok(Some(format!("wrote {} lines to {input}", text.lines().count())))
+        }
+    }
+}
+
+fn move_explorer_file(
+    buffers: &mut [Buffer],
+    explorer: Option<&mut Explorer>,
+    kind: PromptKind,
+    input: &str,
+    overwrite: bool,
+    config: &Config,
+    servers: &mut LanguageServers,
+) -> Result<(), String> {
+    let Some(explorer) = explorer else {
+        return Ok(());
+    };
+
+    let (from, to) = if kind == PromptKind::RenameFile {
+        explorer.rename(input, overwrite)?
+    } else {
+        explorer.move_to(input, overwrite)?
+    };
+
+    move_open_buffers(buffers, &from, &to, config, servers)
+}
+
+enum DiffSource {
+    Buffer(usize),
+
+    Saved(PathBuf),
+}
+
+struct DiffSession {
+    view: DiffView,
+
+    sources: [DiffSource; 2],
+}
+
+fn get_diff_text(buffers: &[Buffer], source: &DiffSource) -> Result<String, String> {
+    match source {
+        DiffSource::Buffer(x) => Ok(buffers[*x].editor.to_string()),
+        DiffSource::Saved(path) => std::fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {e}", path.to_string_lossy())),
+    }
+}
+
+fn get_diff_title(buffers: &[Buffer], source: &DiffSource) -> String {
+    match source {
+        DiffSource::Buffer(x) => buffers[*x].get_title(),
+        DiffSource::Saved(path) => format!("{} (saved)", path.to_string_lossy()),
+    }
+}
+
+fn open_diff(buffers: &[Buffer], sources: [DiffSource; 2]) -> Result<DiffSession, String> {
+    let [left, right] = &sources;
+
+    let view = DiffView::new(
+        &get_diff_title(buffers, left),
+        &get_diff_text(buffers, left)?,
+        &get_diff_title(buffers, right),
+        &get_diff_text(buffers, right)?,
     );
+
+    Ok(DiffSession { view, sources })
+}
+
+fn refresh_diff(buffers: &[Buffer], session: &mut DiffSession) -> Result<(), String> {
+    let [left, right] = &session.sources;
+
+    let (left, right) = (get_diff_text(buffers, left)?, get_diff_text(buffers, right)?);
+
+    session.view.set_text(&left, &right);
+
+    Ok(())
+}
+
+fn copy_diff_change(buffers: &mut [Buffer], session: &mut DiffSession, to: Side) -> Result<(), String> {
+    let target = match to {
+        Side::Left => &session.sources[0],
+        Side::Right => &session.sources[1],
+    };
+
+    let DiffSource::Buffer(index) = target else {
+        return Err("the saved file can't be changed here".to_string());
+    };
+
+    let Some((lines, text)) = session.view.get_copy(to) else {
+        return Err("no differences".to_string());
+    };
+
+    let editor = &mut buffers[*index].editor;
+
+    let start = editor.position_to_byte(lines.start, 0);
+    let end = editor.position_to_byte(lines.end, 0);
+
+    editor.replace_range(start, end, &text, false);
+
+    refresh_diff(buffers, session)
+}
+
+fn save_diff(buffers: &mut [Buffer], session: &mut DiffSession) -> Result<(), String> {
+    for source in &session.sources {
+        if let DiffSource::Buffer(x) = source {
+            if buffers[*x].is_modified() {
+                save_buffer(&mut buffers[*x], false)?;
+            }
+        }
+    }
+
+    refresh_diff(buffers, session)
+}
+
+fn check_unsaved(buffers: &[Buffer], paths: &[PathBuf]) -> Result<(), String> {
+    let unsaved = buffers
+        .iter()
+        .filter(|x| x.is_modified())
+        .filter(|x| std::path::absolute(&x.path).is_ok_and(|x| paths.contains(&x)))
+        .map(Buffer::get_name)
+        .collect::<Vec<_>>();
+
+    if unsaved.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("save {} first", unsaved.join(", ")))
+    }
+}
+
+// puts the replaced content into the open buffers of those files, callers check for unsaved changes first
+fn reload_open_buffers(buffers: &mut [Buffer], files: &[(&Path, &str)]) {
+    for buffer in buffers.iter_mut() {
+        let Ok(path) = std::path::absolute(&buffer.path) else {
+            continue;
+        };
+
+        if let Some((_, content)) = files.iter().find(|(x, _)| *x == path) {
+            buffer.editor.replace_text(content);
+            buffer.editor.set_saved();
+            buffer.update_stamp();
+        }
+    }
+}
+
+fn apply_replace(buffers: &mut [Buffer], changes: &ProjectReplace) -> Result<AppliedReplace, String> {
+    check_unsaved(buffers, &changes.get_paths())?;
+
+    let applied = changes.apply()?;
+
+    reload_open_buffers(buffers, &applied.get_files());
+
+    Ok(applied)
+}
+
+fn undo_replace(buffers: &mut [Buffer], applied: &AppliedReplace) -> Result<String, String> {
+    let paths = applied
+        .get_files()
+        .into_iter()
+        .map(|(x, _)| x.to_path_buf())
+        .collect::<Vec<_>>();
+
+    check_unsaved(buffers, &paths)?;
+
+    reload_open_buffers(buffers, &applied.undo()?);
+
+    Ok(format!("restored {}", applied.get_names()))
+}
+
+#[derive(Parser)]
+struct Args {
+    file_paths: Vec<PathBuf>,
+
+    #[arg(long, num_args = 2, value_names = ["LEFT", "RIGHT"], conflicts_with = "file_paths")]
+    diff: Option<Vec<PathBuf>>,
+
+    #[arg(long, short, default_value_t = false)]
+    disable_mouse_interaction: bool,
+
+    #[arg(long, short)]
+    tab_width: Option<usize>,
+
+    #[arg(long, short)]
+    relative_line_numbers: bool,
+}
+
+impl Args {
+    fn config_overrides(&self) -> ConfigFile {
+        ConfigFile {
+            tab_width: self.tab_width,
+            relative_line_numbers: self.relative_line_numbers.then_some(true),
+            disable_mouse_interaction: self.disable_mouse_interaction.then_some(true),
+            ..Default::default()
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let mut files = Vec::new();
+
+    let paths = args.diff.as_ref().unwrap_or(&args.file_paths);
+
+    if paths.is_empty() {
+        files.push((PathBuf::new(), String::new(), true));
+    }
+
+    for path in paths {
+        match read_file(path) {
+            Ok((content, newly_loaded)) => files.push((path.clone(), content, newly_loaded)),
+            Err(e) => {
+                println!("Failed to read file: {:?}", e);
+                return;
+            }
+        }
+    }
+
+    // a scratch buffer picks up the config of the working directory
+    let config_path = paths
+        .first()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(SCRATCH_NAME));
+
+    let config = match Config::load(&config_path, args.config_overrides()) {
+        Ok(x) => x,
+        Err(e) => {
+            println!("Failed to load config: {}", e);
+            return;
+        }
+    };
+
+    let theme = match Theme::load(
+        &config.settings_for(&config_path).theme,
+        ColorSupport::detect(),
+    ) {
+        Ok(x) => x,
+        Err(e) => {
+            println!("Failed to load theme: {}", e);
+            return;
+        }
+    };
+
+    terminal_main(files, config, theme, args.diff.is_some());
 }
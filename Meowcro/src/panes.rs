@@ -0,0 +1,360 @@
+use crate::ui::{Align, DrawResult};
+
+const MIN_RATIO: f32 = 0.1;
+
+const MAX_RATIO: f32 = 0.9;
+
+const RESIZE_STEP: f32 = 0.05;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    SideBySide,
+    Stacked,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Pane {
+    pub id: usize,
+
+    pub buffer: usize,
+
+    pub view: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+
+    pub y: u32,
+
+    pub width: u32,
+
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+enum Node {
+    Pane(Pane),
+
+    Split(Orientation, f32, Box<Node>, Box<Node>),
+}
+
+enum Resize {
+    NotFound,
+
+    Found,
+
+    Done,
+}
+
+pub struct Panes {
+    root: Node,
+
+    focused: usize,
+
+    next_id: usize,
+}
+
+impl Panes {
+    pub fn new(buffer: usize, view: usize) -> Self {
+        Self {
+            root: Node::Pane(Pane {
+                id: 0,
+                buffer,
+                view,
+            }),
+            focused: 0,
+            next_id: 1,
+        }
+    }
+
+    pub fn get_focused(&self) -> Pane {
+        self.panes()
+            .into_iter()
+            .find(|x| x.id == self.focused)
+            .expect("the focused pane exists")
+    }
+
+    pub fn focus(&mut self, id: usize) {
+        if self.panes().iter().any(|x| x.id == id) {
+            self.focused = id;
+        }
+    }
+
+    pub fn panes(&self) -> Vec<Pane> {
+        let mut panes = Vec::new();
+
+        collect_panes(&self.root, &mut panes);
+
+        panes
+    }
+
+    pub fn set_pane(&mut self, pane: Pane) {
+        if let Some(node) = find_node(&mut self.root, pane.id) {
+            *node = Node::Pane(pane);
+        }
+    }
+
+    pub fn split(&mut self, orientation: Orientation, view: usize) -> Pane {
+        let focused = self.get_focused();
+
+        let pane = Pane {
+            id: self.next_id,
+            buffer: focused.buffer,
+            view,
+        };
+
+        self.next_id += 1;
+
+        if let Some(node) = find_node(&mut self.root, focused.id) {
+            *node = Node::Split(
+                orientation,
+                0.5,
+                Box::new(Node::Pane(focused)),
+                Box::new(Node::Pane(pane)),
+            );
+        }
+
+        self.focused = pane.id;
+
+        pane
+    }
+
+    pub fn close(&mut self) -> Option<Pane> {
+        let focused = self.get_focused();
+
+        if !remove_pane(&mut self.root, focused.id) {
+            return None;
+        }
+
+        self.focused = self.panes()[0].id;
+
+        Some(focused)
+    }
+
+    pub fn resize(&mut self, towards: Align) -> bool {
+        let (orientation, delta) = match towards {
+            Align::Left => (Orientation::SideBySide, -RESIZE_STEP),
+            Align::Right => (Orientation::SideBySide, RESIZE_STEP),
+            Align::Top => (Orientation::Stacked, -RESIZE_STEP),
+            Align::Bottom => (Orientation::Stacked, RESIZE_STEP),
+        };
+
+        matches!(
+            resize_split(&mut self.root, self.focused, orientation, delta),
+            Resize::Done
+        )
+    }
+
+    pub fn focus_towards(&mut self, towards: Align, area: Rect) -> bool {
+        let layout = self.layout(area);
+
+        let Some((_, current)) = layout.iter().find(|(x, _)| x.id == self.focused) else {
+            return false;
+        };
+
+        let next = layout
+            .iter()
+            .filter_map(|(pane, rect)| {
+                let (gap, overlaps, offset) = match towards {
+                    Align::Left => (
+                        current.x.checked_sub(rect.x + rect.width)?,
+                        rect.y < current.y + current.height && current.y < rect.y + rect.height,
+                        rect.y.abs_diff(current.y),
+                    ),
+                    Align::Right => (
+                        rect.x.checked_sub(current.x + current.width)?,
+                        rect.y < current.y + current.height && current.y < rect.y + rect.height,
+                        rect.y.abs_diff(current.y),
+                    ),
+                    Align::Top => (
+                        current.y.checked_sub(rect.y + rect.height)?,
+                        rect.x < current.x + current.width && current.x < rect.x + rect.width,
+                        rect.x.abs_diff(current.x),
+                    ),
+                    Align::Bottom => (
+                        rect.y.checked_sub(current.y + current.height)?,
+                        rect.x < current.x + current.width && current.x < rect.x + rect.width,
+                        rect.x.abs_diff(current.x),
+                    ),
+                };
+
+                overlaps.then_some((gap, offset, pane.id))
+            })
+            .min();
+
+        if let Some((_, _, id)) = next {
+            self.focused = id;
+        }
+
+        next.is_some()
+    }
+
+    pub fn layout(&self, area: Rect) -> Vec<(Pane, Rect)> {
+        let mut layout = Vec::new();
+
+        layout_node(&self.root, area, &mut layout);
+
+        layout
+    }
+
+    pub fn draw<R: DrawResult>(
+        &self,
+        area: Rect,
+        draw_pane: &mut impl FnMut(&Pane, Rect) -> R,
+        separator: &impl Fn(u32) -> R,
+    ) -> R {
+        draw_node(&self.root, area, draw_pane, separator)
+    }
+}
+
+fn collect_panes(node: &Node, panes: &mut Vec<Pane>) {
+    match node {
+        Node::Pane(x) => panes.push(*x),
+        Node::Split(_, _, first, second) => {
+            collect_panes(first, panes);
+            collect_panes(second, panes);
+        }
+    }
+}
+
+fn find_node(node: &mut Node, id: usize) -> Option<&mut Node> {
+    match node {
+        Node::Pane(x) if x.id == id => Some(node),
+        Node::Pane(_) => None,
+        Node::Split(_, _, first, second) => find_node(first, id).or_else(|| find_node(second, id)),
+    }
+}
+
+fn remove_pane(node: &mut Node, id: usize) -> bool {
+    let Node::Split(_, _, first, second) = node else {
+        return false;
+    };
+
+    let remaining = match (&**first, &**second) {
+        (Node::Pane(x), _) if x.id == id => second,
+        (_, Node::Pane(x)) if x.id == id => first,
+        _ => return remove_pane(first, id) || remove_pane(second, id),
+    };
+
+    let remaining = std::mem::replace(
+        &mut **remaining,
+        Node::Pane(Pane {
+            id,
+            buffer: 0,
+            view: 0,
+        }),
+    );
+
+    *node = remaining;
+
+    true
+}
+
+fn resize_split(node: &mut Node, id: usize, orientation: Orientation, delta: f32) -> Resize {
+    match node {
+        Node::Pane(x) if x.id == id => Resize::Found,
+        Node::Pane(_) => Resize::NotFound,
+        Node::Split(split_orientation, ratio, first, second) => {
+            let result = match resize_split(first, id, orientation, delta) {
+                Resize::NotFound => resize_split(second, id, orientation, delta),
+                x => x,
+            };
+
+            match result {
+                Resize::Found if *split_orientation == orientation => {
+                    *ratio = (*ratio + delta).clamp(MIN_RATIO, MAX_RATIO);
+
+                    Resize::Done
+                }
+                x => x,
+            }
+        }
+    }
+}
+
+fn split_rect(orientation: Orientation, ratio: f32, rect: Rect) -> (Rect, Rect) {
+    match orientation {
+        Orientation::SideBySide => {
+            let available = rect.width.saturating_sub(1);
+
+            let first = ((available as f32 * ratio).round() as u32).clamp(available.min(1), available);
+
+            (
+                Rect {
+                    width: first,
+                    ..rect
+                },
+                Rect {
+                    x: rect.x + first + 1,
+                    width: available - first,
+                    ..rect
+                },
+            )
+        }
+        Orientation::Stacked => {
+            let first = ((rect.height as f32 * ratio).round() as u32)
+                .clamp(rect.height.min(1), rect.height);
+
+            (
+                Rect {
+                    height: first,
+                    ..rect
+                },
+                Rect {
+                    y: rect.y + first,
+                    height: rect.height - first,
+                    ..rect
+                },
+            )
+        }
+    }
+}
+
+fn layout_node(node: &Node, rect: Rect, layout: &mut Vec<(Pane, Rect)>) {
+    match node {
+        Node::Pane(x) => layout.push((*x, rect)),
+        Node::Split(orientation, ratio, first, second) => {
+            let (first_rect, second_rect) = split_rect(*orientation, *ratio, rect);
+
+            layout_node(first, first_rect, layout);
+            layout_node(second, second_rect, layout);
+        }
+    }
+}
+
+fn draw_node<R: DrawResult>(
+    node: &Node,
+    rect: Rect,
+    draw_pane: &mut impl FnMut(&Pane, Rect) -> R,
+    separator: &impl Fn(u32) -> R,
+) -> R {
+    match node {
+        Node::Pane(x) => draw_pane(x, rect),
+        Node::Split(orientation, ratio, first, second) => {
+            let (first_rect, second_rect) = split_rect(*orientation, *ratio, rect);
+
+            let first = draw_node(first, first_rect, draw_pane, separator);
+            let second = draw_node(second, second_rect, draw_pane, separator);
+
+            match orientation {
+                Orientation::SideBySide => first
+                    .combine_horizontal(
+                        separator(rect.height),
+                        first_rect.width + 1,
+                        first_rect.width,
+                        rect.height,
+                    )
+                    .combine_horizontal(second, rect.width, first_rect.width + 1, rect.height),
+                Orientation::Stacked => {
+                    first.combine_vertical(second, rect.width, first_rect.height, rect.height)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,406 @@
+use crate::gitignore::{is_binary, walk};
+use crate::save::{resolve_symlinks, write_temporary};
+use crate::search::SearchQuery;
+use regex::{NoExpand, Regex};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+    pub line: usize,
+
+    pub old: String,
+
+    pub new: String,
+
+    pub included: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReplace {
+    pub path: PathBuf,
+
+    pub original: String,
+
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileReplace {
+    pub fn get_replaced(&self) -> String {
+        let mut hunks = self.hunks.iter().filter(|x| x.included).peekable();
+
+        let mut result = String::with_capacity(self.original.len());
+
+        for (index, line) in self.original.split_inclusive('\n').enumerate() {
+            match hunks.next_if(|x| x.line == index) {
+                Some(hunk) => {
+                    result.push_str(&hunk.new);
+                    result.push_str(&line[line_content(line).len()..]);
+                }
+                None => result.push_str(line),
+            }
+        }
+
+        result
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RowKind {
+    File,
+
+    Removed,
+
+    Added,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Row {
+    File(usize),
+
+    Removed(usize, usize),
+
+    Added(usize, usize),
+}
+
+pub struct ProjectReplace {
+    root: PathBuf,
+
+    files: Vec<FileReplace>,
+
+    rows: Vec<Row>,
+
+    selected: usize,
+
+    scroll: usize,
+}
+
+impl ProjectReplace {
+    pub fn new(root: PathBuf, query: &SearchQuery, replacement: &str) -> Result<Self, String> {
+        let regex = query.build_regex()?;
+
+        let mut files = Vec::new();
+
+        walk(&root, &mut |path| {
+            let relative = path.strip_prefix(&root).unwrap_or(&path).to_path_buf();
+
+            if !query.includes(&relative.to_string_lossy().replace('\\', "/")) || is_binary(&path) {
+                return;
+            }
+
+            let Ok(original) = std::fs::read_to_string(&path) else {
+                return;
+            };
+
+            let hunks = replace_lines(&regex, query.regex, &original, replacement);
+
+            if !hunks.is_empty() {
+                files.push(FileReplace {
+                    path: relative,
+                    original,
+                    hunks,
+                });
+            }
+        });
+
+        let rows = files
+            .iter()
+            .enumerate()
+            .flat_map(|(file, x)| {
+                std::iter::once(Row::File(file)).chain(
+                    (0..x.hunks.len())
+                        .flat_map(move |hunk| [Row::Removed(file, hunk), Row::Added(file, hunk)]),
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            root,
+            files,
+            rows,
+            selected: 0,
+            scroll: 0,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn get_paths(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|x| x.hunks.iter().any(|x| x.included))
+            .map(|x| self.root.join(&x.path))
+            .collect()
+    }
+
+    pub fn get_title(&self) -> String {
+        format!(
+            "replace {} of {} lines in {} files, space toggles, enter applies, esc cancels",
+            self.count_included(),
+            self.files.iter().map(|x| x.hunks.len()).sum::<usize>(),
+            self.files.len()
+        )
+    }
+
+    pub fn get_rows(&self) -> Vec<(String, RowKind)> {
+        self.rows
+            .iter()
+            .map(|row| match *row {
+                Row::File(file) => {
+                    let file = &self.files[file];
+
+                    let marker = if file.hunks.iter().all(|x| x.included) {
+                        "[x]"
+                    } else if file.hunks.iter().any(|x| x.included) {
+                        "[-]"
+                    } else {
+                        "[ ]"
+                    };
+
+                    (
+                        format!("{marker} {}", file.path.to_string_lossy()),
+                        RowKind::File,
+                    )
+                }
+                Row::Removed(file, hunk) => {
+                    let hunk = &self.files[file].hunks[hunk];
+
+                    let marker = if hunk.included { "[x]" } else { "[ ]" };
+
+                    (
+                        format!("  {marker} {:>5} - {}", hunk.line + 1, hunk.old),
+                        RowKind::Removed,
+                    )
+                }
+                Row::Added(file, hunk) => {
+                    let hunk = &self.files[file].hunks[hunk];
+
+                    (format!("{:>12} + {}", "", hunk.new), RowKind::Added)
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn select(&mut self, row: usize) {
+        match self.rows.get(row) {
+            Some(Row::Added(..)) => self.selected = row - 1,
+            Some(_) => self.selected = row,
+            None => (),
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let mut remaining = delta.unsigned_abs();
+        let mut row = self.selected;
+
+        while remaining > 0 {
+            let next = if delta < 0 {
+                row.checked_sub(1)
+            } else {
+                Some(row + 1).filter(|x| *x < self.rows.len())
+            };
+
+            let Some(next) = next else {
+                break;
+            };
+
+            row = next;
+
+            if !matches!(self.rows[row], Row::Added(..)) {
+                self.selected = row;
+                remaining -= 1;
+            }
+        }
+    }
+
+    pub fn scroll_to_selected(&mut self, height: usize) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if height > 0 && self.selected + 1 >= self.scroll + height {
+            self.scroll = (self.selected + 2).saturating_sub(height);
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        match self.rows.get(self.selected).copied() {
+            Some(Row::File(file)) => {
+                let included = !self.files[file].hunks.iter().all(|x| x.included);
+
+                for hunk in &mut self.files[file].hunks {
+                    hunk.included = included;
+                }
+            }
+            Some(Row::Removed(file, hunk)) | Some(Row::Added(file, hunk)) => {
+                let hunk = &mut self.files[file].hunks[hunk];
+
+                hunk.included = !hunk.included;
+            }
+            None => (),
+        }
+    }
+
+    pub fn apply(&self) -> Result<AppliedReplace, String> {
+        if self.count_included() == 0 {
+            return Err("no changes selected".to_string());
+        }
+
+        let mut files = Vec::new();
+
+        for file in &self.files {
+            if !file.hunks.iter().any(|x| x.included) {
+                continue;
+            }
+
+            let path = self.root.join(&file.path);
+
+            if std::fs::read_to_string(&path).ok().as_ref() != Some(&file.original) {
+                return Err(format!(
+                    "{} changed since the preview",
+                    file.path.to_string_lossy()
+                ));
+            }
+
+            files.push((path, file.original.clone(), file.get_replaced()));
+        }
+
+        let applied = AppliedReplace {
+            root: self.root.clone(),
+            lines: self.count_included(),
+            files,
+        };
+
+        write_files(&applied.get_files())?;
+
+        Ok(applied)
+    }
+
+    fn count_included(&self) -> usize {
+        self.files
+            .iter()
+            .flat_map(|x| &x.hunks)
+            .filter(|x| x.included)
+            .count()
+    }
+}
+
+pub struct AppliedReplace {
+    root: PathBuf,
+
+    lines: usize,
+
+    files: Vec<(PathBuf, String, String)>,
+}
+
+impl AppliedReplace {
+    pub fn get_summary(&self) -> String {
+        format!(
+            "replaced {} lines in {} files: {}",
+            self.lines,
+            self.files.len(),
+            self.get_names()
+        )
+    }
+
+    pub fn get_names(&self) -> String {
+        self.files
+            .iter()
+            .map(|(path, _, _)| path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn get_files(&self) -> Vec<(&Path, &str)> {
+        self.files
+            .iter()
+            .map(|(path, _, replaced)| (path.as_path(), replaced.as_str()))
+            .collect()
+    }
+
+    pub fn undo(&self) -> Result<Vec<(&Path, &str)>, String> {
+        for (path, _, replaced) in &self.files {
+            if std::fs::read_to_string(path).ok().as_ref() != Some(replaced) {
+                return Err(format!(
+                    "{} changed since the replace",
+                    path.to_string_lossy()
+                ));
+            }
+        }
+
+        let originals = self
+            .files
+            .iter()
+            .map(|(path, original, _)| (path.as_path(), original.as_str()))
+            .collect::<Vec<_>>();
+
+        write_files(&originals)?;
+
+        Ok(originals)
+    }
+}
+
+fn line_content(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|x| x.strip_suffix('\r').unwrap_or(x))
+        .unwrap_or(line)
+}
+
+fn replace_lines(regex: &Regex, expand: bool, content: &str, replacement: &str) -> Vec<Hunk> {
+    content
+        .split_inclusive('\n')
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let old = line_content(text);
+
+            let new = if expand {
+                regex.replace_all(old, replacement)
+            } else {
+                regex.replace_all(old, NoExpand(replacement))
+            };
+
+            (new != old).then(|| Hunk {
+                line,
+                old: old.to_string(),
+                new: new.to_string(),
+                included: true,
+            })
+        })
+        .collect()
+}
+
+pub fn write_files(files: &[(&Path, &str)]) -> Result<(), String> {
+    let mut written = Vec::new();
+
+    for (path, content) in files {
+        match write_temporary(&resolve_symlinks(path), content.as_bytes()) {
+            Ok(x) => written.push(x),
+            Err(e) => {
+                for x in &written {
+                    let _ = std::fs::remove_file(x);
+                }
+
+                return Err(format!("{}: {e}", path.to_string_lossy()));
+            }
+        }
+    }
+
+    for (index, (path, _)) in files.iter().enumerate() {
+        if let Err(e) = std::fs::rename(&written[index], resolve_symlinks(path)) {
+            for x in &written[index..] {
+                let _ = std::fs::remove_file(x);
+            }
+
+            return Err(format!("{}: {e}", path.to_string_lossy()));
+        }
+    }
+
+    Ok(())
+}
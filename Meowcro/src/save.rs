@@ -0,0 +1,226 @@
+use serde::Deserialize;
+use std::fs::{File, Metadata, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// the most links followed before giving up, like the kernel does
+const MAX_SYMLINKS: usize = 40;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Backup {
+    #[default]
+    None,
+
+    Tilde,
+
+    Timestamped,
+}
+
+pub fn temporary_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    path.with_file_name(format!(".{name}.meowcro-tmp"))
+}
+
+pub fn backup_path(path: &Path, backup: Backup, now: SystemTime) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+
+    match backup {
+        Backup::None => None,
+        Backup::Tilde => Some(path.with_file_name(format!("{name}~"))),
+        Backup::Timestamped => {
+            let seconds = now.duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or_default();
+
+            Some(path.with_file_name(format!("{name}.{seconds}~")))
+        }
+    }
+}
+
+// follows symlinks to the file they point to, even when that doesn't exist yet
+pub fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+
+    for _ in 0..MAX_SYMLINKS {
+        let Ok(target) = std::fs::read_link(&path) else {
+            break;
+        };
+
+        path = match path.parent() {
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+    }
+
+    path
+}
+
+#[cfg(unix)]
+fn copy_owner(metadata: &Metadata, path: &Path) {
+    use std::os::unix::fs::MetadataExt;
+
+    // only root can give files away, but the group can often still be kept
+    if std::os::unix::fs::chown(path, Some(metadata.uid()), Some(metadata.gid())).is_err() {
+        std::os::unix::fs::chown(path, None, Some(metadata.gid())).ok();
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_owner(_: &Metadata, _: &Path) {}
+
+#[cfg(target_os = "linux")]
+fn copy_xattrs(from: &Path, to: &Path) {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let (Ok(from), Ok(to)) = (
+        CString::new(from.as_os_str().as_bytes()),
+        CString::new(to.as_os_str().as_bytes()),
+    ) else {
+        return;
+    };
+
+    let length = unsafe { libc::listxattr(from.as_ptr(), std::ptr::null_mut(), 0) };
+
+    if length <= 0 {
+        return;
+    }
+
+    let mut names = vec![0_u8; length as usize];
+
+    let length = unsafe { libc::listxattr(from.as_ptr(), names.as_mut_ptr().cast(), names.len()) };
+
+    if length <= 0 {
+        return;
+    }
+
+    names.truncate(length as usize);
+
+    for name in names.split(|x| *x == 0).filter(|x| !x.is_empty()) {
+        let Ok(name) = CString::new(name) else {
+            continue;
+        };
+
+        let size = unsafe { libc::getxattr(from.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) };
+
+        if size < 0 {
+            continue;
+        }
+
+        let mut value = vec![0_u8; size as usize];
+
+        let size = unsafe {
+            libc::getxattr(from.as_ptr(), name.as_ptr(), value.as_mut_ptr().cast(), value.len())
+        };
+
+        if size < 0 {
+            continue;
+        }
+
+        // attributes the filesystem or security module doesn't let us set are left out
+        unsafe {
+            libc::setxattr(to.as_ptr(), name.as_ptr(), value.as_ptr().cast(), size as usize, 0);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_xattrs(_: &Path, _: &Path) {}
+
+#[cfg(unix)]
+fn has_other_links(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn has_other_links(_: &Metadata) -> bool {
+    false
+}
+
+fn sync_directory(path: &Path) {
+    if let Some(parent) = path.parent().filter(|x| !x.as_os_str().is_empty()) {
+        File::open(parent).and_then(|x| x.sync_all()).ok();
+    }
+}
+
+// writes the content next to the file, with the permissions, owner and extended attributes of the file it replaces
+pub fn write_temporary(path: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    let temporary = temporary_path(path);
+
+    let _ = std::fs::remove_file(&temporary);
+
+    let result = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temporary)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()?;
+
+            if let Ok(metadata) = std::fs::metadata(path) {
+                copy_owner(&metadata, &temporary);
+                copy_xattrs(path, &temporary);
+
+                std::fs::set_permissions(&temporary, metadata.permissions())?;
+            }
+
+            Ok(())
+        });
+
+    match result {
+        Ok(()) => Ok(temporary),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temporary);
+
+            Err(e)
+        }
+    }
+}
+
+pub fn save_file(path: &Path, content: &[u8], backup: Backup) -> std::io::Result<()> {
+    let path = resolve_symlinks(path);
+
+    if let Some(parent) = path.parent().filter(|x| !x.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let metadata = std::fs::metadata(&path).ok();
+
+    // replacing a file with other hard links would split it from them, so it's overwritten in place instead
+    let in_place = metadata.as_ref().is_some_and(has_other_links);
+
+    if let Some(backup) = metadata.as_ref().and(backup_path(&path, backup, SystemTime::now())) {
+        let _ = std::fs::remove_file(&backup);
+
+        // the old file is only kept as it is when it's replaced, otherwise the backup needs its own copy
+        if in_place || std::fs::hard_link(&path, &backup).is_err() {
+            std::fs::copy(&path, &backup)?;
+        }
+    }
+
+    if in_place {
+        let mut file = OpenOptions::new().write(true).truncate(true).open(&path)?;
+
+        file.write_all(content)?;
+
+        return file.sync_all();
+    }
+
+    let temporary = write_temporary(&path, content)?;
+
+    if let Err(e) = std::fs::rename(&temporary, &path) {
+        let _ = std::fs::remove_file(&temporary);
+
+        return Err(e);
+    }
+
+    sync_directory(&path);
+
+    Ok(())
+}
@@ -0,0 +1,359 @@
+use crate::gitignore::{is_binary, walk, Glob};
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+
+const MAX_MATCHES: usize = 10000;
+
+#[derive(Clone)]
+pub struct SearchQuery {
+    pub pattern: String,
+
+    pub regex: bool,
+
+    pub case_sensitive: bool,
+
+    pub include: Vec<Glob>,
+
+    pub exclude: Vec<Glob>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut query = Self {
+            pattern: String::new(),
+            regex: false,
+            case_sensitive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        };
+
+        let mut rest = input.trim_start();
+
+        loop {
+            let (flag, after) = rest.split_once(' ').unwrap_or((rest, ""));
+
+            match flag {
+                "-r" => query.regex = true,
+                "-c" => query.case_sensitive = true,
+                "-g" => {
+                    let after = after.trim_start();
+
+                    let (glob, after) = after.split_once(' ').unwrap_or((after, ""));
+
+                    match glob.strip_prefix('!') {
+                        Some(x) => query.exclude.push(Glob::new(x)?),
+                        None => query.include.push(Glob::new(glob)?),
+                    }
+
+                    rest = after.trim_start();
+                    continue;
+                }
+                "--" => {
+                    rest = after;
+                    break;
+                }
+                _ => break,
+            }
+
+            rest = after.trim_start();
+        }
+
+        if rest.is_empty() {
+            return Err("nothing to search for".to_string());
+        }
+
+        query.pattern = rest.to_string();
+
+        Ok(query)
+    }
+
+    pub fn build_regex(&self) -> Result<Regex, String> {
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .map_err(|e| e.to_string().lines().last().unwrap_or_default().to_string())
+    }
+
+    pub fn includes(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|x| x.is_match(path)))
+            && !self.exclude.iter().any(|x| x.is_match(path))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub line: usize,
+
+    pub range: Range<usize>,
+
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileMatches {
+    pub path: PathBuf,
+
+    pub matches: Vec<SearchMatch>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Row {
+    File(usize),
+
+    Match(usize, usize),
+}
+
+pub struct ProjectSearch {
+    root: PathBuf,
+
+    files: Vec<FileMatches>,
+
+    rows: Vec<Row>,
+
+    receiver: Option<Receiver<FileMatches>>,
+
+    cancelled: Arc<AtomicBool>,
+
+    selected: usize,
+
+    scroll: usize,
+
+    truncated: bool,
+}
+
+impl ProjectSearch {
+    pub fn new(root: PathBuf, query: SearchQuery) -> Result<Self, String> {
+        let regex = query.build_regex()?;
+
+        let (sender, receiver) = channel();
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let walk_root = root.clone();
+        let walk_cancelled = cancelled.clone();
+
+        std::thread::spawn(move || {
+            let mut total = 0;
+
+            walk(&walk_root, &mut |path| {
+                if total >= MAX_MATCHES || walk_cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let relative = path.strip_prefix(&walk_root).unwrap_or(&path);
+
+                if !query.includes(&relative.to_string_lossy().replace('\\', "/")) || is_binary(&path) {
+                    return;
+                }
+
+                let Ok(content) = std::fs::read_to_string(&path) else {
+                    return;
+                };
+
+                let matches = search_text(&regex, &content, MAX_MATCHES - total);
+
+                if matches.is_empty() {
+                    return;
+                }
+
+                total += matches.len();
+
+                let _ = sender.send(FileMatches {
+                    path: relative.to_path_buf(),
+                    matches,
+                });
+            });
+        });
+
+        Ok(Self {
+            root,
+            files: Vec::new(),
+            rows: Vec::new(),
+            receiver: Some(receiver),
+            cancelled,
+            selected: 0,
+            scroll: 0,
+            truncated: false,
+        })
+    }
+
+    pub fn poll(&mut self) -> bool {
+        let Some(receiver) = self.receiver.as_ref() else {
+            return false;
+        };
+
+        let mut changed = false;
+
+        loop {
+            match receiver.try_recv() {
+                Ok(x) => {
+                    let file = self.files.len();
+
+                    self.rows.push(Row::File(file));
+                    self.rows
+                        .extend((0..x.matches.len()).map(|index| Row::Match(file, index)));
+
+                    self.files.push(x);
+
+                    if self.files.len() == 1 {
+                        self.selected = 1;
+                    }
+
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.receiver = None;
+                    self.truncated = self.count_matches() >= MAX_MATCHES;
+                    changed = true;
+                    break;
+                }
+            }
+        }
+
+        changed
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    pub fn get_title(&self) -> String {
+        let matches = self.count_matches();
+
+        let title = format!(
+            "{matches} {} in {} {}",
+            if matches == 1 { "match" } else { "matches" },
+            self.files.len(),
+            if self.files.len() == 1 { "file" } else { "files" },
+        );
+
+        if self.is_searching() {
+            format!("{title}, searching...")
+        } else if self.truncated {
+            format!("{title}, stopped at {MAX_MATCHES} matches")
+        } else {
+            title
+        }
+    }
+
+    pub fn get_rows(&self) -> Vec<(String, bool)> {
+        self.rows
+            .iter()
+            .map(|row| match *row {
+                Row::File(file) => {
+                    let file = &self.files[file];
+
+                    (
+                        format!("{} ({})", file.path.to_string_lossy(), file.matches.len()),
+                        true,
+                    )
+                }
+                Row::Match(file, index) => {
+                    let found = &self.files[file].matches[index];
+
+                    (format!("{:>6}: {}", found.line + 1, found.text.trim()), false)
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn get_selected_match(&self) -> Option<(PathBuf, &SearchMatch)> {
+        match self.rows.get(self.selected) {
+            Some(Row::Match(file, index)) => {
+                let file = &self.files[*file];
+
+                Some((self.root.join(&file.path), &file.matches[*index]))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn select(&mut self, row: usize) {
+        match self.rows.get(row) {
+            Some(Row::Match(..)) => self.selected = row,
+            Some(Row::File(_)) if row + 1 < self.rows.len() => self.selected = row + 1,
+            _ => (),
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let mut remaining = delta.unsigned_abs();
+        let mut row = self.selected;
+
+        while remaining > 0 {
+            let next = if delta < 0 {
+                row.checked_sub(1)
+            } else {
+                Some(row + 1).filter(|x| *x < self.rows.len())
+            };
+
+            let Some(next) = next else {
+                break;
+            };
+
+            row = next;
+
+            if matches!(self.rows[row], Row::Match(..)) {
+                self.selected = row;
+                remaining -= 1;
+            }
+        }
+    }
+
+    pub fn scroll_to_selected(&mut self, height: usize) {
+        let top = self.selected.saturating_sub(1);
+
+        if top < self.scroll {
+            self.scroll = top;
+        } else if height > 0 && self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+    }
+
+    fn count_matches(&self) -> usize {
+        self.files.iter().map(|x| x.matches.len()).sum()
+    }
+}
+
+impl Drop for ProjectSearch {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+pub fn search_text(regex: &Regex, content: &str, limit: usize) -> Vec<SearchMatch> {
+    content
+        .lines()
+        .enumerate()
+        .flat_map(|(line, text)| {
+            regex
+                .find_iter(text)
+                .filter(|x| !x.is_empty())
+                .map(move |x| SearchMatch {
+                    line,
+                    range: x.range(),
+                    text: text.to_string(),
+                })
+        })
+        .take(limit)
+        .collect()
+}
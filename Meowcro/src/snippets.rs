@@ -0,0 +1,519 @@
+use crate::editor::{LineLayout, TextChange, TextEditor};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BUILTIN_SNIPPETS: &[(&str, &str)] = &[
+    ("rust", include_str!("../snippets/rust.toml")),
+    ("meowscript", include_str!("../snippets/meowscript.toml")),
+];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SnippetDef {
+    description: Option<String>,
+
+    body: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Variable {
+    FileName,
+    FilePath,
+    Directory,
+    Line,
+    Date,
+    Time,
+    Year,
+    Month,
+    Day,
+}
+
+impl Variable {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "FILENAME" => Self::FileName,
+            "FILEPATH" => Self::FilePath,
+            "DIRECTORY" => Self::Directory,
+            "LINE" => Self::Line,
+            "DATE" => Self::Date,
+            "TIME" => Self::Time,
+            "YEAR" => Self::Year,
+            "MONTH" => Self::Month,
+            "DAY" => Self::Day,
+            _ => return None,
+        })
+    }
+
+    fn resolve(self, context: &SnippetContext) -> String {
+        let (year, month, day, hour, minute) = utc_now(context.now);
+
+        match self {
+            Self::FileName => context
+                .path
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            Self::FilePath => context.path.to_string_lossy().to_string(),
+            Self::Directory => context
+                .path
+                .parent()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            Self::Line => (context.line + 1).to_string(),
+            Self::Date => format!("{year:04}-{month:02}-{day:02}"),
+            Self::Time => format!("{hour:02}:{minute:02}"),
+            Self::Year => format!("{year:04}"),
+            Self::Month => format!("{month:02}"),
+            Self::Day => format!("{day:02}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Variable(Variable),
+    Stop(usize, Vec<Part>),
+}
+
+pub struct Snippet {
+    pub trigger: String,
+
+    pub description: Option<String>,
+
+    parts: Vec<Part>,
+}
+
+pub struct SnippetContext<'a> {
+    pub path: &'a Path,
+
+    pub line: usize,
+
+    pub indent: &'a str,
+
+    pub now: SystemTime,
+}
+
+pub struct Expansion {
+    pub text: String,
+
+    pub stops: Vec<(usize, Range<usize>)>,
+}
+
+impl Snippet {
+    pub fn parse(trigger: &str, description: Option<String>, body: &str) -> Result<Self, String> {
+        let mut chars = body.chars().peekable();
+
+        let parts =
+            parse_parts(&mut chars, false).map_err(|e| format!("snippet `{trigger}`: {e}"))?;
+
+        Ok(Self {
+            trigger: trigger.to_string(),
+            description,
+            parts,
+        })
+    }
+
+    pub fn expand(&self, context: &SnippetContext) -> Expansion {
+        let mut defaults: HashMap<usize, &[Part]> = HashMap::new();
+
+        for part in &self.parts {
+            if let Part::Stop(number, default) = part {
+                let entry = defaults.entry(*number).or_insert(default);
+
+                if entry.is_empty() {
+                    *entry = default;
+                }
+            }
+        }
+
+        let mut expansion = Expansion {
+            text: String::new(),
+            stops: Vec::new(),
+        };
+
+        for part in &self.parts {
+            match part {
+                Part::Stop(number, _) => {
+                    let start = expansion.text.len();
+
+                    for part in defaults[number] {
+                        push_part(&mut expansion.text, part, context);
+                    }
+
+                    expansion.stops.push((*number, start..expansion.text.len()));
+                }
+                part => push_part(&mut expansion.text, part, context),
+            }
+        }
+
+        expansion
+    }
+}
+
+fn push_part(text: &mut String, part: &Part, context: &SnippetContext) {
+    let content = match part {
+        Part::Text(x) => x.clone(),
+        Part::Variable(x) => x.resolve(context),
+        Part::Stop(..) => return,
+    };
+
+    text.push_str(&content.replace('\n', &format!("\n{}", context.indent)));
+}
+
+fn parse_parts(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    in_placeholder: bool,
+) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek().is_some_and(|x| matches!(x, '$' | '}' | '\\')) => {
+                text.push(chars.next().unwrap());
+            }
+            '}' if in_placeholder => {
+                if !text.is_empty() {
+                    parts.push(Part::Text(text));
+                }
+
+                return Ok(parts);
+            }
+            '$' => {
+                let braced = chars.next_if_eq(&'{').is_some();
+
+                let mut name = String::new();
+
+                while let Some(x) = chars.next_if(|x| x.is_ascii_alphanumeric() || *x == '_') {
+                    name.push(x);
+                }
+
+                if name.is_empty() {
+                    if braced {
+                        return Err("expected a number or a name after `${`".to_string());
+                    }
+
+                    text.push('$');
+                    continue;
+                }
+
+                let part = match name.parse::<usize>() {
+                    Ok(number) => {
+                        let default = if braced && chars.next_if_eq(&':').is_some() {
+                            let default = parse_parts(chars, true)?;
+
+                            if default.iter().any(|x| matches!(x, Part::Stop(..))) {
+                                return Err("placeholders can't be nested".to_string());
+                            }
+
+                            default
+                        } else {
+                            if braced && chars.next() != Some('}') {
+                                return Err(format!("expected `}}` after `${{{name}`"));
+                            }
+
+                            Vec::new()
+                        };
+
+                        Part::Stop(number, default)
+                    }
+                    Err(_) => {
+                        let variable = Variable::from_name(&name)
+                            .ok_or_else(|| format!("unknown variable `${name}`"))?;
+
+                        if braced && chars.next() != Some('}') {
+                            return Err(format!("expected `}}` after `${{{name}`"));
+                        }
+
+                        Part::Variable(variable)
+                    }
+                };
+
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+
+                parts.push(part);
+            }
+            c => text.push(c),
+        }
+    }
+
+    if in_placeholder {
+        return Err("unterminated placeholder, expected `}`".to_string());
+    }
+
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+
+    Ok(parts)
+}
+
+fn utc_now(now: SystemTime) -> (i64, u32, u32, u32, u32) {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs() as i64)
+        .unwrap_or(0);
+
+    let days = seconds.div_euclid(86400);
+    let time = seconds.rem_euclid(86400);
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (
+        year,
+        month,
+        day,
+        (time / 3600) as u32,
+        (time % 3600 / 60) as u32,
+    )
+}
+
+pub struct SnippetSet {
+    snippets: HashMap<String, Vec<Snippet>>,
+}
+
+impl SnippetSet {
+    pub fn parse_file(source: &str, content: &str) -> Result<Vec<Snippet>, String> {
+        let definitions: HashMap<String, SnippetDef> =
+            toml::from_str(content).map_err(|e| format!("{source}: {}", e.message().trim()))?;
+
+        let mut snippets = definitions
+            .into_iter()
+            .map(|(trigger, x)| {
+                Snippet::parse(&trigger, x.description, &x.body)
+                    .map_err(|e| format!("{source}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        snippets.sort_by(|x, y| x.trigger.cmp(&y.trigger));
+
+        Ok(snippets)
+    }
+
+    pub fn load() -> (Self, Vec<String>) {
+        let mut snippets: HashMap<String, Vec<Snippet>> = HashMap::new();
+        let mut errors = Vec::new();
+
+        for (filetype, content) in BUILTIN_SNIPPETS {
+            match Self::parse_file(&format!("built-in snippets `{filetype}`"), content) {
+                Ok(x) => {
+                    snippets.insert(filetype.to_string(), x);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let user_dir = crate::config::config_dir().map(|x| x.join("snippets"));
+
+        let mut user_files = user_dir
+            .and_then(|x| std::fs::read_dir(x).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|x| x.ok())
+            .map(|x| x.path())
+            .filter(|x| x.extension().is_some_and(|x| x == "toml"))
+            .collect::<Vec<_>>();
+
+        user_files.sort();
+
+        for path in user_files {
+            let Some(filetype) = path.file_stem().map(|x| x.to_string_lossy().to_string()) else {
+                continue;
+            };
+
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| format!("{}: {e}", path.to_string_lossy()))
+                .and_then(|x| Self::parse_file(&path.to_string_lossy(), &x));
+
+            match result {
+                Ok(x) => {
+                    let existing = snippets.entry(filetype).or_default();
+
+                    existing.retain(|y| !x.iter().any(|z| z.trigger == y.trigger));
+                    existing.extend(x);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (Self { snippets }, errors)
+    }
+
+    pub fn find(&self, path: &Path, trigger: &str) -> Option<&Snippet> {
+        let filetype = crate::filetype::detect(path)?;
+
+        self.snippets
+            .get(filetype)?
+            .iter()
+            .find(|x| x.trigger == trigger)
+    }
+}
+
+pub struct SnippetSession {
+    stops: Vec<Vec<Range<usize>>>,
+
+    active: usize,
+
+    extent: Range<usize>,
+
+    edited: Option<usize>,
+}
+
+impl SnippetSession {
+    pub fn new(start: usize, expansion: &Expansion) -> Self {
+        let mut numbers = expansion.stops.iter().map(|(x, _)| *x).collect::<Vec<_>>();
+
+        numbers.sort_by_key(|x| (*x == 0, *x));
+        numbers.dedup();
+
+        let mut stops = numbers
+            .iter()
+            .map(|number| {
+                expansion
+                    .stops
+                    .iter()
+                    .filter(|(x, _)| x == number)
+                    .map(|(_, x)| start + x.start..start + x.end)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let end = start + expansion.text.len();
+
+        if numbers.last() != Some(&0) {
+            stops.push(std::iter::once(end..end).collect());
+        }
+
+        Self {
+            stops,
+            active: 0,
+            extent: start..end,
+            edited: None,
+        }
+    }
+
+    pub fn get_active(&self) -> Range<usize> {
+        self.stops[self.active][0].clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.active + 1 == self.stops.len()
+    }
+
+    pub fn move_active(&mut self, forward: bool) -> Range<usize> {
+        self.active = if forward {
+            (self.active + 1).min(self.stops.len() - 1)
+        } else {
+            self.active.saturating_sub(1)
+        };
+
+        self.get_active()
+    }
+
+    pub fn contains(&self, byte: usize) -> bool {
+        self.extent.start <= byte && byte <= self.extent.end
+    }
+
+    pub fn apply_changes(&mut self, changes: &[TextChange]) -> bool {
+        for change in changes {
+            if !adjust_range(&mut self.extent, change, true) {
+                return false;
+            }
+
+            for (index, stop) in self.stops.iter_mut().enumerate() {
+                let active = index == self.active;
+
+                for (range_index, range) in stop.iter_mut().enumerate() {
+                    let touched = change.start >= range.start && change.old_end <= range.end;
+
+                    if !adjust_range(range, change, active) {
+                        return false;
+                    }
+
+                    if active && touched {
+                        self.edited = Some(range_index);
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn apply_mirror_edits<L: LineLayout>(&mut self, editor: &mut TextEditor<L>) -> bool {
+        let Some(source) = self.edited.take() else {
+            return false;
+        };
+
+        if editor.can_redo() {
+            return false;
+        }
+
+        let text = editor.get_text();
+        let ranges = &self.stops[self.active];
+        let content = text.byte_slice(ranges[source].clone()).to_string();
+
+        let mut edits = ranges
+            .iter()
+            .enumerate()
+            .filter(|(x, range)| {
+                *x != source && text.byte_slice((*range).clone()) != content.as_str()
+            })
+            .map(|(_, range)| range.clone())
+            .collect::<Vec<_>>();
+
+        if edits.is_empty() {
+            return false;
+        }
+
+        edits.sort_by_key(|x| std::cmp::Reverse(x.start));
+
+        let history = editor.get_history_position().saturating_sub(1);
+
+        for range in edits {
+            editor.replace_range(range.start, range.end, &content, false);
+        }
+
+        editor.group_changes_since(history);
+
+        true
+    }
+}
+
+fn adjust_range(range: &mut Range<usize>, change: &TextChange, grow: bool) -> bool {
+    let delta = change.new_end as isize - change.old_end as isize;
+
+    let before = change.old_end < range.start
+        || (change.old_end == range.start && !(grow && change.start == range.start));
+
+    if before {
+        range.start = range.start.saturating_add_signed(delta);
+        range.end = range.end.saturating_add_signed(delta);
+    } else if change.start > range.end || (change.start == range.end && !grow) {
+    } else if change.start >= range.start && change.old_end <= range.end {
+        range.end = range.end.saturating_add_signed(delta);
+    } else {
+        return false;
+    }
+
+    true
+}
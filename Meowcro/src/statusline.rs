@@ -0,0 +1,136 @@
+use ropey::RopeSlice;
+use serde::Deserialize;
+
+const INDENT_SCAN_LINES: usize = 1000;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    Mode,
+
+    File,
+
+    Message,
+
+    Filetype,
+
+    Encoding,
+
+    LineEnding,
+
+    Indent,
+
+    Selection,
+
+    Position,
+
+    Lines,
+
+    Percent,
+}
+
+impl Segment {
+    pub fn is_accent(self) -> bool {
+        matches!(self, Self::Mode | Self::Position)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Indent {
+    Tabs,
+
+    Spaces(usize),
+}
+
+pub struct StatusInfo<'a> {
+    pub mode: &'a str,
+
+    pub title: &'a str,
+
+    pub modified: bool,
+
+    pub message: Option<&'a str>,
+
+    pub filetype: Option<&'a str>,
+
+    pub crlf: bool,
+
+    pub indent: Indent,
+
+    pub selection: Option<(usize, usize)>,
+
+    pub position: (usize, usize),
+
+    pub lines: usize,
+}
+
+impl<'a> StatusInfo<'a> {
+    pub fn get_text(&self, segment: Segment) -> Option<String> {
+        match segment {
+            Segment::Mode => Some(self.mode.to_string()),
+            Segment::File => Some(format!("{}{}", self.title, if self.modified { "*" } else { "" })),
+            Segment::Message => self.message.map(str::to_string),
+            Segment::Filetype => Some(self.filetype.unwrap_or("plain").to_string()),
+            Segment::Encoding => Some("utf-8".to_string()),
+            Segment::LineEnding => Some(if self.crlf { "CRLF" } else { "LF" }.to_string()),
+            Segment::Indent => Some(match self.indent {
+                Indent::Tabs => "tabs".to_string(),
+                Indent::Spaces(x) => format!("spaces: {x}"),
+            }),
+            Segment::Selection => self.selection.map(|(chars, lines)| {
+                if lines > 1 {
+                    format!("{chars} chars, {lines} lines selected")
+                } else {
+                    format!("{chars} chars selected")
+                }
+            }),
+            Segment::Position => Some(format!("{}:{}", self.position.0, self.position.1)),
+            Segment::Lines => Some(format!("{} lines", self.lines)),
+            Segment::Percent => Some(format!(
+                "{}%",
+                (self.position.0 * 100).checked_div(self.lines).unwrap_or(0)
+            )),
+        }
+    }
+
+    pub fn get_segments(&self, segments: &[Segment]) -> Vec<(Segment, String)> {
+        segments
+            .iter()
+            .filter_map(|x| Some((*x, self.get_text(*x)?)))
+            .filter(|(_, x)| !x.is_empty())
+            .collect()
+    }
+}
+
+pub fn uses_crlf(text: RopeSlice) -> bool {
+    text.lines()
+        .next()
+        .is_some_and(|x| x.len_chars() > 1 && x.char(x.len_chars() - 2) == '\r')
+}
+
+pub fn detect_indent(text: RopeSlice) -> Indent {
+    let mut tabs = 0;
+    let mut spaces = 0;
+    let mut smallest = usize::MAX;
+
+    for line in text.lines().take(INDENT_SCAN_LINES) {
+        match line.chars().next() {
+            Some('\t') => tabs += 1,
+            Some(' ') => {
+                let width = line.chars().take_while(|x| *x == ' ').count();
+
+                if line.chars().nth(width).is_some_and(|x| !x.is_whitespace()) {
+                    spaces += 1;
+                    smallest = smallest.min(width);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if spaces > tabs {
+        Indent::Spaces(smallest)
+    } else {
+        Indent::Tabs
+    }
+}
@@ -0,0 +1,466 @@
+use crate::editor::TextChange;
+use crate::terminal::Highlight;
+use regex::Regex;
+use ropey::RopeSlice;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+const BUILTIN_GRAMMARS: &[(&str, &str)] = &[
+    ("rust", include_str!("../grammars/rust.toml")),
+    ("python", include_str!("../grammars/python.toml")),
+    ("shell", include_str!("../grammars/shell.toml")),
+    ("markdown", include_str!("../grammars/markdown.toml")),
+    ("json", include_str!("../grammars/json.toml")),
+    ("toml", include_str!("../grammars/toml.toml")),
+    ("meowscript", include_str!("../grammars/meowscript.toml")),
+];
+
+const MAIN_CONTEXT: &str = "main";
+
+const MAX_CONTEXT_DEPTH: usize = 32;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDef {
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+
+    words: Option<Vec<String>>,
+
+    token: Option<String>,
+
+    group: Option<usize>,
+
+    push: Option<String>,
+
+    #[serde(default)]
+    pop: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContextDef {
+    token: Option<String>,
+
+    #[serde(default)]
+    rules: Vec<RuleDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GrammarDef {
+    name: String,
+
+    #[serde(default)]
+    filetypes: Vec<String>,
+
+    #[serde(default)]
+    extensions: Vec<String>,
+
+    contexts: HashMap<String, ContextDef>,
+}
+
+#[derive(Copy, Clone)]
+enum Action {
+    None,
+    Push(usize),
+    Pop,
+}
+
+struct Rule {
+    highlight: Highlight,
+
+    wrapper: usize,
+
+    group: usize,
+
+    action: Action,
+}
+
+struct Context {
+    highlight: Highlight,
+
+    regex: Option<Regex>,
+
+    rules: Vec<Rule>,
+}
+
+pub struct Grammar {
+    name: String,
+
+    filetypes: Vec<String>,
+
+    extensions: Vec<String>,
+
+    contexts: Vec<Context>,
+}
+
+impl Grammar {
+    pub fn parse(source: &str, content: &str) -> Result<Self, String> {
+        let definition: GrammarDef =
+            toml::from_str(content).map_err(|e| format!("{source}: {}", e.message().trim()))?;
+
+        let mut names = definition.contexts.keys().cloned().collect::<Vec<String>>();
+        names.sort_by_key(|x| (x != MAIN_CONTEXT, x.clone()));
+
+        if names.first().map(String::as_str) != Some(MAIN_CONTEXT) {
+            return Err(format!("{source}: missing context `{MAIN_CONTEXT}`"));
+        }
+
+        let token = |name: &Option<String>, default: Highlight| match name {
+            None => Ok(default),
+            Some(x) if x == "text" => Ok(Highlight::Text),
+            Some(x) => Highlight::from_name(x)
+                .filter(|x| x.is_syntax())
+                .ok_or_else(|| format!("{source}: unknown token `{x}`")),
+        };
+
+        let mut contexts = Vec::with_capacity(names.len());
+
+        for name in names.iter() {
+            let context = &definition.contexts[name];
+
+            let highlight = token(&context.token, Highlight::Text)?;
+
+            let mut patterns = Vec::new();
+            let mut rules = Vec::new();
+            let mut group = 1;
+
+            for (index, rule) in context.rules.iter().enumerate() {
+                let location = format!("{source}: context `{name}`, rule {}", index + 1);
+
+                let pattern = match (&rule.pattern, &rule.words) {
+                    (Some(x), None) => x.clone(),
+                    (None, Some(x)) => format!(
+                        r"\b(?:{})\b",
+                        x.iter()
+                            .map(|x| regex::escape(x))
+                            .collect::<Vec<_>>()
+                            .join("|")
+                    ),
+                    _ => {
+                        return Err(format!(
+                            "{location}: expected exactly one of `match` or `words`"
+                        ))
+                    }
+                };
+
+                let captures = Regex::new(&pattern)
+                    .map_err(|e| format!("{location}: {e}"))?
+                    .captures_len();
+
+                let inner_group = rule.group.unwrap_or(0);
+
+                if inner_group >= captures {
+                    return Err(format!("{location}: no capture group {inner_group}"));
+                }
+
+                let action = match (&rule.push, rule.pop) {
+                    (Some(_), true) => {
+                        return Err(format!("{location}: a rule can't both push and pop"))
+                    }
+                    (Some(x), false) => Action::Push(
+                        names
+                            .iter()
+                            .position(|y| y == x)
+                            .ok_or_else(|| format!("{location}: unknown context `{x}`"))?,
+                    ),
+                    (None, true) => Action::Pop,
+                    (None, false) => Action::None,
+                };
+
+                rules.push(Rule {
+                    highlight: token(&rule.token, highlight)?,
+                    wrapper: group,
+                    group: group + inner_group,
+                    action,
+                });
+
+                patterns.push(format!("({pattern})"));
+                group += captures;
+            }
+
+            let regex = if patterns.is_empty() {
+                None
+            } else {
+                Some(Regex::new(&patterns.join("|")).map_err(|e| format!("{source}: {e}"))?)
+            };
+
+            contexts.push(Context {
+                highlight,
+                regex,
+                rules,
+            });
+        }
+
+        Ok(Self {
+            name: definition.name,
+            filetypes: definition.filetypes,
+            extensions: definition.extensions,
+            contexts,
+        })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let filetype = crate::filetype::detect(path);
+        let extension = path.extension().and_then(|x| x.to_str());
+
+        filetype.is_some_and(|x| self.filetypes.iter().any(|y| x == y))
+            || extension.is_some_and(|x| self.extensions.iter().any(|y| x == y))
+    }
+
+    fn highlight_line(&self, line: &str, stack: &mut Vec<usize>, spans: &mut Vec<Span>) {
+        let mut position = 0;
+        let mut empty_matches = 0;
+
+        while position < line.len() {
+            let context = &self.contexts[*stack.last().unwrap_or(&0)];
+
+            let found = context.regex.as_ref().and_then(|regex| {
+                let captures = regex.captures_at(line, position)?;
+
+                let rule = context
+                    .rules
+                    .iter()
+                    .find(|rule| captures.get(rule.wrapper).is_some())?;
+
+                let whole = captures.get(rule.wrapper)?;
+
+                Some((rule, captures.get(rule.group).unwrap_or(whole), whole))
+            });
+
+            let Some((rule, token, whole)) = found else {
+                push_span(spans, position..line.len(), context.highlight);
+                break;
+            };
+
+            push_span(spans, position..token.start(), context.highlight);
+            push_span(spans, token.range(), rule.highlight);
+
+            let next = if rule.group == rule.wrapper {
+                whole.end()
+            } else {
+                token.end()
+            };
+
+            let changed_context = match rule.action {
+                Action::Push(x) if stack.len() < MAX_CONTEXT_DEPTH => {
+                    stack.push(x);
+                    true
+                }
+                Action::Pop if stack.len() > 1 => {
+                    stack.pop();
+                    true
+                }
+                _ => false,
+            };
+
+            if next > position {
+                position = next;
+                empty_matches = 0;
+            } else if changed_context && empty_matches < MAX_CONTEXT_DEPTH {
+                empty_matches += 1;
+            } else {
+                let skipped = line.ceil_char_boundary(position + 1);
+                push_span(spans, position..skipped, context.highlight);
+                position = skipped;
+                empty_matches = 0;
+            }
+        }
+    }
+}
+
+fn push_span(spans: &mut Vec<Span>, range: std::ops::Range<usize>, highlight: Highlight) {
+    if range.is_empty() || highlight == Highlight::Text {
+        return;
+    }
+
+    match spans.last_mut() {
+        Some(last) if last.end == range.start && last.highlight == highlight => {
+            last.end = range.end;
+        }
+        _ => spans.push(Span {
+            start: range.start,
+            end: range.end,
+            highlight,
+        }),
+    }
+}
+
+pub struct GrammarSet {
+    grammars: Vec<Rc<Grammar>>,
+}
+
+impl GrammarSet {
+    pub fn load() -> (Self, Vec<String>) {
+        let mut grammars = Vec::new();
+        let mut errors = Vec::new();
+
+        let user_dir = crate::config::config_dir().map(|x| x.join("grammars"));
+
+        let mut user_files = user_dir
+            .and_then(|x| std::fs::read_dir(x).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|x| x.ok())
+            .map(|x| x.path())
+            .filter(|x| x.extension().is_some_and(|x| x == "toml"))
+            .collect::<Vec<_>>();
+
+        user_files.sort();
+
+        for path in user_files {
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| format!("{}: {e}", path.to_string_lossy()))
+                .and_then(|x| Grammar::parse(&path.to_string_lossy(), &x));
+
+            match result {
+                Ok(x) => grammars.push(Rc::new(x)),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        for (name, content) in BUILTIN_GRAMMARS {
+            if grammars.iter().any(|x| x.name == *name) {
+                continue;
+            }
+
+            match Grammar::parse(&format!("built-in grammar `{name}`"), content) {
+                Ok(x) => grammars.push(Rc::new(x)),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (Self { grammars }, errors)
+    }
+
+    pub fn for_path(&self, path: &Path) -> Option<Rc<Grammar>> {
+        self.grammars.iter().find(|x| x.matches(path)).cloned()
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Span {
+    pub start: usize,
+
+    pub end: usize,
+
+    pub highlight: Highlight,
+}
+
+#[derive(Clone, Default)]
+struct LineCache {
+    start: Vec<usize>,
+
+    end: Vec<usize>,
+
+    spans: Vec<Span>,
+
+    dirty: bool,
+}
+
+pub struct Highlighter {
+    grammar: Rc<Grammar>,
+
+    lines: Vec<LineCache>,
+
+    first_dirty: usize,
+}
+
+impl Highlighter {
+    pub fn new(grammar: Rc<Grammar>, text: RopeSlice) -> Self {
+        let lines = vec![
+            LineCache {
+                dirty: true,
+                ..Default::default()
+            };
+            text.len_lines()
+        ];
+
+        Self {
+            grammar,
+            lines,
+            first_dirty: 0,
+        }
+    }
+
+    pub fn apply_changes(&mut self, changes: &[TextChange]) {
+        for change in changes {
+            let start = change.start_line.min(self.lines.len());
+            let old_end = (change.old_end_line + 1).min(self.lines.len()).max(start);
+            let inserted = change.new_end_line + 1 - change.start_line;
+
+            self.lines.splice(
+                start..old_end,
+                std::iter::repeat_n(
+                    LineCache {
+                        dirty: true,
+                        ..Default::default()
+                    },
+                    inserted,
+                ),
+            );
+
+            self.first_dirty = self.first_dirty.min(start);
+        }
+    }
+
+    pub fn update(&mut self, text: RopeSlice, until_line: usize) {
+        self.lines.resize(
+            text.len_lines(),
+            LineCache {
+                dirty: true,
+                ..Default::default()
+            },
+        );
+
+        let mut index = self.first_dirty;
+        let mut buffer = String::new();
+
+        while index < self.lines.len().min(until_line) {
+            let start = if index == 0 {
+                vec![0]
+            } else {
+                self.lines[index - 1].end.clone()
+            };
+
+            if !self.lines[index].dirty && self.lines[index].start == start {
+                match self.lines[index..].iter().position(|x| x.dirty) {
+                    Some(x) => index += x,
+                    None => index = self.lines.len(),
+                }
+
+                continue;
+            }
+
+            buffer.clear();
+            buffer.extend(text.line(index).chunks());
+
+            let mut stack = start.clone();
+            let mut spans = Vec::new();
+
+            self.grammar.highlight_line(&buffer, &mut stack, &mut spans);
+
+            self.lines[index] = LineCache {
+                start,
+                end: stack,
+                spans,
+                dirty: false,
+            };
+
+            index += 1;
+        }
+
+        self.first_dirty = index;
+    }
+
+    pub fn get_line_spans(&self, line: usize) -> &[Span] {
+        self.lines
+            .get(line)
+            .filter(|x| !x.dirty)
+            .map(|x| &x.spans[..])
+            .unwrap_or(&[])
+    }
+}
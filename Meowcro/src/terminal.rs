@@ -1,5 +1,6 @@
 use crate::unicode::{TERM_TAB_WIDTH, move_grapheme, string_width};
 use crate::editor::{LineLayout, GraphemePosition};
+use crate::theme::{CellStyle, Theme};
 use ropey::RopeSlice;
 use crossterm::{
     cursor::{self, SetCursorStyle},
@@ -7,7 +8,7 @@ use crossterm::{
         DisableMouseCapture, EnableMouseCapture, 
     },
     execute, queue, style,
-    style::Color,
+    style::Attribute,
     terminal::{enable_raw_mode, disable_raw_mode, LeaveAlternateScreen, EnterAlternateScreen},
 };
 use std::io::{stdout, Write};
@@ -102,26 +103,212 @@ pub enum Highlight {
     Selection,
     Gutter,
     Status,
+    StatusAccent,
+    Popup,
+    PopupSelection,
+    Tab,
+    TabSelected,
+    Error,
+    Warning,
+    Info,
+    ErrorSign,
+    WarningSign,
+    InfoSign,
+    Added,
+    Removed,
+    Modified,
+    DiffText,
+    ConflictMarker,
+    ConflictOurs,
+    ConflictBase,
+    ConflictTheirs,
+    Keyword,
+    Type,
+    Function,
+    String,
+    Escape,
+    Number,
+    Constant,
+    Comment,
+    Operator,
+    Attribute,
+    Heading,
+    Emphasis,
+    Link,
 }
 
 impl Highlight {
-    pub fn get_color_foreground_crossterm(self) -> Color {
+    pub const ALL: [Highlight; 36] = [
+        Self::Text,
+        Self::Selection,
+        Self::Gutter,
+        Self::Status,
+        Self::StatusAccent,
+        Self::Popup,
+        Self::PopupSelection,
+        Self::Tab,
+        Self::TabSelected,
+        Self::Error,
+        Self::Warning,
+        Self::Info,
+        Self::ErrorSign,
+        Self::WarningSign,
+        Self::InfoSign,
+        Self::Added,
+        Self::Removed,
+        Self::Modified,
+        Self::DiffText,
+        Self::ConflictMarker,
+        Self::ConflictOurs,
+        Self::ConflictBase,
+        Self::ConflictTheirs,
+        Self::Keyword,
+        Self::Type,
+        Self::Function,
+        Self::String,
+        Self::Escape,
+        Self::Number,
+        Self::Constant,
+        Self::Comment,
+        Self::Operator,
+        Self::Attribute,
+        Self::Heading,
+        Self::Emphasis,
+        Self::Link,
+    ];
+
+    pub fn name(self) -> &'static str {
         match self {
-            Self::Text => Color::Reset,
-            Self::Selection => Color::Black,
-            Self::Gutter => Color::Yellow,
-            Self::Status => Color::Black,
+            Self::Text => "text",
+            Self::Selection => "selection",
+            Self::Gutter => "gutter",
+            Self::Status => "status",
+            Self::StatusAccent => "status_accent",
+            Self::Popup => "popup",
+            Self::PopupSelection => "popup_selection",
+            Self::Tab => "tab",
+            Self::TabSelected => "tab_selected",
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+            Self::ErrorSign => "error_sign",
+            Self::WarningSign => "warning_sign",
+            Self::InfoSign => "info_sign",
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "modified",
+            Self::DiffText => "diff_text",
+            Self::ConflictMarker => "conflict_marker",
+            Self::ConflictOurs => "conflict_ours",
+            Self::ConflictBase => "conflict_base",
+            Self::ConflictTheirs => "conflict_theirs",
+            Self::Keyword => "keyword",
+            Self::Type => "type",
+            Self::Function => "function",
+            Self::String => "string",
+            Self::Escape => "escape",
+            Self::Number => "number",
+            Self::Constant => "constant",
+            Self::Comment => "comment",
+            Self::Operator => "operator",
+            Self::Attribute => "attribute",
+            Self::Heading => "heading",
+            Self::Emphasis => "emphasis",
+            Self::Link => "link",
         }
     }
 
-    pub fn get_color_background_crossterm(self) -> Color {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+
+    pub fn is_syntax(self) -> bool {
+        self >= Self::Keyword
+    }
+
+    pub fn fallbacks(self) -> &'static [Highlight] {
         match self {
-            Self::Text => Color::Reset,
-            Self::Selection => Color::Blue,
-            Self::Gutter => Color::Reset,
-            Self::Status => Color::Grey,
+            Self::Escape => &[Self::Escape, Self::String],
+            Self::Constant => &[Self::Constant, Self::Number],
+            Self::Attribute => &[Self::Attribute, Self::Keyword],
+            Self::Heading => &[Self::Heading, Self::Keyword],
+            Self::Emphasis => &[Self::Emphasis, Self::Keyword],
+            Self::Link => &[Self::Link, Self::String],
+            Self::StatusAccent => &[Self::StatusAccent, Self::TabSelected, Self::Selection],
+            Self::Popup => &[Self::Popup, Self::Status],
+            Self::PopupSelection => &[Self::PopupSelection, Self::Selection],
+            Self::Tab => &[Self::Tab, Self::Status],
+            Self::TabSelected => &[Self::TabSelected, Self::Selection],
+            Self::ErrorSign => &[Self::ErrorSign, Self::Gutter],
+            Self::WarningSign => &[Self::WarningSign, Self::Gutter],
+            Self::InfoSign => &[Self::InfoSign, Self::Gutter],
+            Self::Added => &[Self::Added, Self::String],
+            Self::Removed => &[Self::Removed, Self::Text],
+            Self::Modified => &[Self::Modified, Self::WarningSign, Self::Gutter],
+            Self::DiffText => &[Self::DiffText, Self::Selection],
+            Self::ConflictMarker => &[Self::ConflictMarker, Self::Heading, Self::Keyword],
+            Self::ConflictOurs => &[Self::ConflictOurs, Self::Added, Self::String],
+            Self::ConflictBase => &[Self::ConflictBase, Self::Comment],
+            Self::ConflictTheirs => &[Self::ConflictTheirs, Self::Function],
+            Self::Text => &[Self::Text],
+            Self::Selection => &[Self::Selection],
+            Self::Gutter => &[Self::Gutter],
+            Self::Status => &[Self::Status],
+            Self::Error => &[Self::Error],
+            Self::Warning => &[Self::Warning],
+            Self::Info => &[Self::Info],
+            Self::Keyword => &[Self::Keyword],
+            Self::Type => &[Self::Type],
+            Self::Function => &[Self::Function],
+            Self::String => &[Self::String],
+            Self::Number => &[Self::Number],
+            Self::Comment => &[Self::Comment],
+            Self::Operator => &[Self::Operator],
         }
     }
+
+    pub fn is_inverted_without_color(self) -> bool {
+        matches!(
+            self,
+            Self::Selection | Self::Status | Self::StatusAccent | Self::Popup | Self::Tab
+        )
+    }
+
+    pub fn is_underlined_without_color(self) -> bool {
+        matches!(self, Self::Error | Self::Warning | Self::Info)
+    }
+}
+
+fn queue_style(style: CellStyle, previous: Option<CellStyle>) {
+    let attributes_changed = previous
+        .map(|x| {
+            (x.bold, x.italic, x.underline, x.reverse)
+                != (style.bold, style.italic, style.underline, style.reverse)
+        })
+        .unwrap_or(true);
+
+    if attributes_changed {
+        queue!(stdout(), style::SetAttribute(Attribute::Reset)).unwrap();
+
+        for (enabled, attribute) in [
+            (style.bold, Attribute::Bold),
+            (style.italic, Attribute::Italic),
+            (style.underline, Attribute::Underlined),
+            (style.reverse, Attribute::Reverse),
+        ] {
+            if enabled {
+                queue!(stdout(), style::SetAttribute(attribute)).unwrap();
+            }
+        }
+    }
+
+    if attributes_changed || previous.map(|x| x.foreground) != Some(style.foreground) {
+        queue!(stdout(), style::SetForegroundColor(style.foreground)).unwrap();
+    }
+
+    if attributes_changed || previous.map(|x| x.background) != Some(style.background) {
+        queue!(stdout(), style::SetBackgroundColor(style.background)).unwrap();
+    }
 }
 
 pub fn render(
@@ -129,6 +316,7 @@ pub fn render(
     cursor_position: Option<(usize, usize)>,
     buffer: &[Char],
     previous_buffer: &[Char],
+    theme: &Theme,
 ) {
     let mut x = 0;
     let mut y = 0;
@@ -136,15 +324,7 @@ pub fn render(
     let mut prev_y = 0;
     let mut prev_chars = previous_buffer.iter().peekable();
     let mut force_move = true;
-    let mut prev_fg = Color::Reset;
-    let mut prev_bg = Color::Reset;
-
-    queue!(
-        stdout(),
-        style::SetForegroundColor(Color::Reset),
-        style::SetBackgroundColor(Color::Reset)
-    )
-    .unwrap();
+    let mut prev_style = None;
 
     for c in buffer.iter() {
         if x != prev_x || y != prev_y || Some(&c) != prev_chars.peek() {
@@ -152,17 +332,11 @@ pub fn render(
                 queue!(stdout(), cursor::MoveTo(x as u16, y as u16)).unwrap();
             }
 
-            let fg = c.color.get_color_foreground_crossterm();
-            let bg = c.color.get_color_background_crossterm();
-
-            if fg != prev_fg {
-                queue!(stdout(), style::SetForegroundColor(fg)).unwrap();
-                prev_fg = fg;
-            }
+            let cell_style = theme.style(c.color);
 
-            if bg != prev_bg {
-                queue!(stdout(), style::SetBackgroundColor(bg)).unwrap();
-                prev_bg = bg;
+            if prev_style != Some(cell_style) {
+                queue_style(cell_style, prev_style);
+                prev_style = Some(cell_style);
             }
 
             queue!(stdout(), style::Print(c.c)).unwrap();
@@ -195,6 +369,8 @@ pub fn render(
         }
     }
 
+    queue!(stdout(), style::SetAttribute(Attribute::Reset), style::ResetColor).unwrap();
+
     if let Some((x, y)) = cursor_position {
         queue!(stdout(), cursor::Show, cursor::MoveTo(x as u16, y as u16),).unwrap();
     } else {
@@ -232,6 +408,14 @@ pub fn setup_terminal(disable_mouse_interaction: bool) {
     }
 }
 
+pub fn set_mouse_capture(enabled: bool) {
+    if enabled {
+        execute!(stdout(), EnableMouseCapture).unwrap();
+    } else {
+        execute!(stdout(), DisableMouseCapture).unwrap();
+    }
+}
+
 pub fn cleanup_terminal(message: &str) {
     execute!(
         stdout(),
@@ -0,0 +1,378 @@
+use crate::terminal::Highlight;
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const BUILTIN_THEMES: &[(&str, &str)] = &[
+    ("default", include_str!("../themes/default.toml")),
+    ("dark", include_str!("../themes/dark.toml")),
+    ("light", include_str!("../themes/light.toml")),
+    ("meownix", include_str!("../themes/meownix.toml")),
+];
+
+const MAX_INHERIT_DEPTH: usize = 8;
+
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (128, 0, 0)),
+    (Color::DarkGreen, (0, 128, 0)),
+    (Color::DarkYellow, (128, 128, 0)),
+    (Color::DarkBlue, (0, 0, 128)),
+    (Color::DarkMagenta, (128, 0, 128)),
+    (Color::DarkCyan, (0, 128, 128)),
+    (Color::Grey, (192, 192, 192)),
+    (Color::DarkGrey, (128, 128, 128)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (0, 0, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    None,
+}
+
+impl ColorSupport {
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default().to_lowercase();
+
+        if !var("NO_COLOR").is_empty() {
+            return Self::None;
+        }
+
+        let colorterm = var("COLORTERM");
+        let term = var("TERM");
+
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else if term == "dumb" {
+            Self::None
+        } else {
+            Self::Ansi16
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct CellStyle {
+    pub foreground: Color,
+
+    pub background: Color,
+
+    pub bold: bool,
+
+    pub italic: bool,
+
+    pub underline: bool,
+
+    pub reverse: bool,
+}
+
+impl Default for CellStyle {
+    fn default() -> Self {
+        Self {
+            foreground: Color::Reset,
+            background: Color::Reset,
+            bold: false,
+            italic: false,
+            underline: false,
+            reverse: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Index(u8),
+    Name(String),
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(try_from = "RawColor")]
+enum ColorDef {
+    Reset,
+    Named(Color),
+    Rgb(u8, u8, u8),
+    Index(u8),
+}
+
+impl TryFrom<RawColor> for ColorDef {
+    type Error = String;
+
+    fn try_from(raw: RawColor) -> Result<Self, String> {
+        match raw {
+            RawColor::Index(x) => Ok(Self::Index(x)),
+            RawColor::Name(x) => parse_color(&x).ok_or_else(|| {
+                format!(
+                    "invalid color `{x}`, expected `#rrggbb`, a color name or a 256-color index"
+                )
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+struct StyleDef {
+    fg: Option<ColorDef>,
+
+    bg: Option<ColorDef>,
+
+    bold: Option<bool>,
+
+    italic: Option<bool>,
+
+    underline: Option<bool>,
+
+    reverse: Option<bool>,
+}
+
+impl StyleDef {
+    fn merge(&self, base: &StyleDef) -> StyleDef {
+        StyleDef {
+            fg: self.fg.or(base.fg),
+            bg: self.bg.or(base.bg),
+            bold: self.bold.or(base.bold),
+            italic: self.italic.or(base.italic),
+            underline: self.underline.or(base.underline),
+            reverse: self.reverse.or(base.reverse),
+        }
+    }
+
+    fn resolve(&self, support: ColorSupport) -> CellStyle {
+        CellStyle {
+            foreground: self
+                .fg
+                .map(|x| convert_color(x, support))
+                .unwrap_or(Color::Reset),
+            background: self
+                .bg
+                .map(|x| convert_color(x, support))
+                .unwrap_or(Color::Reset),
+            bold: self.bold.unwrap_or(false),
+            italic: self.italic.unwrap_or(false),
+            underline: self.underline.unwrap_or(false),
+            reverse: self.reverse.unwrap_or(false),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    inherits: Option<String>,
+
+    #[serde(default)]
+    styles: HashMap<String, StyleDef>,
+}
+
+pub struct Theme {
+    styles: Vec<CellStyle>,
+}
+
+impl Theme {
+    pub fn load(name: &str, support: ColorSupport) -> Result<Self, String> {
+        let definitions = load_definitions(name, 0)?;
+
+        let styles = Highlight::ALL
+            .iter()
+            .map(|highlight| {
+                if support == ColorSupport::None {
+                    return CellStyle {
+                        reverse: highlight.is_inverted_without_color(),
+                        underline: highlight.is_underlined_without_color(),
+                        ..Default::default()
+                    };
+                }
+
+                highlight
+                    .fallbacks()
+                    .iter()
+                    .find_map(|x| definitions.get(x.name()))
+                    .cloned()
+                    .unwrap_or_default()
+                    .merge(
+                        definitions
+                            .get(Highlight::Text.name())
+                            .unwrap_or(&StyleDef::default()),
+                    )
+                    .resolve(support)
+            })
+            .collect();
+
+        Ok(Self { styles })
+    }
+
+    pub fn style(&self, highlight: Highlight) -> CellStyle {
+        self.styles[highlight as usize]
+    }
+}
+
+fn load_definitions(name: &str, depth: usize) -> Result<HashMap<String, StyleDef>, String> {
+    if depth > MAX_INHERIT_DEPTH {
+        return Err(format!(
+            "theme `{name}` inherits too deeply, is there a cycle?"
+        ));
+    }
+
+    let user_path =
+        crate::config::config_dir().map(|x| x.join("themes").join(format!("{name}.toml")));
+
+    let (source, content) = match user_path
+        .as_ref()
+        .and_then(|x| std::fs::read_to_string(x).ok())
+    {
+        Some(x) => (user_path.unwrap().to_string_lossy().to_string(), x),
+        None => match BUILTIN_THEMES.iter().find(|(x, _)| *x == name) {
+            Some((_, x)) => (format!("built-in theme `{name}`"), x.to_string()),
+            None => return Err(format!("unknown theme `{name}`")),
+        },
+    };
+
+    let file: ThemeFile =
+        toml::from_str(&content).map_err(|e| format!("{source}: {}", e.message().trim()))?;
+
+    if let Some(x) = file
+        .styles
+        .keys()
+        .find(|x| Highlight::from_name(x).is_none())
+    {
+        return Err(format!("{source}: unknown highlight `{x}`"));
+    }
+
+    let mut definitions = match file.inherits {
+        Some(parent) => load_definitions(&parent, depth + 1)?,
+        None => HashMap::new(),
+    };
+
+    for (key, style) in file.styles {
+        let merged = match definitions.get(&key) {
+            Some(base) => style.merge(base),
+            None => style,
+        };
+
+        definitions.insert(key, merged);
+    }
+
+    Ok(definitions)
+}
+
+fn parse_color(string: &str) -> Option<ColorDef> {
+    let string = string.trim().to_lowercase();
+
+    if let Some(hex) = string.strip_prefix('#') {
+        let digits = hex
+            .chars()
+            .map(|x| x.to_digit(16).map(|x| x as u8))
+            .collect::<Option<Vec<u8>>>()?;
+
+        return match digits[..] {
+            [r, g, b] => Some(ColorDef::Rgb(r * 17, g * 17, b * 17)),
+            [r1, r2, g1, g2, b1, b2] => {
+                Some(ColorDef::Rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2))
+            }
+            _ => None,
+        };
+    }
+
+    if string == "reset" || string == "default" {
+        return Some(ColorDef::Reset);
+    }
+
+    Color::try_from(string.as_str()).ok().map(ColorDef::Named)
+}
+
+fn convert_color(color: ColorDef, support: ColorSupport) -> Color {
+    match (color, support) {
+        (_, ColorSupport::None) => Color::Reset,
+        (ColorDef::Reset, _) => Color::Reset,
+        (ColorDef::Named(x), _) => x,
+        (ColorDef::Rgb(r, g, b), ColorSupport::TrueColor) => Color::Rgb { r, g, b },
+        (ColorDef::Rgb(r, g, b), ColorSupport::Ansi256) => {
+            Color::AnsiValue(rgb_to_ansi256(r, g, b))
+        }
+        (ColorDef::Rgb(r, g, b), ColorSupport::Ansi16) => rgb_to_ansi16(r, g, b),
+        (ColorDef::Index(x), ColorSupport::Ansi16) => {
+            let (r, g, b) = ansi256_to_rgb(x);
+            rgb_to_ansi16(r, g, b)
+        }
+        (ColorDef::Index(x), _) => Color::AnsiValue(x),
+    }
+}
+
+fn color_distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let dr = r1 as i32 - r2 as i32;
+    let dg = g1 as i32 - g2 as i32;
+    let db = b1 as i32 - b2 as i32;
+
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+fn cube_level(value: u8) -> u8 {
+    match value {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (value - 35) / 40,
+    }
+}
+
+fn cube_value(level: u8) -> u8 {
+    if level == 0 {
+        0
+    } else {
+        55 + level * 40
+    }
+}
+
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let (cr, cg, cb) = (cube_level(r), cube_level(g), cube_level(b));
+    let cube = (cube_value(cr), cube_value(cg), cube_value(cb));
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_level = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_value = 8 + gray_level * 10;
+
+    if color_distance((r, g, b), (gray_value, gray_value, gray_value))
+        < color_distance((r, g, b), cube)
+    {
+        232 + gray_level
+    } else {
+        16 + 36 * cr + 6 * cg + cb
+    }
+}
+
+pub fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_COLORS[index as usize].1,
+        16..=231 => {
+            let index = index - 16;
+            (
+                cube_value(index / 36),
+                cube_value((index / 6) % 6),
+                cube_value(index % 6),
+            )
+        }
+        _ => {
+            let value = 8 + (index - 232) * 10;
+            (value, value, value)
+        }
+    }
+}
+
+pub fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, rgb)| color_distance(*rgb, (r, g, b)))
+        .map(|(x, _)| *x)
+        .unwrap()
+}
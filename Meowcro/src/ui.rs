@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 #[allow(dead_code)]
 pub enum Align {
     Bottom,
@@ -23,17 +23,27 @@ pub struct LayoutItem<'a, R: DrawResult, I, O: OutputResult> {
 pub struct Layout<'a, R: DrawResult, I, O: OutputResult> {
     items: Vec<LayoutItem<'a, R, I, O>>,
     space: (u32, u32, u32, u32),
+    popup: Option<&'a dyn Widget<R, I, O>>,
+    modal: Option<&'a dyn Widget<R, I, O>>,
 }
 
 impl<'a, R: DrawResult, I, O: OutputResult> Layout<'a, R, I, O> {
-    pub fn new(width: u32, height: u32) -> Self {
+    pub fn new_at(x: u32, y: u32, width: u32, height: u32) -> Self {
         Self {
-            space: (0, 0, width, height),
+            space: (x, y, x + width, y + height),
             items: Vec::new(),
+            popup: None,
+            modal: None,
         }
     }
 
     pub fn interact(self, interactions: &I) -> O {
+        if let Some(modal) = self.modal {
+            let (x, y, width, height) = modal_area(modal, self.space);
+
+            return modal.interact(interactions, x, y, width, height);
+        }
+
         self.items
             .iter()
             .map(|x| x.widget.interact(interactions, x.x, x.y, x.width, x.height))
@@ -98,7 +108,46 @@ impl<'a, R: DrawResult, I, O: OutputResult> Layout<'a, R, I, O> {
             height = new_height;
         }
 
-        result
+        if let (Some(popup), Some((cursor_x, cursor_y))) = (self.popup, result.cursor()) {
+            let (popup_width, popup_height) = popup.minimum_size(width, height);
+
+            let popup_width = popup_width.min(width);
+
+            let (popup_y, popup_height) = if cursor_y + 1 + popup_height <= height {
+                (cursor_y + 1, popup_height)
+            } else if popup_height <= cursor_y {
+                (cursor_y - popup_height, popup_height)
+            } else {
+                (cursor_y + 1, height.saturating_sub(cursor_y + 1))
+            };
+
+            let popup_x = cursor_x.min(width - popup_width);
+
+            if popup_width > 0 && popup_height > 0 {
+                result = result.overlay(
+                    popup.draw(popup_width, popup_height),
+                    width,
+                    popup_x,
+                    popup_y,
+                    popup_width,
+                );
+            }
+        }
+
+        match self.modal {
+            Some(modal) => overlay_modal(result, modal, width, height),
+            None => result,
+        }
+    }
+
+    pub fn add_popup(mut self, item: &'a dyn Widget<R, I, O>) -> Self {
+        self.popup = Some(item);
+        self
+    }
+
+    pub fn add_modal(mut self, item: &'a dyn Widget<R, I, O>) -> Self {
+        self.modal = Some(item);
+        self
     }
 
     pub fn add_item(
@@ -170,6 +219,41 @@ impl<'a, R: DrawResult, I, O: OutputResult> Layout<'a, R, I, O> {
     }
 }
 
+fn modal_area<R: DrawResult, I, O: OutputResult>(
+    modal: &dyn Widget<R, I, O>,
+    space: (u32, u32, u32, u32),
+) -> (u32, u32, u32, u32) {
+    let (width, height) = (space.2 - space.0, space.3 - space.1);
+
+    let (modal_width, modal_height) = modal.minimum_size(width, height);
+
+    let (modal_width, modal_height) = (modal_width.min(width), modal_height.min(height));
+
+    (
+        space.0 + (width - modal_width) / 2,
+        space.1 + 2.min(height - modal_height),
+        modal_width,
+        modal_height,
+    )
+}
+
+pub fn overlay_modal<R: DrawResult, I, O: OutputResult>(
+    result: R,
+    modal: &dyn Widget<R, I, O>,
+    width: u32,
+    height: u32,
+) -> R {
+    let (x, y, modal_width, modal_height) = modal_area(modal, (0, 0, width, height));
+
+    let modal = modal.draw(modal_width, modal_height);
+
+    let cursor = modal.cursor().map(|(cursor_x, cursor_y)| (cursor_x + x, cursor_y + y));
+
+    result
+        .overlay(modal, width, x, y, modal_width)
+        .with_cursor(cursor)
+}
+
 pub trait Widget<R: DrawResult, I, O: OutputResult>: Drawable<R> + Interactive<I, O> {
     fn minimum_size(&self, width: u32, height: u32) -> (u32, u32);
 
@@ -188,6 +272,9 @@ pub trait DrawResult {
     fn empty(width: u32, height: u32) -> Self;
     fn combine_horizontal(self, other: Self, width: u32, split: u32, height: u32) -> Self;
     fn combine_vertical(self, other: Self, width: u32, split: u32, height: u32) -> Self;
+    fn overlay(self, other: Self, width: u32, x: u32, y: u32, other_width: u32) -> Self;
+    fn cursor(&self) -> Option<(u32, u32)>;
+    fn with_cursor(self, cursor: Option<(u32, u32)>) -> Self;
 }
 
 pub trait OutputResult {
@@ -69,4 +69,28 @@ pub fn is_newline(c: char) -> bool {
         '\n', '\r', '\u{000B}', '\u{000C}', '\u{0085}', '\u{2028}', '\u{2029}',
     ]
     .contains(&c)
-}
\ No newline at end of file
+}
+
+pub fn is_word_character(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+pub fn word_start(text: RopeSlice, byte: usize) -> usize {
+    let mut index = text.byte_to_char(byte);
+
+    while index > 0 && is_word_character(text.char(index - 1)) {
+        index -= 1;
+    }
+
+    text.char_to_byte(index)
+}
+
+pub fn word_end(text: RopeSlice, byte: usize) -> usize {
+    let mut index = text.byte_to_char(byte);
+
+    while index < text.len_chars() && is_word_character(text.char(index)) {
+        index += 1;
+    }
+
+    text.char_to_byte(index)
+}
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+// files in folders inotify can't watch are checked this often instead
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FileStamp {
+    modified: Option<SystemTime>,
+
+    len: u64,
+}
+
+impl FileStamp {
+    pub fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+struct WatchedDirectory {
+    path: PathBuf,
+
+    // `None` when inotify couldn't watch it, its files are polled instead
+    #[cfg(target_os = "linux")]
+    watch: Option<inotify::WatchDescriptor>,
+}
+
+impl WatchedDirectory {
+    fn is_watched(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.watch.is_some();
+
+        #[cfg(not(target_os = "linux"))]
+        return false;
+    }
+}
+
+pub struct FileWatcher {
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+
+    directories: Vec<WatchedDirectory>,
+
+    polled: Vec<(PathBuf, Option<FileStamp>)>,
+
+    last_poll: Instant,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            inotify: inotify::Inotify::init().ok(),
+            directories: Vec::new(),
+            polled: Vec::new(),
+            last_poll: Instant::now(),
+        }
+    }
+}
+
+impl FileWatcher {
+    // watches exactly these files, dropping the folders of files that aren't open anymore. Files
+    // are watched through their folder, so replacing them (like formatters and git do) is noticed too
+    pub fn watch(&mut self, paths: &[&Path]) {
+        let files = paths
+            .iter()
+            .filter_map(|x| std::path::absolute(x).ok())
+            .filter(|x| x.parent().is_some())
+            .collect::<Vec<_>>();
+
+        let in_use = |directory: &Path| files.iter().any(|x| x.parent() == Some(directory));
+
+        #[cfg(target_os = "linux")]
+        for directory in self.directories.iter_mut().filter(|x| !in_use(&x.path)) {
+            if let (Some(x), Some(watch)) = (self.inotify.as_mut(), directory.watch.take()) {
+                x.watches().remove(watch).ok();
+            }
+        }
+
+        self.directories.retain(|x| in_use(&x.path));
+
+        for file in &files {
+            let directory = file.parent().unwrap();
+
+            if self.directories.iter().any(|x| x.path == directory) {
+                continue;
+            }
+
+            self.directories.push(WatchedDirectory {
+                path: directory.to_path_buf(),
+                #[cfg(target_os = "linux")]
+                watch: self.inotify.as_mut().and_then(|x| {
+                    use inotify::WatchMask;
+
+                    let mask = WatchMask::CLOSE_WRITE
+                        | WatchMask::CREATE
+                        | WatchMask::DELETE
+                        | WatchMask::MOVED_FROM
+                        | WatchMask::MOVED_TO;
+
+                    x.watches().add(directory, mask).ok()
+                }),
+            });
+        }
+
+        let unwatched = |file: &Path| {
+            self.directories
+                .iter()
+                .any(|x| Some(x.path.as_path()) == file.parent() && !x.is_watched())
+        };
+
+        self.polled.retain(|(x, _)| files.contains(x) && unwatched(x));
+
+        for file in files {
+            if unwatched(&file) && !self.polled.iter().any(|(x, _)| *x == file) {
+                let stamp = FileStamp::read(&file);
+                self.polled.push((file, stamp));
+            }
+        }
+    }
+
+    // whether any watched file might have changed since the last poll
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+
+        #[cfg(target_os = "linux")]
+        if let Some(x) = self.inotify.as_mut() {
+            let mut buffer = [0; 4096];
+
+            // dropping a folder's watch reports `IGNORED`, that's not a change
+            while let Ok(mut events) = x.read_events(&mut buffer) {
+                changed |= events.any(|x| !x.mask.contains(inotify::EventMask::IGNORED));
+            }
+        }
+
+        if self.polled.is_empty() || self.last_poll.elapsed() < POLL_INTERVAL {
+            return changed;
+        }
+
+        self.last_poll = Instant::now();
+
+        for (path, stamp) in self.polled.iter_mut() {
+            let next = FileStamp::read(path);
+
+            changed |= next != *stamp;
+            *stamp = next;
+        }
+
+        changed
+    }
+}
@@ -1,5 +1,15 @@
+use crate::diffview::DiffView;
+use crate::editor::TextEditor;
+use crate::explorer::Entry;
+use crate::git::ChangeKind;
+use crate::lsp::Severity;
+use crate::syntax::Highlighter;
+use crate::terminal::{Highlight, TermLineLayoutSettings};
+use crate::unicode::{string_width, TERM_TAB_WIDTH};
+use std::ops::Range;
+
 #[derive(Copy, Clone)]
-pub struct LineNumbers {
+pub struct LineNumbers<'a> {
     pub start: usize,
 
     pub total: usize,
@@ -7,15 +17,24 @@ pub struct LineNumbers {
     pub current: usize,
 
     pub relative: bool,
+
+    pub signs: &'a [(usize, Severity)],
 }
 
-impl LineNumbers {
-    pub fn new(start: usize, total: usize, current: usize, relative: bool) -> Self {
+impl<'a> LineNumbers<'a> {
+    pub fn new(
+        start: usize,
+        total: usize,
+        current: usize,
+        relative: bool,
+        signs: &'a [(usize, Severity)],
+    ) -> Self {
         Self {
             start,
             total,
             current,
             relative,
+            signs,
         }
     }
 
@@ -44,4 +63,257 @@ impl<'a> TextLine<'a> {
     pub fn new(string: &'a str) -> Self {
         Self { string }
     }
-}
\ No newline at end of file
+}
+
+pub struct Prompt<'a> {
+    pub label: &'a str,
+
+    pub text: &'a str,
+
+    pub cursor: usize,
+
+    pub feedback: Option<(&'a str, Highlight)>,
+}
+
+impl<'a> Prompt<'a> {
+    pub fn new(
+        label: &'a str,
+        text: &'a str,
+        cursor: usize,
+        feedback: Option<(&'a str, Highlight)>,
+    ) -> Self {
+        Self {
+            label,
+            text,
+            cursor,
+            feedback,
+        }
+    }
+}
+
+pub struct ChangeMarkers<'a> {
+    pub start: usize,
+
+    pub markers: &'a [(usize, ChangeKind)],
+}
+
+impl<'a> ChangeMarkers<'a> {
+    pub fn new(start: usize, markers: &'a [(usize, ChangeKind)]) -> Self {
+        Self { start, markers }
+    }
+}
+
+pub struct StatusLine<'a> {
+    pub left: &'a [(String, Highlight)],
+
+    pub center: &'a [(String, Highlight)],
+
+    pub right: &'a [(String, Highlight)],
+}
+
+impl<'a> StatusLine<'a> {
+    pub fn new(
+        left: &'a [(String, Highlight)],
+        center: &'a [(String, Highlight)],
+        right: &'a [(String, Highlight)],
+    ) -> Self {
+        Self {
+            left,
+            center,
+            right,
+        }
+    }
+}
+
+pub struct Separator;
+
+pub struct EditorView<'a> {
+    pub editor: &'a TextEditor<TermLineLayoutSettings>,
+
+    pub highlighter: Option<&'a Highlighter>,
+
+    pub diagnostics: &'a [(Range<usize>, Severity)],
+
+    pub regions: &'a [(Range<usize>, Highlight)],
+}
+
+impl<'a> EditorView<'a> {
+    pub fn new(
+        editor: &'a TextEditor<TermLineLayoutSettings>,
+        highlighter: Option<&'a Highlighter>,
+        diagnostics: &'a [(Range<usize>, Severity)],
+        regions: &'a [(Range<usize>, Highlight)],
+    ) -> Self {
+        Self {
+            editor,
+            highlighter,
+            diagnostics,
+            regions,
+        }
+    }
+}
+
+pub struct Popup<'a> {
+    pub lines: &'a [String],
+
+    pub selected: Option<usize>,
+}
+
+impl<'a> Popup<'a> {
+    pub fn new(lines: &'a [String], selected: Option<usize>) -> Self {
+        Self { lines, selected }
+    }
+}
+
+pub struct TabBar<'a> {
+    pub tabs: &'a [String],
+
+    pub selected: usize,
+}
+
+impl<'a> TabBar<'a> {
+    pub fn new(tabs: &'a [String], selected: usize) -> Self {
+        Self { tabs, selected }
+    }
+
+    pub fn visible_tabs(&self, width: usize) -> Vec<(usize, usize, usize)> {
+        let widths = self
+            .tabs
+            .iter()
+            .map(|x| string_width(x.chars(), TERM_TAB_WIDTH) + 2)
+            .collect::<Vec<_>>();
+
+        let mut first = 0;
+
+        while first < self.selected && widths[first..=self.selected].iter().sum::<usize>() > width {
+            first += 1;
+        }
+
+        let mut column = 0;
+
+        (first..self.tabs.len())
+            .map_while(|index| {
+                let start = column;
+
+                column += widths[index];
+
+                (column <= width).then_some((index, start, widths[index]))
+            })
+            .collect()
+    }
+}
+
+pub struct FileTree<'a> {
+    pub entries: &'a [Entry],
+
+    pub selected: usize,
+
+    pub scroll: usize,
+
+    pub focused: bool,
+}
+
+impl<'a> FileTree<'a> {
+    pub fn new(entries: &'a [Entry], selected: usize, scroll: usize, focused: bool) -> Self {
+        Self {
+            entries,
+            selected,
+            scroll,
+            focused,
+        }
+    }
+}
+
+pub struct Picker<'a> {
+    pub prompt: &'a str,
+
+    pub query: &'a str,
+
+    pub items: &'a [&'a str],
+
+    pub selected: usize,
+
+    pub status: &'a str,
+
+    pub preview: Option<&'a [String]>,
+}
+
+impl<'a> Picker<'a> {
+    pub fn new(
+        prompt: &'a str,
+        query: &'a str,
+        items: &'a [&'a str],
+        selected: usize,
+        status: &'a str,
+        preview: Option<&'a [String]>,
+    ) -> Self {
+        Self {
+            prompt,
+            query,
+            items,
+            selected,
+            status,
+            preview,
+        }
+    }
+}
+
+pub struct DialogBox<'a> {
+    pub message: &'a str,
+
+    pub buttons: &'a [&'a str],
+
+    pub selected: usize,
+}
+
+impl<'a> DialogBox<'a> {
+    pub fn new(message: &'a str, buttons: &'a [&'a str], selected: usize) -> Self {
+        Self {
+            message,
+            buttons,
+            selected,
+        }
+    }
+}
+
+pub struct ResultsPanel<'a> {
+    pub title: &'a str,
+
+    pub rows: &'a [(String, Highlight)],
+
+    pub selected: usize,
+
+    pub scroll: usize,
+
+    pub focused: bool,
+}
+
+impl<'a> ResultsPanel<'a> {
+    pub fn new(
+        title: &'a str,
+        rows: &'a [(String, Highlight)],
+        selected: usize,
+        scroll: usize,
+        focused: bool,
+    ) -> Self {
+        Self {
+            title,
+            rows,
+            selected,
+            scroll,
+            focused,
+        }
+    }
+}
+
+pub struct DiffPanel<'a> {
+    pub view: &'a DiffView,
+
+    pub message: Option<&'a str>,
+}
+
+impl<'a> DiffPanel<'a> {
+    pub fn new(view: &'a DiffView, message: Option<&'a str>) -> Self {
+        Self { view, message }
+    }
+}
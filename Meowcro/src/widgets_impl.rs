@@ -1,9 +1,43 @@
 use crate::widgets::*;
-use crate::editor::*;
 use crate::ui::*;
+use crate::diffview::{DiffRow, RowKind, Side};
+use crate::git::ChangeKind;
+use crate::lsp::Severity;
 use crate::terminal::*;
 use crate::unicode::*;
 
+const MAX_POPUP_HEIGHT: usize = 10;
+
+const MAX_POPUP_WIDTH: usize = 60;
+
+const FILE_TREE_WIDTH: u32 = 30;
+
+const MAX_PICKER_WIDTH: u32 = 60;
+
+const MAX_PREVIEW_PICKER_WIDTH: u32 = 110;
+
+const MAX_PICKER_HEIGHT: u32 = 20;
+
+const RESULTS_PANEL_HEIGHT: u32 = 10;
+
+const MAX_DIALOG_WIDTH: usize = 60;
+
+fn diagnostic_highlight(severity: Severity) -> Highlight {
+    match severity {
+        Severity::Error => Highlight::Error,
+        Severity::Warning => Highlight::Warning,
+        Severity::Information | Severity::Hint => Highlight::Info,
+    }
+}
+
+fn diagnostic_sign(severity: Severity) -> Char {
+    match severity {
+        Severity::Error => Char::new('E', Highlight::ErrorSign),
+        Severity::Warning => Char::new('W', Highlight::WarningSign),
+        Severity::Information | Severity::Hint => Char::new('I', Highlight::InfoSign),
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub enum UiEvent {
     Clicked(usize, usize, bool),
@@ -20,6 +54,16 @@ pub enum UiReaction {
     SetRelativeCursorPos(usize, usize, bool),
 
     ScrollBy(isize),
+
+    SelectTab(usize),
+
+    SelectEntry(usize),
+
+    SelectResult(usize),
+
+    SetPromptCursor(usize),
+
+    PressButton(usize),
 }
 
 impl<'a> Drawable<TerminalBuffer> for TextLine<'a> {
@@ -60,7 +104,753 @@ impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for TextLine<'a> {
     }
 }
 
-impl Drawable<TerminalBuffer> for LineNumbers {
+fn status_group(segments: &[(String, Highlight)]) -> Vec<Char> {
+    let mut group = Vec::new();
+
+    for (index, (text, highlight)) in segments.iter().enumerate() {
+        if index > 0 {
+            group.push(Char::new('|', Highlight::Status));
+        }
+
+        group.extend(
+            format!(" {text} ")
+                .chars()
+                .map(|c| Char::new(if c == '\t' { ' ' } else { c }, *highlight)),
+        );
+    }
+
+    group
+}
+
+fn chars_width(chars: &[Char]) -> usize {
+    string_width(chars.iter().map(|x| x.c), TERM_TAB_WIDTH)
+}
+
+fn pad_status(buffer: &mut Vec<Char>, width: usize) {
+    let current = chars_width(buffer);
+
+    buffer.extend(std::iter::repeat_n(
+        Char::new(' ', Highlight::Status),
+        width.saturating_sub(current),
+    ));
+}
+
+impl<'a> Drawable<TerminalBuffer> for StatusLine<'a> {
+    fn draw(&self, width: u32, _: u32) -> TerminalBuffer {
+        let width = width as usize;
+
+        let available = width - chars_width(&status_group(self.left)).min(width / 2);
+
+        let right = (0..=self.right.len())
+            .map(|x| status_group(&self.right[x..]))
+            .find(|x| chars_width(x) <= available)
+            .unwrap_or_default();
+
+        let right_start = width - chars_width(&right);
+
+        let mut buffer = Vec::with_capacity(width);
+        let mut column = 0;
+
+        for c in status_group(self.left) {
+            column += string_width(std::iter::once(c.c), TERM_TAB_WIDTH);
+
+            if column > right_start {
+                break;
+            }
+
+            buffer.push(c);
+        }
+
+        let center = status_group(self.center);
+
+        let center_start = width.saturating_sub(chars_width(&center)) / 2;
+
+        if center_start >= chars_width(&buffer) && center_start + chars_width(&center) <= right_start {
+            pad_status(&mut buffer, center_start);
+            buffer.extend(center);
+        }
+
+        pad_status(&mut buffer, right_start);
+        buffer.extend(right);
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for StatusLine<'a> {
+    fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
+        Vec::new()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for StatusLine<'a> {
+    fn minimum_size(&self, width: u32, _: u32) -> (u32, u32) {
+        (width, 1)
+    }
+
+    fn maximum_size(&self, width: u32, _: u32) -> (u32, u32) {
+        (width, 1)
+    }
+}
+
+impl<'a> Prompt<'a> {
+    fn get_label_width(&self) -> usize {
+        string_width(self.label.chars(), TERM_TAB_WIDTH) + 1
+    }
+
+    fn get_scroll(&self, width: usize) -> usize {
+        let available = width.saturating_sub(self.get_label_width() + 1);
+
+        let mut scroll = 0;
+
+        while scroll < self.cursor
+            && string_width(self.text.chars().skip(scroll).take(self.cursor - scroll), TERM_TAB_WIDTH)
+                > available
+        {
+            scroll += 1;
+        }
+
+        scroll
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for Prompt<'a> {
+    fn draw(&self, width: u32, _: u32) -> TerminalBuffer {
+        let width = width as usize;
+
+        let scroll = self.get_scroll(width);
+
+        let text = self.text.chars().skip(scroll).collect::<String>();
+
+        let cursor = self.get_label_width()
+            + string_width(text.chars().take(self.cursor - scroll), TERM_TAB_WIDTH);
+
+        let line = format!(" {}{text}", self.label);
+
+        let mut buffer = match self.feedback {
+            Some((feedback, highlight)) => {
+                let line_width = string_width(line.chars(), TERM_TAB_WIDTH) + 2;
+
+                let mut buffer = padded_line(&line, line_width.min(width), Highlight::Status);
+
+                buffer.extend(padded_line(
+                    feedback,
+                    width.saturating_sub(line_width),
+                    highlight,
+                ));
+
+                buffer
+            }
+            None => padded_line(&line, width, Highlight::Status),
+        };
+
+        buffer.truncate(width);
+
+        (buffer, Some((cursor.min(width.saturating_sub(1)), 0)))
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for Prompt<'a> {
+    fn interact(&self, event: &UiEvent, x: u32, y: u32, width: u32, _: u32) -> Vec<UiReaction> {
+        let UiEvent::Clicked(click_x, click_y, _) = *event else {
+            return Vec::new();
+        };
+
+        let start = x as usize + self.get_label_width();
+
+        if click_y != y as usize || !(start..x as usize + width as usize).contains(&click_x) {
+            return Vec::new();
+        }
+
+        let mut column = 0;
+        let mut cursor = self.get_scroll(width as usize);
+
+        for c in self.text.chars().skip(cursor) {
+            column += string_width(std::iter::once(c), TERM_TAB_WIDTH);
+
+            if column > click_x - start {
+                break;
+            }
+
+            cursor += 1;
+        }
+
+        vec![UiReaction::SetPromptCursor(cursor)]
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for Prompt<'a> {
+    fn minimum_size(&self, width: u32, _: u32) -> (u32, u32) {
+        (width, 1)
+    }
+
+    fn maximum_size(&self, width: u32, _: u32) -> (u32, u32) {
+        (width, 1)
+    }
+}
+
+impl Drawable<TerminalBuffer> for Separator {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        (
+            std::iter::repeat_n(Char::new('|', Highlight::Status), width as usize * height as usize)
+                .collect(),
+            None,
+        )
+    }
+}
+
+impl Interactive<UiEvent, Vec<UiReaction>> for Separator {
+    fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
+        Vec::new()
+    }
+}
+
+impl Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for Separator {
+    fn minimum_size(&self, _: u32, height: u32) -> (u32, u32) {
+        (1, height)
+    }
+
+    fn maximum_size(&self, _: u32, height: u32) -> (u32, u32) {
+        (1, height)
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for TabBar<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let mut buffer = Vec::with_capacity(width as usize * height as usize);
+
+        let mut column = 0;
+
+        for (index, _, tab_width) in self.visible_tabs(width as usize) {
+            let highlight = if index == self.selected {
+                Highlight::TabSelected
+            } else {
+                Highlight::Tab
+            };
+
+            buffer.extend(
+                format!(" {} ", self.tabs[index])
+                    .chars()
+                    .map(|x| Char::new(x, highlight)),
+            );
+
+            column += tab_width;
+        }
+
+        buffer.extend(std::iter::repeat_n(
+            Char::new(' ', Highlight::Tab),
+            (width as usize * height as usize).saturating_sub(column),
+        ));
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for TabBar<'a> {
+    fn interact(&self, event: &UiEvent, x: u32, y: u32, width: u32, _: u32) -> Vec<UiReaction> {
+        let UiEvent::Clicked(click_x, click_y, false) = *event else {
+            return Vec::new();
+        };
+
+        if click_y != y as usize || click_x < x as usize {
+            return Vec::new();
+        }
+
+        self.visible_tabs(width as usize)
+            .into_iter()
+            .find(|(_, start, tab_width)| (*start..start + tab_width).contains(&(click_x - x as usize)))
+            .map(|(index, _, _)| UiReaction::SelectTab(index))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for TabBar<'a> {
+    fn minimum_size(&self, width: u32, _: u32) -> (u32, u32) {
+        (width, 1)
+    }
+
+    fn maximum_size(&self, width: u32, _: u32) -> (u32, u32) {
+        (width, 1)
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for FileTree<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let mut buffer = Vec::with_capacity(width as usize * height as usize);
+
+        for index in self.scroll..self.scroll + height as usize {
+            let highlight = if index == self.selected && self.focused {
+                Highlight::Selection
+            } else if index == self.selected {
+                Highlight::Status
+            } else {
+                Highlight::Text
+            };
+
+            let label = self
+                .entries
+                .get(index)
+                .map(|x| x.get_label())
+                .unwrap_or_default();
+
+            let mut column = 0;
+
+            for c in label.chars() {
+                let character_width = string_width(std::iter::once(c), TERM_TAB_WIDTH);
+
+                if column + character_width > width as usize {
+                    break;
+                }
+
+                buffer.push(Char::new(c, highlight));
+                column += character_width;
+            }
+
+            buffer.extend(std::iter::repeat_n(
+                Char::new(' ', highlight),
+                width as usize - column,
+            ));
+        }
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for FileTree<'a> {
+    fn interact(&self, event: &UiEvent, x: u32, y: u32, width: u32, height: u32) -> Vec<UiReaction> {
+        let UiEvent::Clicked(click_x, click_y, false) = *event else {
+            return Vec::new();
+        };
+
+        let (x, y) = (x as usize, y as usize);
+
+        if !(x..x + width as usize).contains(&click_x) || !(y..y + height as usize).contains(&click_y) {
+            return Vec::new();
+        }
+
+        let index = self.scroll + click_y - y;
+
+        if index < self.entries.len() {
+            vec![UiReaction::SelectEntry(index)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for FileTree<'a> {
+    fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (FILE_TREE_WIDTH.min(width / 2), height)
+    }
+
+    fn maximum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.minimum_size(width, height)
+    }
+}
+
+fn padded_line(text: &str, width: usize, highlight: Highlight) -> Vec<Char> {
+    styled_line(text.chars().map(|x| (x, highlight)), width, highlight)
+}
+
+fn styled_line(chars: impl Iterator<Item = (char, Highlight)>, width: usize, fill: Highlight) -> Vec<Char> {
+    let mut line = Vec::with_capacity(width);
+
+    let mut column = 0;
+
+    for (c, highlight) in chars {
+        let c = if c == '\t' { ' ' } else { c };
+
+        let character_width = string_width(std::iter::once(c), TERM_TAB_WIDTH);
+
+        if column + character_width > width {
+            break;
+        }
+
+        line.push(Char::new(c, highlight));
+        column += character_width;
+    }
+
+    line.extend(std::iter::repeat_n(Char::new(' ', fill), width - column));
+
+    line
+}
+
+impl<'a> Drawable<TerminalBuffer> for Picker<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let (width, height) = (width as usize, height as usize);
+
+        let input = format!(" {}{}", self.prompt, self.query);
+
+        let input_width = string_width(input.chars(), TERM_TAB_WIDTH);
+        let status_width = string_width(self.status.chars(), TERM_TAB_WIDTH) + 1;
+
+        let mut buffer = padded_line(&input, width.saturating_sub(status_width), Highlight::Popup);
+        buffer.extend(padded_line(
+            &format!("{} ", self.status),
+            width.min(status_width),
+            Highlight::Popup,
+        ));
+
+        let list_width = match self.preview {
+            Some(_) => width.saturating_sub(1) / 2,
+            None => width,
+        };
+
+        let list_height = height.saturating_sub(1);
+
+        let scroll = (self.selected + 1).saturating_sub(list_height);
+
+        for row in 0..list_height {
+            let index = scroll + row;
+
+            let highlight = if index == self.selected {
+                Highlight::PopupSelection
+            } else {
+                Highlight::Popup
+            };
+
+            let item = self.items.get(index).copied().unwrap_or("");
+
+            buffer.extend(padded_line(&format!(" {item}"), list_width, highlight));
+
+            if let Some(preview) = self.preview {
+                buffer.push(Char::new('|', Highlight::Popup));
+
+                let line = preview.get(row).map(String::as_str).unwrap_or("");
+
+                buffer.extend(padded_line(
+                    &format!(" {line}"),
+                    width - list_width - 1,
+                    Highlight::Popup,
+                ));
+            }
+        }
+
+        (buffer, Some((input_width.min(width.saturating_sub(1)), 0)))
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for Picker<'a> {
+    fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
+        Vec::new()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for Picker<'a> {
+    fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let max_width = match self.preview {
+            Some(_) => MAX_PREVIEW_PICKER_WIDTH,
+            None => MAX_PICKER_WIDTH,
+        };
+
+        (
+            max_width.min(width.saturating_sub(4)),
+            MAX_PICKER_HEIGHT.min(height.saturating_sub(4)),
+        )
+    }
+
+    fn maximum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.minimum_size(width, height)
+    }
+}
+
+impl<'a> DialogBox<'a> {
+    fn get_button_label(&self, index: usize) -> String {
+        format!("[ {} ]", self.buttons[index])
+    }
+
+    fn get_buttons_width(&self) -> usize {
+        (0..self.buttons.len())
+            .map(|x| string_width(self.get_button_label(x).chars(), TERM_TAB_WIDTH) + 2)
+            .sum()
+    }
+
+    fn get_lines(&self, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for paragraph in self.message.lines() {
+            let mut line = String::new();
+
+            for word in paragraph.split(' ') {
+                let line_width = string_width(line.chars(), TERM_TAB_WIDTH);
+
+                if !line.is_empty() && line_width + 1 + string_width(word.chars(), TERM_TAB_WIDTH) > width {
+                    lines.push(std::mem::take(&mut line));
+                } else if !line.is_empty() {
+                    line.push(' ');
+                }
+
+                line.push_str(word);
+            }
+
+            lines.push(line);
+        }
+
+        lines
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for DialogBox<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let (width, height) = (width as usize, height as usize);
+
+        let mut buffer = padded_line("", width, Highlight::Popup);
+
+        for line in self.get_lines(width.saturating_sub(4)) {
+            buffer.extend(padded_line(&format!("  {line}"), width, Highlight::Popup));
+        }
+
+        buffer.extend(padded_line("", width, Highlight::Popup));
+
+        let mut column = width.saturating_sub(self.get_buttons_width());
+
+        buffer.extend(padded_line("", column, Highlight::Popup));
+
+        for index in 0..self.buttons.len() {
+            let highlight = if index == self.selected {
+                Highlight::PopupSelection
+            } else {
+                Highlight::Popup
+            };
+
+            let label = self.get_button_label(index);
+
+            let label_width = string_width(label.chars(), TERM_TAB_WIDTH).min(width - column);
+
+            buffer.extend(padded_line(&label, label_width, highlight));
+            buffer.extend(padded_line("", 2.min(width - column - label_width), Highlight::Popup));
+
+            column = (column + label_width + 2).min(width);
+        }
+
+        buffer.extend(padded_line("", width - column, Highlight::Popup));
+
+        buffer.resize(width * height, Char::new(' ', Highlight::Popup));
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for DialogBox<'a> {
+    fn interact(&self, event: &UiEvent, x: u32, y: u32, width: u32, height: u32) -> Vec<UiReaction> {
+        let UiEvent::Clicked(click_x, click_y, _) = *event else {
+            return Vec::new();
+        };
+
+        if click_y + 2 != (y + height) as usize {
+            return Vec::new();
+        }
+
+        let mut column = x as usize + (width as usize).saturating_sub(self.get_buttons_width());
+
+        for index in 0..self.buttons.len() {
+            let label_width = string_width(self.get_button_label(index).chars(), TERM_TAB_WIDTH);
+
+            if (column..column + label_width).contains(&click_x) {
+                return vec![UiReaction::PressButton(index)];
+            }
+
+            column += label_width + 2;
+        }
+
+        Vec::new()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for DialogBox<'a> {
+    fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let width = (width as usize).min(MAX_DIALOG_WIDTH);
+
+        let message_width = self
+            .message
+            .lines()
+            .map(|x| string_width(x.chars(), TERM_TAB_WIDTH))
+            .max()
+            .unwrap_or(0);
+
+        let dialog_width = (message_width + 4).max(self.get_buttons_width() + 2).min(width);
+
+        let lines = self.get_lines(dialog_width.saturating_sub(4)).len();
+
+        (dialog_width as u32, ((lines + 4) as u32).min(height))
+    }
+
+    fn maximum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.minimum_size(width, height)
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for ResultsPanel<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let width = width as usize;
+
+        let mut buffer = padded_line(&format!(" {}", self.title), width, Highlight::Status);
+
+        for index in self.scroll..self.scroll + (height as usize).saturating_sub(1) {
+            let (text, style) = self
+                .rows
+                .get(index)
+                .map(|(x, y)| (x.as_str(), *y))
+                .unwrap_or(("", Highlight::Text));
+
+            let highlight = if index == self.selected && self.focused {
+                Highlight::Selection
+            } else if index == self.selected {
+                Highlight::Status
+            } else {
+                style
+            };
+
+            buffer.extend(padded_line(&format!(" {text}"), width, highlight));
+        }
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for ResultsPanel<'a> {
+    fn interact(&self, event: &UiEvent, x: u32, y: u32, width: u32, height: u32) -> Vec<UiReaction> {
+        let UiEvent::Clicked(click_x, click_y, false) = *event else {
+            return Vec::new();
+        };
+
+        let (x, y) = (x as usize, y as usize);
+
+        if !(x..x + width as usize).contains(&click_x) || !(y + 1..y + height as usize).contains(&click_y) {
+            return Vec::new();
+        }
+
+        let index = self.scroll + click_y - y - 1;
+
+        if index < self.rows.len() {
+            vec![UiReaction::SelectResult(index)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for ResultsPanel<'a> {
+    fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (width, RESULTS_PANEL_HEIGHT.min(height / 3))
+    }
+
+    fn maximum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.minimum_size(width, height)
+    }
+}
+
+impl<'a> DiffPanel<'a> {
+    fn draw_side(&self, row: &DiffRow, side: Side, changes: &[std::ops::Range<usize>], width: usize) -> Vec<Char> {
+        let digits = self.view.len_lines().max(1).to_string().len();
+
+        let line = match side {
+            Side::Left => row.left,
+            Side::Right => row.right,
+        };
+
+        let selected = row.hunk.is_some() && row.hunk == self.view.get_selected();
+
+        let gutter = match line {
+            Some(x) => format!("{}{:>digits$} ", if selected { '>' } else { ' ' }, x + 1),
+            None => format!("{}{:digits$} ", if selected { '>' } else { ' ' }, ""),
+        };
+
+        let mut buffer = padded_line(&gutter, (digits + 2).min(width), Highlight::Gutter);
+
+        let width = width - buffer.len();
+
+        let Some(line) = line else {
+            buffer.extend(std::iter::repeat_n(Char::new('-', Highlight::Gutter), width));
+            return buffer;
+        };
+
+        let highlight = match row.kind {
+            RowKind::Equal => Highlight::Text,
+            RowKind::Changed => Highlight::Modified,
+            RowKind::Removed => Highlight::Removed,
+            RowKind::Added => Highlight::Added,
+        };
+
+        let chars = self.view.get_line(side, line).chars().enumerate().map(|(index, c)| {
+            if changes.iter().any(|x| x.contains(&index)) {
+                (c, Highlight::DiffText)
+            } else {
+                (c, highlight)
+            }
+        });
+
+        buffer.extend(styled_line(chars, width, Highlight::Text));
+
+        buffer
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for DiffPanel<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let (width, height) = (width as usize, height as usize);
+
+        let left_width = width.saturating_sub(1) / 2;
+        let right_width = width.saturating_sub(left_width + 1);
+
+        let separator = Char::new('|', Highlight::Gutter);
+
+        let mut buffer = padded_line(&format!(" {}", self.view.get_title(Side::Left)), left_width, Highlight::Status);
+        buffer.push(separator);
+        buffer.extend(padded_line(&format!(" {}", self.view.get_title(Side::Right)), right_width, Highlight::Status));
+
+        let rows = self.view.get_rows();
+
+        for index in self.view.get_scroll()..self.view.get_scroll() + height.saturating_sub(2) {
+            match rows.get(index) {
+                Some(row) => {
+                    let (left_changes, right_changes) = if row.kind == RowKind::Changed {
+                        self.view.get_inline_changes(row)
+                    } else {
+                        (Vec::new(), Vec::new())
+                    };
+
+                    buffer.extend(self.draw_side(row, Side::Left, &left_changes, left_width));
+                    buffer.push(separator);
+                    buffer.extend(self.draw_side(row, Side::Right, &right_changes, right_width));
+                }
+                None => {
+                    buffer.extend(padded_line("~", left_width, Highlight::Gutter));
+                    buffer.push(separator);
+                    buffer.extend(padded_line("~", right_width, Highlight::Gutter));
+                }
+            }
+        }
+
+        let status = format!(
+            " {} | alt+j/k: next/previous | <, >: copy to left/right | esc: close",
+            self.message.map(str::to_string).unwrap_or_else(|| self.view.get_status())
+        );
+
+        buffer.extend(padded_line(&status, width, Highlight::Status));
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for DiffPanel<'a> {
+    fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
+        Vec::new()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for DiffPanel<'a> {
+    fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (width, height)
+    }
+
+    fn maximum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (width, height)
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for LineNumbers<'a> {
     fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
         let mut buffer = Vec::with_capacity(width as usize * height as usize);
 
@@ -74,6 +864,11 @@ impl Drawable<TerminalBuffer> for LineNumbers {
         for line in start..end {
             let mut column = 0;
 
+            if let Some((_, severity)) = self.signs.iter().find(|(x, _)| x + 1 == line) {
+                buffer.push(diagnostic_sign(*severity));
+                column += 1;
+            }
+
             while column < (width as usize).min(space_padding) {
                 buffer.push(Char::new(' ', Highlight::Gutter));
                 column += 1;
@@ -109,14 +904,16 @@ impl Drawable<TerminalBuffer> for LineNumbers {
         }
 
         buffer.extend(
-            std::iter::repeat((0..width as usize).map(|x| {
-                if x == padding {
-                    Char::new('~', Highlight::Gutter)
-                } else {
-                    Char::new(' ', Highlight::Gutter)
-                }
-            }))
-            .take((height as usize).saturating_sub(end - start))
+            std::iter::repeat_n(
+                (0..width as usize).map(|x| {
+                    if x == padding {
+                        Char::new('~', Highlight::Gutter)
+                    } else {
+                        Char::new(' ', Highlight::Gutter)
+                    }
+                }),
+                (height as usize).saturating_sub(end - start),
+            )
             .flatten(),
         );
 
@@ -124,13 +921,13 @@ impl Drawable<TerminalBuffer> for LineNumbers {
     }
 }
 
-impl Interactive<UiEvent, Vec<UiReaction>> for LineNumbers {
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for LineNumbers<'a> {
     fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
         Vec::new()
     }
 }
 
-impl Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for LineNumbers {
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for LineNumbers<'a> {
     fn minimum_size(&self, _: u32, height: u32) -> (u32, u32) {
         (self.width(height as usize) as u32, height)
     }
@@ -140,30 +937,84 @@ impl Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for LineNumbers {
     }
 }
 
-impl Drawable<TerminalBuffer> for TextEditor<TermLineLayoutSettings> {
+impl<'a> Drawable<TerminalBuffer> for ChangeMarkers<'a> {
     fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
         let mut buffer = Vec::with_capacity(width as usize * height as usize);
 
-        let selection_range = self.get_selection_range().unwrap_or(0..0);
+        for line in self.start..self.start + height as usize {
+            let marker = match self.markers.iter().find(|(x, _)| *x == line) {
+                Some((_, ChangeKind::Added)) => Char::new('+', Highlight::Added),
+                Some((_, ChangeKind::Modified)) => Char::new('~', Highlight::Modified),
+                Some((_, ChangeKind::Deleted)) => Char::new('-', Highlight::Removed),
+                None => Char::new(' ', Highlight::Gutter),
+            };
+
+            buffer.push(marker);
+            buffer.extend(std::iter::repeat_n(
+                Char::new(' ', Highlight::Gutter),
+                (width as usize).saturating_sub(1),
+            ));
+        }
 
-        for line_num in self.get_lines_scrolled()..self.get_lines_scrolled() + height as usize {
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for ChangeMarkers<'a> {
+    fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
+        Vec::new()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for ChangeMarkers<'a> {
+    fn minimum_size(&self, _: u32, height: u32) -> (u32, u32) {
+        (1, height)
+    }
+
+    fn maximum_size(&self, _: u32, height: u32) -> (u32, u32) {
+        (1, height)
+    }
+}
+
+impl<'a> Drawable<TerminalBuffer> for EditorView<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let mut buffer = Vec::with_capacity(width as usize * height as usize);
+
+        let editor = self.editor;
+
+        let selection_range = editor.get_selection_range().unwrap_or(0..0);
+
+        for line_num in editor.get_lines_scrolled()..editor.get_lines_scrolled() + height as usize {
             let mut column = 0;
 
             let mut cursor = 0;
 
-            if let Some(line) = self.get_text().get_line(line_num) {
-                let line_start = self.get_text().line_to_byte(line_num);
+            if let Some(line) = editor.get_text().get_line(line_num) {
+                let line_start = editor.get_text().line_to_byte(line_num);
+
+                let region = self
+                    .regions
+                    .iter()
+                    .find(|(x, _)| x.contains(&line_num))
+                    .map(|(_, x)| *x);
+
+                let mut spans = self
+                    .highlighter
+                    .map(|x| x.get_line_spans(line_num))
+                    .unwrap_or(&[])
+                    .iter()
+                    .peekable();
 
                 while cursor < line.len_bytes()
-                    && column < self.get_columns_scrolled() + width as usize
+                    && column < editor.get_columns_scrolled() + width as usize
                 {
                     let next_cursor = move_grapheme(1, cursor, line);
 
                     let grapheme = line.byte_slice(cursor..next_cursor);
 
                     if grapheme.chars().any(is_newline) {
-                        if column >= self.get_columns_scrolled()
-                            && column < self.get_columns_scrolled() + width as usize
+                        if column >= editor.get_columns_scrolled()
+                            && column < editor.get_columns_scrolled() + width as usize
                             && selection_range.contains(&(cursor + line_start))
                         {
                             buffer.push(Char::new(' ', Highlight::Selection));
@@ -174,37 +1025,63 @@ impl Drawable<TerminalBuffer> for TextEditor<TermLineLayoutSettings> {
                         break;
                     }
 
-                    let grapheme_width = string_width(grapheme.chars(), self.get_tab_width());
+                    while spans.peek().is_some_and(|x| x.end <= cursor) {
+                        spans.next();
+                    }
+
+                    let diagnostic = self.diagnostics.iter().find(|(x, _)| {
+                        x.contains(&(cursor + line_start))
+                            || (x.is_empty() && x.start == cursor + line_start)
+                    });
+
+                    let highlight = if selection_range.contains(&(cursor + line_start)) {
+                        Highlight::Selection
+                    } else if let Some((_, severity)) = diagnostic {
+                        diagnostic_highlight(*severity)
+                    } else if let Some(x) = region {
+                        x
+                    } else {
+                        spans
+                            .peek()
+                            .filter(|x| x.start <= cursor)
+                            .map(|x| x.highlight)
+                            .unwrap_or(Highlight::Text)
+                    };
+
+                    let grapheme_width = string_width(grapheme.chars(), editor.get_tab_width());
 
-                    if column < self.get_columns_scrolled()
-                        && column + grapheme_width > self.get_columns_scrolled()
+                    if column < editor.get_columns_scrolled()
+                        && column + grapheme_width > editor.get_columns_scrolled()
                     {
                         buffer.extend(
-                            std::iter::repeat(Char::new_text(' ', false))
-                                .take(column + grapheme_width - self.get_columns_scrolled()),
+                            std::iter::repeat_n(
+                                Char::new_text(' ', false),
+                                column + grapheme_width - editor.get_columns_scrolled(),
+                            ),
                         );
 
-                    } else if column + grapheme_width > self.get_columns_scrolled() + width as usize
+                    } else if column + grapheme_width > editor.get_columns_scrolled() + width as usize
                     {
                         buffer.extend(
-                            std::iter::repeat(Char::new_text(' ', false))
-                                .take(self.get_columns_scrolled() + width as usize - column),
+                            std::iter::repeat_n(
+                                Char::new_text(' ', false),
+                                editor.get_columns_scrolled() + width as usize - column,
+                            ),
                         );
 
-                    } else if column >= self.get_columns_scrolled()
-                        && column + grapheme_width <= self.get_columns_scrolled() + width as usize
+                    } else if column >= editor.get_columns_scrolled()
+                        && column + grapheme_width <= editor.get_columns_scrolled() + width as usize
                         && grapheme.chars().eq(std::iter::once('\t'))
                     {
-                        buffer.extend(std::iter::repeat(' ').take(self.get_tab_width()).map(|x| {
-                            Char::new_text(x, selection_range.contains(&(cursor + line_start)))
-                        }));
+                        buffer.extend(
+                            std::iter::repeat_n(' ', editor.get_tab_width())
+                                .map(|x| Char::new(x, highlight)),
+                        );
 
-                    } else if column >= self.get_columns_scrolled()
-                        && column + grapheme_width <= self.get_columns_scrolled() + width as usize
+                    } else if column >= editor.get_columns_scrolled()
+                        && column + grapheme_width <= editor.get_columns_scrolled() + width as usize
                     {
-                        buffer.extend(grapheme.chars().map(|x| {
-                            Char::new_text(x, selection_range.contains(&(cursor + line_start)))
-                        }));
+                        buffer.extend(grapheme.chars().map(|x| Char::new(x, highlight)));
                     }
 
                     cursor = next_cursor;
@@ -213,20 +1090,21 @@ impl Drawable<TerminalBuffer> for TextEditor<TermLineLayoutSettings> {
             }
 
             buffer.extend(
-                std::iter::repeat(Char::new_text(' ', false)).take(
-                    (width as usize + self.get_columns_scrolled())
-                        .saturating_sub(column.max(self.get_columns_scrolled())),
+                std::iter::repeat_n(
+                    Char::new_text(' ', false),
+                    (width as usize + editor.get_columns_scrolled())
+                        .saturating_sub(column.max(editor.get_columns_scrolled())),
                 ),
             );
         }
 
-        let cursor_pos = self.get_relative_cursor_pos();
+        let cursor_pos = editor.get_relative_cursor_pos();
 
         (buffer, cursor_pos)
     }
 }
 
-impl Interactive<UiEvent, Vec<UiReaction>> for TextEditor<TermLineLayoutSettings> {
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for EditorView<'a> {
     fn interact(
         &self,
         event: &UiEvent,
@@ -270,7 +1148,7 @@ impl Interactive<UiEvent, Vec<UiReaction>> for TextEditor<TermLineLayoutSettings
     }
 }
 
-impl Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for TextEditor<TermLineLayoutSettings> {
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for EditorView<'a> {
     fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
         (width, height)
     }
@@ -280,6 +1158,75 @@ impl Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for TextEditor<TermLineLay
     }
 }
 
+impl<'a> Drawable<TerminalBuffer> for Popup<'a> {
+    fn draw(&self, width: u32, height: u32) -> TerminalBuffer {
+        let mut buffer = Vec::with_capacity(width as usize * height as usize);
+
+        let scroll = self
+            .selected
+            .map(|x| (x + 1).saturating_sub(height as usize))
+            .unwrap_or(0);
+
+        for index in scroll..scroll + height as usize {
+            let highlight = if self.selected == Some(index) {
+                Highlight::PopupSelection
+            } else {
+                Highlight::Popup
+            };
+
+            let line = self.lines.get(index).map(String::as_str).unwrap_or("");
+
+            let mut column = 0;
+
+            for c in std::iter::once(' ').chain(line.chars()) {
+                let c = if c == '\t' { ' ' } else { c };
+
+                let character_width = string_width(std::iter::once(c), TERM_TAB_WIDTH);
+
+                if column + character_width > width as usize {
+                    break;
+                }
+
+                buffer.push(Char::new(c, highlight));
+                column += character_width;
+            }
+
+            buffer.extend(std::iter::repeat_n(
+                Char::new(' ', highlight),
+                width as usize - column,
+            ));
+        }
+
+        (buffer, None)
+    }
+}
+
+impl<'a> Interactive<UiEvent, Vec<UiReaction>> for Popup<'a> {
+    fn interact(&self, _: &UiEvent, _: u32, _: u32, _: u32, _: u32) -> Vec<UiReaction> {
+        Vec::new()
+    }
+}
+
+impl<'a> Widget<TerminalBuffer, UiEvent, Vec<UiReaction>> for Popup<'a> {
+    fn minimum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let line_width = self
+            .lines
+            .iter()
+            .map(|x| string_width(x.chars(), TERM_TAB_WIDTH))
+            .max()
+            .unwrap_or(0);
+
+        (
+            (line_width + 2).min(MAX_POPUP_WIDTH).min(width as usize) as u32,
+            self.lines.len().min(MAX_POPUP_HEIGHT).min(height as usize) as u32,
+        )
+    }
+
+    fn maximum_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.minimum_size(width, height)
+    }
+}
+
 impl OutputResult for Vec<UiReaction> {
     fn empty() -> Self {
         Vec::new()
@@ -293,8 +1240,7 @@ impl OutputResult for Vec<UiReaction> {
 impl DrawResult for TerminalBuffer {
     fn empty(width: u32, height: u32) -> Self {
         (
-            std::iter::repeat(Char::new_text(' ', false))
-                .take(width as usize * height as usize)
+            std::iter::repeat_n(Char::new_text(' ', false), width as usize * height as usize)
                 .collect(),
             None,
         )
@@ -334,4 +1280,78 @@ impl DrawResult for TerminalBuffer {
             self.1.or(other.1.map(|(x, y)| (x + split as usize, y))),
         )
     }
+
+    fn overlay(self, other: Self, width: u32, x: u32, y: u32, other_width: u32) -> Self {
+        let (x, y) = (x as usize, y as usize);
+
+        let mut rows = split_rows(&self.0, width as usize);
+
+        for (index, other_row) in split_rows(&other.0, other_width as usize)
+            .into_iter()
+            .enumerate()
+        {
+            let Some(row) = rows.get_mut(y + index) else {
+                break;
+            };
+
+            let mut before = Vec::new();
+            let mut after = Vec::new();
+            let mut column = 0;
+
+            for character in row.iter() {
+                let end = column + string_width(std::iter::once(character.c), TERM_TAB_WIDTH);
+
+                if end <= x {
+                    before.push(*character);
+                } else if column >= x + other_width as usize {
+                    after.push(*character);
+                } else {
+                    before.extend(std::iter::repeat_n(
+                        Char::new(' ', character.color),
+                        x.saturating_sub(column),
+                    ));
+                    after.extend(std::iter::repeat_n(
+                        Char::new(' ', character.color),
+                        end.saturating_sub(x + other_width as usize),
+                    ));
+                }
+
+                column = end;
+            }
+
+            *row = before.into_iter().chain(other_row).chain(after).collect();
+        }
+
+        (rows.into_iter().flatten().collect(), self.1)
+    }
+
+    fn cursor(&self) -> Option<(u32, u32)> {
+        self.1.map(|(x, y)| (x as u32, y as u32))
+    }
+
+    fn with_cursor(self, cursor: Option<(u32, u32)>) -> Self {
+        (self.0, cursor.map(|(x, y)| (x as usize, y as usize)))
+    }
+}
+
+fn split_rows(chars: &[Char], width: usize) -> Vec<Vec<Char>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut column = 0;
+
+    for character in chars {
+        row.push(*character);
+        column += string_width(std::iter::once(character.c), TERM_TAB_WIDTH);
+
+        if column >= width {
+            rows.push(std::mem::take(&mut row));
+            column = 0;
+        }
+    }
+
+    if !row.is_empty() {
+        rows.push(row);
+    }
+
+    rows
 }
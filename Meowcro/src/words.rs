@@ -0,0 +1,90 @@
+use crate::editor::TextChange;
+use crate::unicode::is_word_character;
+use ropey::RopeSlice;
+use std::collections::HashMap;
+
+const MIN_WORD_LENGTH: usize = 2;
+
+pub struct WordIndex {
+    lines: Vec<Option<Vec<String>>>,
+
+    counts: HashMap<String, usize>,
+}
+
+impl WordIndex {
+    pub fn new(text: RopeSlice) -> Self {
+        let mut index = Self {
+            lines: vec![None; text.len_lines()],
+            counts: HashMap::new(),
+        };
+
+        index.update(text);
+        index
+    }
+
+    fn forget(&mut self, words: Vec<String>) {
+        for word in words {
+            if let Some(count) = self.counts.get_mut(&word) {
+                *count -= 1;
+
+                if *count == 0 {
+                    self.counts.remove(&word);
+                }
+            }
+        }
+    }
+
+    pub fn apply_changes(&mut self, changes: &[TextChange]) {
+        for change in changes {
+            let start = change.start_line.min(self.lines.len());
+            let old_end = (change.old_end_line + 1).min(self.lines.len()).max(start);
+            let inserted = change.new_end_line + 1 - change.start_line;
+
+            let removed = self
+                .lines
+                .splice(start..old_end, std::iter::repeat_n(None, inserted))
+                .flatten()
+                .collect::<Vec<_>>();
+
+            for words in removed {
+                self.forget(words);
+            }
+        }
+    }
+
+    pub fn update(&mut self, text: RopeSlice) {
+        while self.lines.len() > text.len_lines() {
+            if let Some(words) = self.lines.pop().flatten() {
+                self.forget(words);
+            }
+        }
+
+        self.lines.resize(text.len_lines(), None);
+
+        for index in 0..self.lines.len() {
+            if self.lines[index].is_some() {
+                continue;
+            }
+
+            let words = split_words(&text.line(index).to_string());
+
+            for word in &words {
+                *self.counts.entry(word.clone()).or_default() += 1;
+            }
+
+            self.lines[index] = Some(words);
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.counts.iter().map(|(x, y)| (x.as_str(), *y))
+    }
+}
+
+fn split_words(line: &str) -> Vec<String> {
+    line.split(|x: char| !is_word_character(x))
+        .filter(|x| x.chars().count() >= MIN_WORD_LENGTH)
+        .filter(|x| !x.starts_with(|x: char| x.is_numeric()))
+        .map(str::to_string)
+        .collect()
+}
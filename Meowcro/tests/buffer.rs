@@ -0,0 +1,164 @@
+mod support;
+
+use meowcro::buffer::{apply_workspace_edit, reload_changed_buffers, Buffer};
+use meowcro::completion::CompletionMenu;
+use meowcro::config::Settings;
+use meowcro::editor::TextEditor;
+use meowcro::lsp::{path_to_uri, Position, TextEdit};
+use meowcro::save::Backup;
+use meowcro::syntax::GrammarSet;
+use meowcro::terminal::TermLineLayoutSettings;
+use std::collections::HashMap;
+use std::path::Path;
+use support::TempDir;
+
+fn open(path: &Path, grammars: &GrammarSet) -> Buffer {
+    let content = std::fs::read_to_string(path).unwrap();
+    let editor = TextEditor::new(&content, TermLineLayoutSettings::new(4), 4, false);
+
+    Buffer::new(editor, path.to_path_buf(), Settings::default(), grammars)
+}
+
+fn rename(line: usize, character: usize) -> TextEdit {
+    TextEdit {
+        start: Position::new(line, character),
+        end: Position::new(line, character + 3),
+        text: "kitten".to_string(),
+    }
+}
+
+#[test]
+fn workspace_edits_change_open_buffers_and_write_closed_files() {
+    let root = TempDir::new("buffer-workspace-edit");
+    let (grammars, _) = GrammarSet::load();
+
+    for (file, content) in [
+        ("a.txt", "cat\ncat food\n"),
+        ("b.txt", "the cat\n"),
+        ("c.txt", "cat\n"),
+    ] {
+        std::fs::write(root.join(file), content).unwrap();
+    }
+
+    let mut buffers = vec![
+        open(&root.join("a.txt"), &grammars),
+        open(&root.join("b.txt"), &grammars),
+    ];
+
+    let edits = HashMap::from([
+        (path_to_uri(&root.join("a.txt")), vec![rename(0, 0), rename(1, 0)]),
+        (path_to_uri(&root.join("b.txt")), vec![rename(0, 4)]),
+        (path_to_uri(&root.join("c.txt")), vec![rename(0, 0)]),
+    ]);
+
+    assert_eq!(apply_workspace_edit(&mut buffers, &edits, Backup::None), Ok(3));
+
+    assert_eq!(buffers[0].editor.to_string(), "kitten\nkitten food\n");
+    assert_eq!(buffers[1].editor.to_string(), "the kitten\n");
+    assert!(buffers.iter().all(Buffer::is_modified));
+
+    assert_eq!(std::fs::read_to_string(root.join("a.txt")).unwrap(), "cat\ncat food\n");
+    assert_eq!(std::fs::read_to_string(root.join("b.txt")).unwrap(), "the cat\n");
+    assert_eq!(std::fs::read_to_string(root.join("c.txt")).unwrap(), "kitten\n");
+
+    buffers[0].editor.undo();
+
+    assert_eq!(buffers[0].editor.to_string(), "cat\ncat food\n");
+    assert!(!buffers[0].is_modified());
+}
+
+#[test]
+fn clean_buffers_reload_and_keep_each_views_position() {
+    let root = TempDir::new("buffer-reload");
+    let (grammars, _) = GrammarSet::load();
+
+    std::fs::write(root.join("a.txt"), "one\ntwo cats\nthree\nfour\n").unwrap();
+
+    let mut buffers = vec![open(&root.join("a.txt"), &grammars)];
+    let editor = &mut buffers[0].editor;
+
+    editor.set_cursor(editor.position_to_byte(1, 7));
+
+    let second = editor.add_view();
+
+    editor.set_view(second);
+    editor.set_cursor(editor.position_to_byte(3, 2));
+    editor.set_view(0);
+
+    let mut message = None;
+
+    assert_eq!(reload_changed_buffers(&mut buffers, &mut message), None);
+    assert_eq!(message, None);
+
+    std::fs::write(root.join("a.txt"), "one\ntwo dogs\n").unwrap();
+
+    assert_eq!(reload_changed_buffers(&mut buffers, &mut message), None);
+    assert_eq!(message.as_deref(), Some("reloaded a.txt"));
+
+    let editor = &mut buffers[0].editor;
+
+    assert_eq!(editor.to_string(), "one\ntwo dogs\n");
+    assert_eq!(editor.byte_to_position(editor.get_cursor()), (1, 7));
+    assert!(!buffers[0].is_modified());
+
+    let editor = &mut buffers[0].editor;
+
+    editor.set_view(second);
+
+    assert_eq!(editor.byte_to_position(editor.get_cursor()), (2, 0));
+}
+
+#[test]
+fn modified_buffers_are_not_reloaded() {
+    let root = TempDir::new("buffer-reload-modified");
+    let (grammars, _) = GrammarSet::load();
+
+    for file in ["a.txt", "b.txt"] {
+        std::fs::write(root.join(file), "cat\n").unwrap();
+    }
+
+    let mut buffers = vec![
+        open(&root.join("a.txt"), &grammars),
+        open(&root.join("b.txt"), &grammars),
+    ];
+
+    buffers[1].editor.insert_string(0, "my ", true, true, true);
+
+    for file in ["a.txt", "b.txt"] {
+        std::fs::write(root.join(file), "kitten\n").unwrap();
+    }
+
+    let mut message = None;
+
+    assert_eq!(reload_changed_buffers(&mut buffers, &mut message), Some(1));
+    assert_eq!(message.as_deref(), Some("reloaded a.txt"));
+    assert_eq!(buffers[0].editor.to_string(), "kitten\n");
+    assert_eq!(buffers[1].editor.to_string(), "my cat\n");
+    assert!(buffers[1].is_modified());
+}
+
+#[test]
+fn words_complete_from_every_open_buffer() {
+    let root = TempDir::new("buffer-complete-words");
+    let (grammars, _) = GrammarSet::load();
+
+    std::fs::write(root.join("a.txt"), "the cat\nkit").unwrap();
+    std::fs::write(root.join("b.txt"), "kitten mittens\n").unwrap();
+
+    let mut buffers = [
+        open(&root.join("a.txt"), &grammars),
+        open(&root.join("b.txt"), &grammars),
+    ];
+
+    let end = buffers[0].editor.get_text().len_bytes();
+    buffers[0].editor.set_cursor(end);
+
+    let indexes = buffers.iter().map(|x| &x.words).collect::<Vec<_>>();
+    let menu = CompletionMenu::from_words(&indexes, &buffers[0]).unwrap();
+
+    assert_eq!(menu.get_lines(), ["kitten"]);
+
+    menu.accept(&mut buffers[0]);
+
+    assert_eq!(buffers[0].editor.to_string(), "the cat\nkitten");
+}
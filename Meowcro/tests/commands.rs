@@ -0,0 +1,113 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use meowcro::commands::{find_command, remember_command, Action, CommandPalette, Context, COMMANDS};
+use meowcro::diffview::Side;
+
+const EDITOR: &[Context] = &[Context::Global, Context::Workspace, Context::Editor];
+
+fn names(palette: &CommandPalette) -> Vec<&'static str> {
+    palette
+        .get_matches()
+        .into_iter()
+        .take(3)
+        .map(|label| COMMANDS.iter().find(|x| x.get_label() == label).unwrap().name)
+        .collect()
+}
+
+#[test]
+fn commands_show_their_keybinding() {
+    let label = |name: &str| COMMANDS.iter().find(|x| x.name == name).unwrap().get_key_label();
+
+    assert_eq!(label("Save"), "Ctrl+s");
+    assert_eq!(label("Previous search result"), "Shift+F4");
+    assert_eq!(label("Complete word"), "Ctrl+Space");
+    assert_eq!(label("Next buffer"), "Ctrl+PageDown");
+    assert_eq!(label("Resize pane left"), "Alt+Shift+Left");
+    assert_eq!(label("Previous snippet field"), "Shift+Tab");
+    assert_eq!(label("Command palette"), "Ctrl+Shift+p");
+}
+
+#[test]
+fn every_command_runs_from_its_keybinding() {
+    for x in COMMANDS {
+        assert_eq!(
+            find_command(x.code, x.modifiers, &[x.action.get_context()]),
+            Some(x.action),
+            "{}",
+            x.name
+        );
+    }
+
+    let explorer = [Context::Global, Context::Workspace, Context::Explorer];
+
+    assert_eq!(find_command(KeyCode::Char('d'), KeyModifiers::NONE, EDITOR), None);
+    assert_eq!(
+        find_command(KeyCode::Char('d'), KeyModifiers::NONE, &explorer),
+        Some(Action::DeleteFile)
+    );
+    assert_eq!(find_command(KeyCode::Char('s'), KeyModifiers::CONTROL, &explorer), None);
+    assert_eq!(
+        find_command(KeyCode::BackTab, KeyModifiers::NONE, &[Context::Snippet]),
+        Some(Action::PreviousSnippetField)
+    );
+    assert_eq!(
+        find_command(KeyCode::F(1), KeyModifiers::NONE, &explorer),
+        Some(Action::CommandPalette)
+    );
+    assert_eq!(
+        find_command(KeyCode::Char('.'), KeyModifiers::ALT, EDITOR),
+        Some(Action::NextBuffer)
+    );
+
+    let diff = [Context::Global, Context::Diff];
+
+    assert_eq!(
+        find_command(KeyCode::Char('s'), KeyModifiers::CONTROL, &diff),
+        Some(Action::SaveDiff)
+    );
+    assert_eq!(
+        find_command(KeyCode::Char('<'), KeyModifiers::SHIFT, &diff),
+        Some(Action::CopyChange(Side::Left))
+    );
+    assert_eq!(find_command(KeyCode::Char('f'), KeyModifiers::ALT, &diff), None);
+}
+
+#[test]
+fn recent_commands_come_first() {
+    let mut recent = Vec::new();
+
+    assert_eq!(names(&CommandPalette::new(&recent, EDITOR)), ["Save", "Undo", "Redo"]);
+
+    let find = COMMANDS.iter().position(|x| x.name == "Find file").unwrap();
+    let quit = COMMANDS.iter().position(|x| x.name == "Quit").unwrap();
+
+    remember_command(&mut recent, find);
+    remember_command(&mut recent, quit);
+    remember_command(&mut recent, find);
+
+    assert_eq!(recent, [find, quit]);
+    assert_eq!(names(&CommandPalette::new(&recent, EDITOR)), ["Find file", "Quit", "Save"]);
+
+    let mut palette = CommandPalette::new(&recent, EDITOR);
+
+    for c in "splt".chars() {
+        palette.push(c);
+    }
+
+    assert_eq!(names(&palette)[..2], ["Split side by side", "Split stacked"]);
+
+    palette.move_selection(1);
+
+    assert_eq!(
+        COMMANDS[palette.get_selected_command().unwrap()].name,
+        "Split stacked"
+    );
+    let mut palette = CommandPalette::new(&recent, &[Context::Global, Context::Diff]);
+
+    assert_eq!(palette.get_status(), "8/8");
+
+    for c in "split".chars() {
+        palette.push(c);
+    }
+
+    assert_eq!(palette.get_status(), "0/8");
+}
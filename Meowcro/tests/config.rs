@@ -0,0 +1,106 @@
+mod support;
+
+use meowcro::config::{Config, ConfigFile, Settings};
+use meowcro::save::Backup;
+use support::TempDir;
+
+#[test]
+fn settings_layer_global_project_filetype_and_flags() {
+    let root = TempDir::new("config-layers");
+
+    std::fs::create_dir_all(root.join("project/src")).unwrap();
+
+    std::fs::write(root.join("global.toml"), "tab_width = 8\ntheme = \"dark\"\nbackup = \"tilde\"\n").unwrap();
+    std::fs::write(
+        root.join("project/.meowcro.toml"),
+        "tab_width = 2\n\n[filetype.rust]\ntab_width = 3\n",
+    )
+    .unwrap();
+
+    let defaults = Settings::default();
+
+    assert_eq!((defaults.tab_width, defaults.theme.as_str()), (4, "default"));
+    assert_eq!(defaults.backup, Backup::None);
+
+    let overrides = ConfigFile {
+        relative_line_numbers: Some(true),
+        ..ConfigFile::default()
+    };
+
+    let file = root.join("project/src/main.rs");
+    let config = Config::load_with_global(Some(root.join("global.toml")), &file, overrides).unwrap();
+
+    let settings = config.settings_for(&file);
+
+    assert_eq!(settings.tab_width, 3);
+    assert_eq!(settings.theme, "dark");
+    assert_eq!(settings.backup, Backup::Tilde);
+    assert!(settings.relative_line_numbers && !settings.disable_mouse_interaction);
+
+    assert_eq!(config.settings_for(&root.join("project/notes.txt")).tab_width, 2);
+    assert_eq!(config.project_root(&file), Some(root.join("project")));
+
+    let missing = Config::load_with_global(None, &root.join("file.txt"), ConfigFile::default()).unwrap();
+
+    assert!(missing.settings_for(&root.join("file.txt")) == Settings::default());
+}
+
+#[test]
+fn invalid_keys_and_values_are_reported_with_their_position() {
+    let root = TempDir::new("config-errors");
+    let path = root.join("config.toml");
+
+    let error = |content: &str| {
+        std::fs::write(&path, content).unwrap();
+
+        match Config::load_with_global(Some(path.clone()), &root.join("file.txt"), ConfigFile::default()) {
+            Ok(_) => panic!("expected {content:?} to be rejected"),
+            Err(e) => e.to_string(),
+        }
+    };
+
+    let prefix = format!("{}: ", path.to_string_lossy());
+
+    let unknown = error("theme = \"dark\"\ntab_widht = 2\n");
+
+    assert!(unknown.starts_with(&format!("{prefix}2:1: unknown field `tab_widht`")), "{unknown}");
+
+    let wrong_type = error("tab_width = \"four\"\n");
+
+    assert!(wrong_type.starts_with(&format!("{prefix}1:13: invalid type: string \"four\"")), "{wrong_type}");
+
+    let wrong_variant = error("[status_line]\nleft = [\"mode\", \"clock\"]\n");
+
+    assert!(wrong_variant.starts_with(&format!("{prefix}2:17: unknown variant `clock`")), "{wrong_variant}");
+}
+
+#[test]
+fn each_buffer_gets_the_project_config_above_it() {
+    let root = TempDir::new("config-projects");
+
+    for project in ["one", "two"] {
+        std::fs::create_dir_all(root.join(project)).unwrap();
+    }
+
+    std::fs::write(root.join("one/.meowcro.toml"), "tab_width = 2\n").unwrap();
+
+    let first = root.join("one/a.txt");
+    let second = root.join("two/b.txt");
+
+    let mut config = Config::load_with_global(None, &first, ConfigFile::default()).unwrap();
+
+    assert_eq!(config.settings_for(&first).tab_width, 2);
+    assert_eq!(config.settings_for(&second).tab_width, 4);
+    assert!(config.reload_if_changed().is_none());
+
+    std::fs::write(root.join("two/.meowcro.toml"), "tab_width = 8\n").unwrap();
+
+    assert!(matches!(config.reload_if_changed(), Some(Ok(()))));
+    assert_eq!(config.settings_for(&second).tab_width, 8);
+    assert_eq!(config.project_root(&second), Some(root.join("two")));
+
+    std::fs::write(root.join("two/.meowcro.toml"), "tab_width = \"wide\"\n").unwrap();
+
+    assert!(matches!(config.reload_if_changed(), Some(Err(_))));
+    assert_eq!(config.settings_for(&first).tab_width, 2);
+}
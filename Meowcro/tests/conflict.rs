@@ -0,0 +1,65 @@
+use meowcro::conflict::{find_conflicts, get_next_conflict, get_previous_conflict, ConflictPart, Resolution};
+use ropey::Rope;
+
+const TEXT: &str = "start
+<<<<<<< HEAD
+ours
+||||||| base
+base
+=======
+theirs
+>>>>>>> branch
+middle
+<<<<<<< HEAD
+a
+=======
+b
+>>>>>>> other
+<<<<<<<< not a marker
+";
+
+#[test]
+fn conflicts_are_found_with_their_sections() {
+    let text = Rope::from_str(TEXT);
+    let conflicts = find_conflicts(text.slice(..));
+
+    assert_eq!(conflicts.len(), 2);
+
+    assert_eq!(conflicts[0].lines, 1..8);
+    assert_eq!(conflicts[0].ours, 2..3);
+    assert_eq!(conflicts[0].base, Some(4..5));
+    assert_eq!(conflicts[0].theirs, 6..7);
+
+    assert_eq!(conflicts[1].lines, 9..14);
+    assert_eq!(conflicts[1].base, None);
+
+    assert_eq!(
+        conflicts[1].get_regions(),
+        vec![
+            (9..10, ConflictPart::Marker),
+            (10..11, ConflictPart::Ours),
+            (11..12, ConflictPart::Marker),
+            (12..13, ConflictPart::Theirs),
+            (13..14, ConflictPart::Marker),
+        ]
+    );
+
+    assert_eq!(get_next_conflict(&conflicts, 0), Some(0));
+    assert_eq!(get_next_conflict(&conflicts, 9), Some(0));
+    assert_eq!(get_previous_conflict(&conflicts, 5), Some(0));
+    assert_eq!(get_previous_conflict(&conflicts, 0), Some(1));
+}
+
+#[test]
+fn conflicts_resolve_to_the_chosen_sections() {
+    let text = Rope::from_str(TEXT);
+    let conflicts = find_conflicts(text.slice(..));
+
+    let resolve = |index: usize, resolution| conflicts[index].resolve(text.slice(..), resolution);
+
+    assert_eq!(resolve(0, Resolution::Ours), Ok("ours\n".to_string()));
+    assert_eq!(resolve(0, Resolution::Theirs), Ok("theirs\n".to_string()));
+    assert_eq!(resolve(0, Resolution::Both), Ok("ours\ntheirs\n".to_string()));
+    assert_eq!(resolve(0, Resolution::Base), Ok("base\n".to_string()));
+    assert!(resolve(1, Resolution::Base).is_err());
+}
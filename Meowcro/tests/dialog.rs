@@ -0,0 +1,33 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use meowcro::dialog::{Dialog, DialogResult};
+
+const BUTTONS: &[&str] = &["Save", "Don't save", "Cancel"];
+
+#[test]
+fn arrows_cycle_and_enter_presses_the_selected_button() {
+    let mut dialog = Dialog::new((), "Save changes?", BUTTONS);
+
+    let mut press = |code| dialog.handle_key(code, KeyModifiers::NONE);
+
+    assert_eq!(press(KeyCode::Left), DialogResult::Pending);
+    assert_eq!(press(KeyCode::Enter), DialogResult::Pressed(2));
+    assert_eq!(press(KeyCode::Tab), DialogResult::Pending);
+    assert_eq!(press(KeyCode::Right), DialogResult::Pending);
+    assert_eq!(press(KeyCode::Enter), DialogResult::Pressed(1));
+    assert_eq!(press(KeyCode::Esc), DialogResult::Cancelled);
+}
+
+#[test]
+fn first_letters_press_buttons() {
+    let mut dialog = Dialog::new(7, "Save changes?", BUTTONS);
+
+    assert_eq!(dialog.handle_key(KeyCode::Char('D'), KeyModifiers::SHIFT), DialogResult::Pressed(1));
+    assert_eq!(dialog.get_selected(), 1);
+    assert_eq!(dialog.handle_key(KeyCode::Char('x'), KeyModifiers::NONE), DialogResult::Pending);
+    assert_eq!(dialog.handle_key(KeyCode::Char('s'), KeyModifiers::CONTROL), DialogResult::Pending);
+
+    dialog.select(5);
+
+    assert_eq!(dialog.get_selected(), 1);
+    assert_eq!(dialog.into_action(), 7);
+}
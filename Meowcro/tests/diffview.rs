@@ -0,0 +1,53 @@
+use meowcro::diffview::{DiffView, RowKind, Side};
+
+#[test]
+fn rows_are_aligned_with_filler_and_inline_changes() {
+    let view = DiffView::new("a", "one\ntwo\nthree\nfour\n", "b", "one\ntwo!\nfour\nfive\n");
+
+    let rows = view
+        .get_rows()
+        .iter()
+        .map(|x| (x.left, x.right, x.kind))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        rows,
+        vec![
+            (Some(0), Some(0), RowKind::Equal),
+            (Some(1), Some(1), RowKind::Changed),
+            (Some(2), None, RowKind::Removed),
+            (Some(3), Some(2), RowKind::Equal),
+            (None, Some(3), RowKind::Added),
+        ]
+    );
+
+    let (left, right) = view.get_inline_changes(&view.get_rows()[1]);
+
+    assert_eq!((left.len(), right.len()), (1, 1));
+    assert_eq!((left[0].clone(), right[0].clone()), (3..3, 3..4));
+    assert_eq!(view.get_status(), "change 1 of 2");
+}
+
+#[test]
+fn copying_changes_between_sides() {
+    let mut view = DiffView::new("a", "one\ntwo\nthree\n", "b", "one\n2\nthree\nfour\n");
+
+    assert_eq!(view.get_copy(Side::Right), Some((1..2, "two\n".to_string())));
+
+    view.select_next();
+
+    assert_eq!(view.get_copy(Side::Left), Some((3..3, "four\n".to_string())));
+
+    view.select_next();
+
+    assert_eq!(view.get_selected(), Some(0));
+
+    view.set_text("one\n2\nthree\n", "one\n2\nthree\nfour\n");
+
+    assert_eq!(view.get_status(), "change 1 of 1");
+
+    view.set_text("same\n", "same\n");
+
+    assert_eq!(view.get_selected(), None);
+    assert_eq!(view.get_status(), "no differences");
+}
@@ -0,0 +1,138 @@
+mod support;
+
+use meowcro::explorer::Explorer;
+use std::path::Path;
+use support::TempDir;
+
+fn project(name: &str) -> TempDir {
+    let root = TempDir::new(&format!("explorer-{name}"));
+
+    for directory in ["src/nested", "target/debug", ".git"] {
+        std::fs::create_dir_all(root.join(directory)).unwrap();
+    }
+
+    for (file, content) in [
+        (".gitignore", "/target\n*.log\n!keep.log\n"),
+        ("src/.gitignore", "generated.rs\n"),
+        ("src/main.rs", ""),
+        ("src/generated.rs", ""),
+        ("src/nested/cat.rs", ""),
+        ("debug.log", ""),
+        ("keep.log", ""),
+        ("README.md", ""),
+    ] {
+        std::fs::write(root.join(file), content).unwrap();
+    }
+
+    root
+}
+
+fn labels(explorer: &Explorer) -> Vec<String> {
+    explorer.get_entries().iter().map(|x| x.get_label()).collect()
+}
+
+fn select(explorer: &mut Explorer, path: &Path) {
+    explorer.select_path(path);
+
+    assert_eq!(explorer.get_selected_entry().unwrap().path, path);
+}
+
+#[test]
+fn tree_respects_gitignore_and_expands_folders() {
+    let root = project("tree");
+
+    let mut explorer = Explorer::new(root.to_path_buf());
+
+    assert_eq!(
+        labels(&explorer),
+        ["+ src/", "  .gitignore", "  README.md", "  keep.log"]
+    );
+
+    assert_eq!(explorer.activate(), None);
+
+    explorer.move_selection(1);
+    explorer.expand();
+
+    assert_eq!(
+        labels(&explorer),
+        [
+            "- src/",
+            "  - nested/",
+            "      cat.rs",
+            "    .gitignore",
+            "    main.rs",
+            "  .gitignore",
+            "  README.md",
+            "  keep.log",
+        ]
+    );
+
+    explorer.move_selection(1);
+
+    assert_eq!(explorer.activate(), Some(root.join("src/nested/cat.rs")));
+
+    explorer.collapse();
+    explorer.collapse();
+
+    assert_eq!(labels(&explorer)[1], "  + nested/");
+
+    explorer.collapse();
+
+    assert_eq!(explorer.get_selected_entry().unwrap().path, root.join("src"));
+
+    explorer.collapse();
+
+    assert_eq!(labels(&explorer)[0], "+ src/");
+}
+
+#[test]
+fn file_operations_update_the_tree() {
+    let root = project("operations");
+
+    let mut explorer = Explorer::new(root.to_path_buf());
+
+    select(&mut explorer, &root.join("src"));
+
+    assert_eq!(explorer.create("lib.rs"), Ok(root.join("src/lib.rs")));
+    assert!(root.join("src/lib.rs").is_file());
+    assert_eq!(explorer.get_selected_entry().unwrap().path, root.join("src/lib.rs"));
+
+    assert_eq!(
+        explorer.create("main.rs"),
+        Err("src/main.rs already exists".to_string())
+    );
+
+    assert_eq!(
+        explorer.rename("kitten.rs", false),
+        Ok((root.join("src/lib.rs"), root.join("src/kitten.rs")))
+    );
+
+    assert_eq!(
+        explorer.move_to("src/nested", false),
+        Ok((root.join("src/kitten.rs"), root.join("src/nested/kitten.rs")))
+    );
+    assert!(explorer.rename("cat.rs", false).is_err());
+    assert_eq!(
+        explorer.get_rename_target("cat.rs"),
+        Ok(root.join("src/nested/cat.rs"))
+    );
+    assert_eq!(
+        explorer.rename("cat.rs", true),
+        Ok((root.join("src/nested/kitten.rs"), root.join("src/nested/cat.rs")))
+    );
+    assert!(!root.join("src/nested/kitten.rs").exists());
+
+    select(&mut explorer, &root.join("src"));
+
+    assert_eq!(
+        explorer.move_to("src/nested/src", false),
+        Err("can't move src into itself".to_string())
+    );
+
+    assert_eq!(explorer.create("assets/"), Ok(root.join("src/assets")));
+    assert!(root.join("src/assets").is_dir());
+
+    assert_eq!(explorer.delete(), Ok(root.join("src/assets")));
+    assert!(!root.join("src/assets").exists());
+    assert!(!labels(&explorer).iter().any(|x| x.contains("assets")));
+}
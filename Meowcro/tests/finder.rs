@@ -0,0 +1,61 @@
+mod support;
+
+use meowcro::finder::FileFinder;
+use meowcro::fuzzy::path_score;
+use std::time::{Duration, Instant};
+use support::TempDir;
+
+#[test]
+fn path_matches_prefer_basenames_and_segments() {
+    assert_eq!(path_score("xyz", "src/main.rs"), None);
+
+    assert!(path_score("main", "src/main.rs") > path_score("main", "domain/lib.rs"));
+    assert!(path_score("srmain", "src/main.rs") > path_score("srmain", "scripts/rmain.rs"));
+    assert!(path_score("cat", "tests/cat.rs") > path_score("cat", "cats/tests.rs"));
+}
+
+#[test]
+fn finder_skips_ignored_and_binary_files() {
+    let root = TempDir::new("finder");
+
+    std::fs::create_dir_all(root.join("src/cats")).unwrap();
+    std::fs::create_dir_all(root.join("target")).unwrap();
+
+    std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+    std::fs::write(root.join("src/cats/main.rs"), "fn main() {\n\tmeow();\n}\n").unwrap();
+    std::fs::write(root.join("src/domain.rs"), "").unwrap();
+    std::fs::write(root.join("target/main.rs"), "").unwrap();
+    std::fs::write(root.join("cat.png"), [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+
+    let mut finder = FileFinder::new(root.to_path_buf());
+
+    let start = Instant::now();
+
+    while finder.is_loading() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        finder.poll();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    assert_eq!(finder.get_matches().len(), 3);
+    assert_eq!(finder.get_status(), "3/3");
+
+    for c in "main".chars() {
+        finder.push(c);
+    }
+
+    assert_eq!(finder.get_matches(), ["src/cats/main.rs", "src/domain.rs"]);
+    assert_eq!(finder.get_selected_path(), Some(root.join("src/cats/main.rs")));
+    assert_eq!(finder.get_preview(), ["fn main() {", "    meow();", "}"]);
+
+    finder.move_selection(-1);
+
+    assert_eq!(finder.get_selected_path(), Some(root.join("src/domain.rs")));
+
+    finder.pop();
+    finder.push('x');
+
+    assert!(finder.get_matches().is_empty());
+    assert_eq!(finder.get_selected_path(), None);
+}
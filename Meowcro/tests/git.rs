@@ -0,0 +1,50 @@
+use meowcro::diff::{diff_lines, Hunk};
+use meowcro::git::{ChangeKind, GitChanges};
+use ropey::Rope;
+
+#[test]
+fn diff_finds_added_changed_and_removed_lines() {
+    let old = ["a", "b", "c", "d", "e"];
+    let new = ["a", "x", "c", "e", "f"];
+
+    assert_eq!(
+        diff_lines(&old, &new),
+        vec![
+            Hunk { old: 1..2, new: 1..2 },
+            Hunk { old: 3..4, new: 3..3 },
+            Hunk { old: 5..5, new: 4..5 },
+        ]
+    );
+
+    assert!(diff_lines(&old, &old).is_empty());
+    assert_eq!(diff_lines(&[], &["a"]), vec![Hunk { old: 0..0, new: 0..1 }]);
+}
+
+#[test]
+fn changes_give_markers_navigation_and_previews() {
+    let mut changes = GitChanges::new("one\ntwo\nthree\nfour\n");
+    let text = Rope::from_str("one\n2\nthree\nnew\n");
+
+    changes.update(text.slice(..));
+
+    assert_eq!(
+        changes.get_markers(),
+        vec![(1, ChangeKind::Modified), (3, ChangeKind::Modified)]
+    );
+
+    assert_eq!(changes.get_next_hunk(1), Some(3));
+    assert_eq!(changes.get_next_hunk(3), Some(1));
+    assert_eq!(changes.get_previous_hunk(1), Some(3));
+
+    let hunk = changes.get_hunk_at(1).unwrap().clone();
+
+    assert_eq!(changes.get_preview(&hunk, text.slice(..)), vec!["- two", "+ 2"]);
+    assert_eq!(changes.get_base_text(&hunk), "two\n");
+    assert!(changes.get_hunk_at(2).is_none());
+
+    let mut changes = GitChanges::new("one\ntwo\n");
+
+    changes.update(Rope::from_str("one\n").slice(..));
+
+    assert_eq!(changes.get_markers(), vec![(0, ChangeKind::Deleted)]);
+}
@@ -0,0 +1,105 @@
+mod support;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use meowcro::input::{add_to_history, complete_path, InputLine, InputResult};
+use support::TempDir;
+
+fn press(line: &mut InputLine, keys: &[KeyCode]) {
+    for key in keys {
+        line.handle_key(*key, KeyModifiers::NONE);
+    }
+}
+
+#[test]
+fn editing_moves_the_cursor_and_confirms() {
+    let mut line = InputLine::new("cat");
+
+    press(&mut line, &[KeyCode::Left, KeyCode::Char('r'), KeyCode::Home, KeyCode::Delete]);
+
+    assert_eq!((line.get_text(), line.get_cursor()), ("art", 0));
+
+    line.handle_key(KeyCode::End, KeyModifiers::NONE);
+    line.handle_key(KeyCode::Char('s'), KeyModifiers::NONE);
+    line.handle_key(KeyCode::Backspace, KeyModifiers::CONTROL);
+
+    assert_eq!(line.get_text(), "");
+
+    press(&mut line, &[KeyCode::Char('ø'), KeyCode::Char('y'), KeyCode::Left, KeyCode::Backspace]);
+
+    assert_eq!((line.get_text(), line.get_cursor()), ("y", 0));
+
+    assert_eq!(
+        line.handle_key(KeyCode::Enter, KeyModifiers::NONE),
+        InputResult::Confirmed("y".to_string())
+    );
+    assert_eq!(line.handle_key(KeyCode::Esc, KeyModifiers::NONE), InputResult::Cancelled);
+}
+
+#[test]
+fn history_recalls_entries_and_restores_the_draft() {
+    let mut history = Vec::new();
+
+    for entry in ["one", "two", "one", ""] {
+        add_to_history(&mut history, entry);
+    }
+
+    assert_eq!(history, ["two", "one"]);
+
+    let mut line = InputLine::new("draft").with_history(&history);
+
+    press(&mut line, &[KeyCode::Up]);
+    assert_eq!(line.get_text(), "one");
+
+    press(&mut line, &[KeyCode::Up, KeyCode::Up]);
+    assert_eq!(line.get_text(), "two");
+
+    press(&mut line, &[KeyCode::Down, KeyCode::Down]);
+    assert_eq!(line.get_text(), "draft");
+}
+
+#[test]
+fn validation_blocks_confirming() {
+    let mut line = InputLine::new("").with_validation(|x| match x.parse::<usize>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err("not a number".to_string()),
+    });
+
+    assert_eq!(line.get_error(), Some("not a number"));
+    assert_eq!(line.handle_key(KeyCode::Enter, KeyModifiers::NONE), InputResult::Ignored);
+
+    press(&mut line, &[KeyCode::Char('4'), KeyCode::Char('2')]);
+
+    assert_eq!(line.get_error(), None);
+    assert_eq!(
+        line.handle_key(KeyCode::Enter, KeyModifiers::NONE),
+        InputResult::Confirmed("42".to_string())
+    );
+}
+
+#[test]
+fn tab_completes_paths() {
+    let root = TempDir::new("input");
+
+    std::fs::create_dir_all(root.join("cats")).unwrap();
+    std::fs::write(root.join("catalog.txt"), "").unwrap();
+    std::fs::write(root.join("dog.txt"), "").unwrap();
+
+    let root_text = format!("{}/", root.to_string_lossy());
+
+    assert_eq!(
+        complete_path(&format!("{root_text}ca")),
+        [format!("{root_text}catalog.txt"), format!("{root_text}cats/")]
+    );
+
+    let mut line = InputLine::new(&format!("{root_text}c")).with_completion(complete_path);
+
+    press(&mut line, &[KeyCode::Tab]);
+
+    assert_eq!(line.get_text(), format!("{root_text}cat"));
+    assert_eq!(line.get_hint(), Some("catalog.txt cats"));
+
+    press(&mut line, &[KeyCode::Char('s'), KeyCode::Tab]);
+
+    assert_eq!(line.get_text(), format!("{root_text}cats/"));
+    assert_eq!(line.get_hint(), None);
+}
@@ -0,0 +1,259 @@
+mod support;
+
+use meowcro::editor::TextEditor;
+use meowcro::lsp::{self, ContentChange, LanguageServer, LspEvent, Position, Severity};
+use std::time::{Duration, Instant};
+use support::PlainLayout;
+
+const URI: &str = "file:///tmp/cats.txt";
+
+fn start() -> LanguageServer {
+    start_with(&[])
+}
+
+fn start_with(args: &[&str]) -> LanguageServer {
+    let args = args.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+    LanguageServer::start(&support::fake_lsp_path(), &args, &std::env::temp_dir()).unwrap()
+}
+
+fn wait_for<T>(server: &mut LanguageServer, mut found: impl FnMut(LspEvent) -> Option<T>) -> T {
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    while Instant::now() < deadline {
+        for event in server.poll() {
+            if let Some(x) = found(event) {
+                return x;
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    panic!("the language server didn't answer in time");
+}
+
+fn server_text(server: &mut LanguageServer) -> String {
+    wait_for(server, |event| match event {
+        LspEvent::Log(x) => Some(x),
+        _ => None,
+    })
+}
+
+fn sync(server: &mut LanguageServer, editor: &mut TextEditor<PlainLayout>, version: i64) {
+    let changes = editor
+        .take_changes()
+        .into_iter()
+        .map(|x| ContentChange {
+            start: Position::new(x.start_position.0, x.start_position.1),
+            end: Position::new(x.old_end_position.0, x.old_end_position.1),
+            text: x.text,
+        })
+        .collect::<Vec<_>>();
+
+    server
+        .did_change(URI, version, &changes, editor.get_text())
+        .unwrap();
+}
+
+#[test]
+fn incremental_changes_keep_the_server_in_sync() {
+    let mut server = start();
+
+    let text = "héllo 😺 cat\nsecond line\r\nthird 𝄞 line\n";
+    let mut editor = TextEditor::new(text, PlainLayout, 4, false);
+
+    server.did_open(URI, "text", 1, text).unwrap();
+
+    assert_eq!(server_text(&mut server), text);
+
+    let after_cat = text.find(" cat").unwrap();
+    editor.insert_string(after_cat, " and 🐈 kitten", true, true, true);
+
+    let second = text.find("second").unwrap() + " and 🐈 kitten".len();
+    editor.remove_range(second, second + "second ".len(), true, true, true);
+
+    let clef = editor.to_string().find('𝄞').unwrap();
+    editor.remove_range(clef, clef + '𝄞'.len_utf8(), true, true, true);
+    editor.insert_string(clef, "♪♪", true, true, true);
+
+    editor.undo();
+    editor.insert_string(editor.get_text().len_bytes(), "tail", true, true, true);
+
+    sync(&mut server, &mut editor, 2);
+
+    assert_eq!(server_text(&mut server), editor.to_string());
+
+    server.shutdown();
+    assert!(!server.is_running());
+}
+
+#[test]
+fn changes_follow_the_servers_sync_kind() {
+    let text = "kitten cat\n";
+
+    let mut server = start_with(&["--sync", "1"]);
+    let mut editor = TextEditor::new(text, PlainLayout, 4, false);
+
+    server.did_open(URI, "text", 1, text).unwrap();
+    assert_eq!(server_text(&mut server), text);
+
+    editor.insert_string(0, "tabby ", true, true, true);
+    sync(&mut server, &mut editor, 2);
+
+    assert_eq!(server_text(&mut server), "tabby kitten cat\n");
+
+    let mut server = start_with(&["--sync", "0"]);
+    let mut editor = TextEditor::new(text, PlainLayout, 4, false);
+
+    server.did_open(URI, "text", 1, text).unwrap();
+    assert_eq!(server_text(&mut server), text);
+
+    editor.insert_string(0, "tabby ", true, true, true);
+    sync(&mut server, &mut editor, 2);
+    server.completion(URI, Position::new(0, 0)).unwrap();
+
+    let labels = wait_for(&mut server, |event| match event {
+        LspEvent::Completion(_, x) => Some(x.into_iter().map(|x| x.label).collect::<Vec<_>>()),
+        _ => None,
+    });
+
+    assert_eq!(labels, ["cat", "kitten"]);
+}
+
+#[test]
+fn positions_are_counted_in_utf16_code_units() {
+    let editor = TextEditor::new("a😺b\nñx\n", PlainLayout, 4, false);
+
+    assert_eq!(editor.byte_to_position(5), (0, 3));
+    assert_eq!(editor.position_to_byte(0, 3), 5);
+    assert_eq!(editor.byte_to_position(9), (1, 1));
+    assert_eq!(editor.position_to_byte(1, 40), 10);
+    assert_eq!(editor.position_to_byte(7, 0), editor.get_text().len_bytes());
+}
+
+#[test]
+fn diagnostics_are_stored_per_document() {
+    let mut server = start();
+
+    server
+        .did_open(URI, "text", 1, "fine\n  bad line\nmeh 😺 bad\n")
+        .unwrap();
+
+    let uri = wait_for(&mut server, |event| match event {
+        LspEvent::Diagnostics(x) => Some(x),
+        _ => None,
+    });
+
+    assert_eq!(uri, URI);
+
+    let diagnostics = server.diagnostics(URI);
+
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].start, Position::new(1, 2));
+    assert_eq!(diagnostics[0].end, Position::new(1, 5));
+    assert_eq!(diagnostics[0].message, "`bad` found");
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+    assert_eq!(diagnostics[2].start, Position::new(2, 7));
+
+    server.did_close(URI).unwrap();
+
+    assert!(server.diagnostics(URI).is_empty());
+}
+
+#[test]
+fn answers_completion_hover_definition_and_rename() {
+    let mut server = start();
+
+    server
+        .did_open(URI, "text", 1, "kitten cat\nkit kitten\n")
+        .unwrap();
+
+    server.completion(URI, Position::new(1, 3)).unwrap();
+
+    let labels = wait_for(&mut server, |event| match event {
+        LspEvent::Completion(uri, x) if uri == URI => {
+            Some(x.into_iter().map(|x| x.label).collect::<Vec<_>>())
+        }
+        _ => None,
+    });
+
+    assert_eq!(labels, ["cat", "kit", "kitten"]);
+
+    server.hover(URI, Position::new(0, 8)).unwrap();
+
+    let hover = wait_for(&mut server, |event| match event {
+        LspEvent::Hover(uri, x) if uri == URI => Some(x),
+        _ => None,
+    });
+
+    assert_eq!(hover, "word `cat`");
+
+    let definition = |server: &mut LanguageServer| {
+        server.definition(URI, Position::new(1, 6)).unwrap();
+
+        wait_for(server, |event| match event {
+            LspEvent::Definition(uri, target, position) if uri == URI => Some((target, position)),
+            _ => None,
+        })
+    };
+
+    assert_eq!(definition(&mut server), (URI.to_string(), Position::new(0, 0)));
+
+    server
+        .did_open("file:///tmp/breeds.txt", "text", 1, "tabby kitten\n")
+        .unwrap();
+
+    assert_eq!(
+        definition(&mut server),
+        ("file:///tmp/breeds.txt".to_string(), Position::new(0, 6))
+    );
+
+    server.rename(URI, Position::new(0, 2), "puppy").unwrap();
+
+    let edits = wait_for(&mut server, |event| match event {
+        LspEvent::Rename(x) => Some(x),
+        _ => None,
+    });
+
+    let edits = &edits[URI];
+
+    assert_eq!(edits.len(), 2);
+    assert_eq!(edits[1].start, Position::new(1, 4));
+    assert_eq!(edits[1].text, "puppy");
+}
+
+#[test]
+fn reports_a_missing_server() {
+    let error = LanguageServer::start("meowcro-no-such-server", &[], &std::env::temp_dir())
+        .err()
+        .unwrap();
+
+    assert!(error.starts_with("failed to start language server `meowcro-no-such-server`"));
+}
+
+#[test]
+fn gives_up_on_a_server_that_fails_to_initialize() {
+    let mut server = start_with(&["--fail-initialize"]);
+
+    server.did_open(URI, "text", 1, "kitten\n").unwrap();
+
+    let message = wait_for(&mut server, |event| match event {
+        LspEvent::Message(x) => Some(x),
+        _ => None,
+    });
+
+    assert!(message.ends_with("failed to initialize: no cats allowed"), "{message}");
+    assert!(!server.is_running());
+    assert!(server.completion(URI, Position::new(0, 0)).is_err());
+}
+
+#[test]
+fn uris_round_trip_paths() {
+    let path = std::path::Path::new("/tmp/cat food/ñam.meow");
+    let uri = lsp::path_to_uri(path);
+
+    assert_eq!(uri, "file:///tmp/cat%20food/%C3%B1am.meow");
+    assert_eq!(lsp::uri_to_path(&uri).unwrap(), path);
+}
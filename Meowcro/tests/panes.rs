@@ -0,0 +1,96 @@
+mod support;
+
+use meowcro::editor::TextEditor;
+use meowcro::panes::{Orientation, Panes, Rect};
+use meowcro::ui::Align;
+use support::PlainLayout;
+
+fn rect(x: u32, y: u32, width: u32, height: u32) -> Rect {
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+fn rects(panes: &Panes) -> Vec<(usize, Rect)> {
+    panes
+        .layout(rect(0, 0, 81, 20))
+        .into_iter()
+        .map(|(pane, rect)| (pane.id, rect))
+        .collect()
+}
+
+#[test]
+fn splits_focus_resize_and_close() {
+    let mut panes = Panes::new(0, 0);
+
+    let right = panes.split(Orientation::SideBySide, 1);
+    let bottom = panes.split(Orientation::Stacked, 2);
+
+    assert_eq!(
+        rects(&panes),
+        vec![
+            (0, rect(0, 0, 40, 20)),
+            (right.id, rect(41, 0, 40, 10)),
+            (bottom.id, rect(41, 10, 40, 10)),
+        ]
+    );
+
+    assert_eq!(panes.get_focused(), bottom);
+    assert!(panes.focus_towards(Align::Top, rect(0, 0, 81, 20)));
+    assert_eq!(panes.get_focused(), right);
+    assert!(!panes.focus_towards(Align::Right, rect(0, 0, 81, 20)));
+    assert!(panes.focus_towards(Align::Left, rect(0, 0, 81, 20)));
+    assert_eq!(panes.get_focused().id, 0);
+
+    assert!(panes.resize(Align::Right));
+    assert!(!panes.resize(Align::Bottom));
+    assert_eq!(rects(&panes)[0].1, rect(0, 0, 44, 20));
+
+    panes.focus(right.id);
+
+    assert_eq!(panes.close(), Some(right));
+    assert_eq!(
+        rects(&panes),
+        vec![(0, rect(0, 0, 44, 20)), (bottom.id, rect(45, 0, 36, 20))]
+    );
+
+    assert_eq!(panes.close().map(|x| x.id), Some(0));
+    assert_eq!(panes.close(), None);
+    assert_eq!(rects(&panes), vec![(bottom.id, rect(0, 0, 81, 20))]);
+}
+
+#[test]
+fn views_keep_their_own_cursor_through_edits() {
+    let mut editor = TextEditor::new("one two three", PlainLayout, 4, false);
+
+    editor.set_cursor(8);
+
+    let first = editor.get_view();
+    let second = editor.add_view();
+
+    editor.set_view(second);
+    editor.set_cursor(0);
+    editor.insert_string_at_cursor("zero ");
+
+    assert_eq!(editor.get_cursor(), 5);
+
+    editor.set_view(first);
+
+    assert_eq!(editor.get_cursor(), 13);
+
+    editor.remove_range(0, 9, true, true, false);
+
+    assert_eq!(editor.get_cursor(), 4);
+
+    editor.set_view(second);
+
+    assert_eq!(editor.get_cursor(), 0);
+
+    editor.remove_view(second);
+
+    assert_eq!(editor.get_view(), first);
+    assert_eq!(editor.get_cursor(), 4);
+}
@@ -0,0 +1,102 @@
+mod support;
+
+use meowcro::replace::{write_files, ProjectReplace, RowKind};
+use meowcro::search::SearchQuery;
+use std::path::Path;
+use support::TempDir;
+
+fn project(name: &str) -> TempDir {
+    let root = TempDir::new(&format!("replace-{name}"));
+
+    std::fs::create_dir_all(root.join("src")).unwrap();
+
+    for (file, content) in [
+        (".gitignore", "*.log\n"),
+        ("src/main.rs", "let cat = 1;\nlet dog = 2;\r\nprint(cat, cat);\n"),
+        ("src/lib.rs", "pub fn cat() {}\n"),
+        ("notes.md", "no pets here"),
+        ("debug.log", "cat\n"),
+    ] {
+        std::fs::write(root.join(file), content).unwrap();
+    }
+
+    root
+}
+
+fn read(root: &Path, file: &str) -> String {
+    std::fs::read_to_string(root.join(file)).unwrap()
+}
+
+fn has_temporaries(folder: &Path) -> bool {
+    std::fs::read_dir(folder)
+        .unwrap()
+        .any(|x| x.unwrap().file_name().to_string_lossy().ends_with("-tmp"))
+}
+
+#[test]
+fn preview_lists_changed_lines_per_file() {
+    let root = project("preview");
+
+    let query = SearchQuery::parse("-r -g src/** (c)at").unwrap();
+    let changes = ProjectReplace::new(root.to_path_buf(), &query, "${1}ow").unwrap();
+
+    let rows = changes.get_rows();
+
+    assert_eq!(changes.get_paths().len(), 2);
+    assert!(rows.contains(&("[x] src/main.rs".to_string(), RowKind::File)));
+    assert!(rows.contains(&("  [x]     3 - print(cat, cat);".to_string(), RowKind::Removed)));
+    assert!(rows.contains(&(format!("{:>12} + print(cow, cow);", ""), RowKind::Added)));
+    assert!(!rows.iter().any(|(x, _)| x.contains("dog") || x.contains("notes")));
+
+    let query = SearchQuery::parse("$1").unwrap();
+
+    assert!(ProjectReplace::new(root.to_path_buf(), &query, "x").unwrap().is_empty());
+}
+
+#[test]
+fn apply_skips_excluded_lines_and_undo_restores() {
+    let root = project("apply");
+
+    let query = SearchQuery::parse("-g src/main.rs cat").unwrap();
+    let mut changes = ProjectReplace::new(root.to_path_buf(), &query, "$kitten").unwrap();
+
+    changes.move_selection(1);
+    changes.toggle();
+
+    assert!(changes.get_title().starts_with("replace 1 of 2 lines in 1 files"));
+
+    let applied = changes.apply().unwrap();
+
+    assert_eq!(read(&root, "src/main.rs"), "let cat = 1;\nlet dog = 2;\r\nprint($kitten, $kitten);\n");
+    assert_eq!(applied.get_summary(), "replaced 1 lines in 1 files: src/main.rs");
+    assert!(changes.apply().is_err());
+
+    applied.undo().unwrap();
+
+    assert_eq!(read(&root, "src/main.rs"), "let cat = 1;\nlet dog = 2;\r\nprint(cat, cat);\n");
+
+    let applied = changes.apply().unwrap();
+
+    std::fs::write(root.join("src/main.rs"), "edited").unwrap();
+
+    assert!(applied.undo().is_err());
+    assert_eq!(read(&root, "src/main.rs"), "edited");
+    assert!(!has_temporaries(&root.join("src")));
+}
+
+#[test]
+fn failed_writes_leave_no_temporaries_behind() {
+    let root = project("write");
+
+    std::fs::create_dir_all(root.join("src/folder")).unwrap();
+    std::fs::write(root.join("src/folder/file"), "").unwrap();
+
+    let main = root.join("src/main.rs");
+    let folder = root.join("src/folder");
+    let lib = root.join("src/lib.rs");
+
+    assert!(write_files(&[(&main, "one"), (&folder, "two"), (&lib, "three")]).is_err());
+    assert_eq!(read(&root, "src/main.rs"), "one");
+    assert_eq!(read(&root, "src/lib.rs"), "pub fn cat() {}\n");
+    assert!(!has_temporaries(&root.join("src")));
+}
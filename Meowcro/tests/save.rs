@@ -0,0 +1,45 @@
+mod support;
+
+use meowcro::save::{save_file, temporary_path, Backup};
+use support::TempDir;
+
+#[cfg(unix)]
+#[test]
+fn saves_through_symlinks_and_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = TempDir::new("save-symlink");
+    let target = root.join("target.sh");
+    let link = root.join("link.sh");
+
+    std::fs::write(&target, "echo one\n").unwrap();
+    std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o750)).unwrap();
+    std::os::unix::fs::symlink("target.sh", &link).unwrap();
+
+    save_file(&link, b"echo two\n", Backup::None).unwrap();
+
+    assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "echo two\n");
+
+    let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+
+    assert_eq!(mode & 0o777, 0o750);
+    assert!(!temporary_path(&target).exists());
+}
+
+#[test]
+fn keeps_a_backup_of_the_old_file() {
+    let root = TempDir::new("save-backup");
+    let path = root.join("nested").join("file.txt");
+
+    save_file(&path, b"one\n", Backup::Tilde).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\n");
+    assert!(!root.join("nested").join("file.txt~").exists());
+
+    save_file(&path, b"two\n", Backup::Tilde).unwrap();
+    save_file(&path, b"three\n", Backup::Tilde).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "three\n");
+    assert_eq!(std::fs::read_to_string(root.join("nested").join("file.txt~")).unwrap(), "two\n");
+}
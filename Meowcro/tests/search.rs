@@ -0,0 +1,96 @@
+mod support;
+
+use meowcro::search::{search_text, ProjectSearch, SearchQuery};
+use std::time::{Duration, Instant};
+use support::TempDir;
+
+#[test]
+fn queries_parse_flags_before_the_pattern() {
+    let query = SearchQuery::parse("-r -c -g *.rs -g !tests/** fn \\w+").unwrap();
+
+    assert_eq!(query.pattern, "fn \\w+");
+    assert!(query.regex && query.case_sensitive);
+    assert!(query.includes("src/main.rs"));
+    assert!(!query.includes("src/main.toml"));
+    assert!(!query.includes("tests/panes.rs"));
+
+    let query = SearchQuery::parse("-- -r cat").unwrap();
+
+    assert_eq!(query.pattern, "-r cat");
+    assert!(!query.regex);
+
+    assert!(SearchQuery::parse("-r ").is_err());
+    assert!(SearchQuery::parse("-r (").unwrap().build_regex().is_err());
+}
+
+#[test]
+fn literal_search_ignores_case_unless_asked() {
+    let text = "Cat cat\ncaT.\nc.t";
+
+    let regex = SearchQuery::parse("cat").unwrap().build_regex().unwrap();
+    let found = search_text(&regex, text, 100);
+
+    assert_eq!(
+        found.iter().map(|x| (x.line, x.range.clone())).collect::<Vec<_>>(),
+        [(0, 0..3), (0, 4..7), (1, 0..3)]
+    );
+    assert_eq!(found[2].text, "caT.");
+
+    let regex = SearchQuery::parse("-c c.t").unwrap().build_regex().unwrap();
+
+    assert_eq!(search_text(&regex, text, 100).len(), 1);
+
+    let regex = SearchQuery::parse("-r -c c.t").unwrap().build_regex().unwrap();
+
+    assert_eq!(search_text(&regex, text, 2).len(), 2);
+}
+
+#[test]
+fn project_search_streams_results_grouped_by_file() {
+    let root = TempDir::new("search");
+
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("target")).unwrap();
+
+    std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+    std::fs::write(root.join("src/a.rs"), "let cat = 1;\nlet dog = cat;\n").unwrap();
+    std::fs::write(root.join("src/b.txt"), "no cats here\n").unwrap();
+    std::fs::write(root.join("target/c.rs"), "cat\n").unwrap();
+    std::fs::write(root.join("cat.bin"), [b'c', b'a', b't', 0]).unwrap();
+
+    let mut search = ProjectSearch::new(root.to_path_buf(), SearchQuery::parse("cat").unwrap()).unwrap();
+
+    let start = Instant::now();
+
+    while search.is_searching() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        search.poll();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    assert_eq!(search.get_title(), "3 matches in 2 files");
+    assert_eq!(
+        search.get_rows(),
+        [
+            ("src/a.rs (2)".to_string(), true),
+            ("     1: let cat = 1;".to_string(), false),
+            ("     2: let dog = cat;".to_string(), false),
+            ("src/b.txt (1)".to_string(), true),
+            ("     1: no cats here".to_string(), false),
+        ]
+    );
+
+    assert_eq!(search.get_selected(), 1);
+
+    search.move_selection(2);
+
+    let (path, found) = search.get_selected_match().unwrap();
+
+    assert_eq!(path, root.join("src/b.txt"));
+    assert_eq!((found.line, found.range.clone()), (0, 3..6));
+
+    search.select(0);
+
+    assert_eq!(search.get_selected(), 1);
+}
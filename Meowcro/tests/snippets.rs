@@ -0,0 +1,159 @@
+mod support;
+
+use meowcro::editor::TextEditor;
+use meowcro::snippets::{Snippet, SnippetContext, SnippetSession, SnippetSet};
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use support::PlainLayout;
+
+fn context<'a>(path: &'a Path, indent: &'a str) -> SnippetContext<'a> {
+    SnippetContext {
+        path,
+        line: 4,
+        indent,
+        now: UNIX_EPOCH + Duration::from_secs(1_709_210_096),
+    }
+}
+
+fn sync(session: &mut Option<SnippetSession>, editor: &mut TextEditor<PlainLayout>) {
+    loop {
+        let changes = editor.take_changes();
+
+        if changes.is_empty() {
+            break;
+        }
+
+        if session.as_mut().is_some_and(|x| !x.apply_changes(&changes)) {
+            *session = None;
+        }
+
+        if !session
+            .as_mut()
+            .is_some_and(|x| x.apply_mirror_edits(editor))
+        {
+            break;
+        }
+    }
+}
+
+#[test]
+fn expands_placeholders_variables_and_indentation() {
+    let snippet = Snippet::parse(
+        "x",
+        None,
+        "fn ${1:name}() -> \\$ {\n    $FILENAME $1 ${DATE} $TIME $LINE $2\n}$0",
+    )
+    .unwrap();
+
+    let expansion = snippet.expand(&context(Path::new("/tmp/cat.rs"), "  "));
+
+    assert_eq!(
+        expansion.text,
+        "fn name() -> $ {\n      cat.rs name 2024-02-29 12:34 5 \n  }"
+    );
+
+    assert_eq!(
+        expansion.stops,
+        vec![(1, 3..7), (1, 30..34), (2, 54..54), (0, 58..58)]
+    );
+}
+
+#[test]
+fn reports_invalid_bodies() {
+    let error = |body| Snippet::parse("x", None, body).err().unwrap();
+
+    assert_eq!(error("$NOPE"), "snippet `x`: unknown variable `$NOPE`");
+    assert_eq!(
+        error("${1:a"),
+        "snippet `x`: unterminated placeholder, expected `}`"
+    );
+    assert_eq!(
+        error("${1:${2}}"),
+        "snippet `x`: placeholders can't be nested"
+    );
+
+    assert!(SnippetSet::parse_file("test", "[x]\nbody = 'a'\nbogus = 1").is_err());
+    assert_eq!(
+        Snippet::parse("x", None, "a $ b")
+            .unwrap()
+            .expand(&context(Path::new("a"), ""))
+            .text,
+        "a $ b"
+    );
+}
+
+#[test]
+fn mirrors_edits_and_undoes_them_in_one_step() {
+    let snippet = Snippet::parse("x", None, "let ${1:a} = $1 + ${2:b};$0").unwrap();
+
+    let mut editor = TextEditor::new("x", PlainLayout, 4, false);
+
+    editor.take_changes();
+
+    let expansion = snippet.expand(&context(Path::new("a"), ""));
+
+    editor.replace_range(0, 1, &expansion.text, true);
+    editor.take_changes();
+
+    let mut session = Some(SnippetSession::new(0, &expansion));
+
+    editor.set_selection(session.as_ref().unwrap().get_active());
+
+    assert_eq!(editor.get_selection().as_deref(), Some("a"));
+
+    editor.cut_selection();
+    editor.insert_string_at_cursor("cat");
+    sync(&mut session, &mut editor);
+
+    assert_eq!(editor.to_string(), "let cat = cat + b;");
+    assert_eq!(editor.get_cursor(), 7);
+
+    let range = session.as_mut().unwrap().move_active(true);
+
+    assert_eq!(&editor.to_string()[range], "b");
+
+    editor.undo();
+    sync(&mut session, &mut editor);
+
+    assert_eq!(editor.to_string(), "let  = a + b;");
+
+    editor.undo();
+    sync(&mut session, &mut editor);
+
+    assert_eq!(editor.to_string(), "let a = a + b;");
+
+    editor.redo();
+    editor.redo();
+    sync(&mut session, &mut editor);
+
+    assert_eq!(editor.to_string(), "let cat = cat + b;");
+
+    let end = session.as_mut().unwrap().move_active(true);
+
+    assert_eq!(end, 18..18);
+    assert!(session.as_ref().unwrap().is_finished());
+}
+
+#[test]
+fn edits_across_a_stop_end_the_session() {
+    let snippet = Snippet::parse("x", None, "(${1:a}, ${2:b})").unwrap();
+
+    let mut editor = TextEditor::new("", PlainLayout, 4, false);
+
+    let expansion = snippet.expand(&context(Path::new("a"), ""));
+
+    editor.insert_string_at_cursor(&expansion.text);
+    editor.take_changes();
+
+    let mut session = Some(SnippetSession::new(0, &expansion));
+
+    editor.insert_string(0, "// ", true, true, false);
+    sync(&mut session, &mut editor);
+
+    assert_eq!(session.as_ref().unwrap().get_active(), 4..5);
+
+    editor.remove_range(4, 8, true, true, true);
+    sync(&mut session, &mut editor);
+
+    assert!(session.is_none());
+}
@@ -0,0 +1,59 @@
+use meowcro::statusline::{detect_indent, uses_crlf, Indent, Segment, StatusInfo};
+use ropey::Rope;
+
+fn info(message: Option<&str>, selection: Option<(usize, usize)>) -> StatusInfo<'_> {
+    StatusInfo {
+        mode: "EDIT",
+        title: "src/main.rs",
+        modified: true,
+        message,
+        filetype: Some("rust"),
+        crlf: false,
+        indent: Indent::Spaces(4),
+        selection,
+        position: (30, 7),
+        lines: 120,
+    }
+}
+
+#[test]
+fn segments_skip_empty_values() {
+    let segments = [
+        Segment::Mode,
+        Segment::File,
+        Segment::Message,
+        Segment::Selection,
+        Segment::Indent,
+        Segment::Percent,
+        Segment::Position,
+    ];
+
+    let texts = |info: &StatusInfo| {
+        info.get_segments(&segments)
+            .into_iter()
+            .map(|(_, x)| x)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        texts(&info(None, None)),
+        ["EDIT", "src/main.rs*", "spaces: 4", "25%", "30:7"]
+    );
+    assert_eq!(
+        texts(&info(Some("saved"), Some((12, 3))))[2..4],
+        ["saved", "12 chars, 3 lines selected"]
+    );
+}
+
+#[test]
+fn line_endings_and_indentation_are_detected() {
+    let text = Rope::from_str("fn main() {\r\n  let x = 1;\r\n    x\r\n\t\r\n}\r\n");
+
+    assert!(uses_crlf(text.slice(..)));
+    assert_eq!(detect_indent(text.slice(..)), Indent::Spaces(2));
+
+    let text = Rope::from_str("fn main() {\n\tlet x = 1;\n}");
+
+    assert!(!uses_crlf(text.slice(..)));
+    assert_eq!(detect_indent(text.slice(..)), Indent::Tabs);
+}
@@ -0,0 +1,243 @@
+use meowcro::lsp;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufReader, Write};
+
+fn send(message: Value) {
+    let content = message.to_string();
+    let mut stdout = std::io::stdout().lock();
+
+    write!(stdout, "Content-Length: {}\r\n\r\n{content}", content.len()).unwrap();
+    stdout.flush().unwrap();
+}
+
+fn offset_of(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line)
+        .map(str::len)
+        .sum::<usize>();
+
+    let mut units = 0;
+
+    for (index, c) in text[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + index;
+        }
+
+        units += c.len_utf16();
+    }
+
+    text.len()
+}
+
+fn position_of(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map(|x| x + 1).unwrap_or(0);
+
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].encode_utf16().count(),
+    })
+}
+
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (start, c.is_alphanumeric() || c == '_') {
+            (None, true) => start = Some(index),
+            (Some(x), false) => {
+                words.push((x, &text[x..index]));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    words
+}
+
+fn word_at(text: &str, offset: usize) -> Option<&str> {
+    words(text)
+        .into_iter()
+        .find(|(start, word)| *start <= offset && offset <= start + word.len())
+        .map(|(_, word)| word)
+}
+
+fn range(text: &str, start: usize, end: usize) -> Value {
+    json!({ "start": position_of(text, start), "end": position_of(text, end) })
+}
+
+fn publish(uri: &str, text: &str) {
+    let diagnostics = words(text)
+        .into_iter()
+        .filter_map(|(start, word)| {
+            let severity = match word {
+                "bad" => 1,
+                "meh" => 2,
+                _ => return None,
+            };
+
+            Some(json!({
+                "range": range(text, start, start + word.len()),
+                "severity": severity,
+                "message": format!("`{word}` found"),
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "method": "window/logMessage",
+        "params": { "type": 4, "message": text },
+    }));
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    }));
+}
+
+fn main() {
+    // `--sync 0` or `--sync 1` asks for no changes or whole documents instead of ranges, and
+    // `--fail-initialize` refuses to start
+    let args = std::env::args().collect::<Vec<_>>();
+
+    let sync = args
+        .iter()
+        .position(|x| x == "--sync")
+        .and_then(|x| args.get(x + 1)?.parse::<u64>().ok())
+        .unwrap_or(2);
+
+    let mut documents: HashMap<String, String> = HashMap::new();
+    let mut input = BufReader::new(std::io::stdin().lock());
+
+    while let Ok(Some(message)) = lsp::read_message(&mut input) {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        let text = documents.get(uri).cloned().unwrap_or_default();
+        let offset = offset_of(&text, &params["position"]);
+
+        let result = match method {
+            "initialize" if args.iter().any(|x| x == "--fail-initialize") => {
+                send(json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "error": { "code": -32603, "message": "no cats allowed" },
+                }));
+                continue;
+            }
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": sync },
+                    "completionProvider": { "triggerCharacters": ["."] },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "renameProvider": true,
+                },
+            }),
+            "shutdown" => Value::Null,
+            "exit" => return,
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+
+                documents.insert(uri.to_string(), text.to_string());
+                publish(uri, text);
+                continue;
+            }
+            "textDocument/didChange" => {
+                let mut text = text;
+
+                for change in params["contentChanges"].as_array().into_iter().flatten() {
+                    let new_text = change["text"].as_str().unwrap_or_default();
+
+                    if sync == 1 {
+                        text = new_text.to_string();
+                        continue;
+                    }
+
+                    let start = offset_of(&text, &change["range"]["start"]);
+                    let end = offset_of(&text, &change["range"]["end"]);
+
+                    text.replace_range(start..end, new_text);
+                }
+
+                publish(uri, &text);
+                documents.insert(uri.to_string(), text);
+                continue;
+            }
+            "textDocument/completion" => {
+                let mut labels = words(&text)
+                    .into_iter()
+                    .map(|(_, x)| x.to_string())
+                    .collect::<Vec<_>>();
+
+                labels.sort();
+                labels.dedup();
+
+                json!(labels
+                    .iter()
+                    .map(|x| json!({ "label": x, "detail": "word" }))
+                    .collect::<Vec<_>>())
+            }
+            "textDocument/hover" => match word_at(&text, offset) {
+                Some(word) => json!({ "contents": { "kind": "plaintext", "value": format!("word `{word}`") } }),
+                None => Value::Null,
+            },
+            "textDocument/definition" => {
+                let mut uris = documents.keys().collect::<Vec<_>>();
+                uris.sort();
+
+                // a word is defined where it first appears, in the first document that has it
+                let first = word_at(&text, offset).and_then(|word| {
+                    uris.into_iter().find_map(|uri| {
+                        let text = &documents[uri];
+
+                        words(text)
+                            .into_iter()
+                            .find(|(_, x)| *x == word)
+                            .map(|(start, word)| (uri, text, start, start + word.len()))
+                    })
+                });
+
+                match first {
+                    Some((uri, text, start, end)) => json!({
+                        "uri": uri,
+                        "range": range(text, start, end),
+                    }),
+                    None => Value::Null,
+                }
+            }
+            "textDocument/rename" => {
+                let new_name = params["newName"].as_str().unwrap_or_default();
+
+                let edits = word_at(&text, offset)
+                    .map(|word| {
+                        words(&text)
+                            .into_iter()
+                            .filter(|(_, x)| *x == word)
+                            .map(|(start, x)| {
+                                json!({ "range": range(&text, start, start + x.len()), "newText": new_name })
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+
+                json!({ "changes": { uri: edits } })
+            }
+            _ => continue,
+        };
+
+        if let Some(id) = message.get("id") {
+            send(json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+        }
+    }
+}
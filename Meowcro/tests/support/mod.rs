@@ -0,0 +1,77 @@
+// each test file uses only some of these
+#![allow(dead_code)]
+
+use meowcro::editor::{GraphemePosition, LineLayout};
+use ropey::RopeSlice;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+// a fresh folder in the system's temporary directory, removed again when the test ends, even when it fails
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("meowcro-{name}-{}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// cargo test builds the fake language server as an example, next to the folder of the test binaries
+pub fn fake_lsp_path() -> String {
+    let mut path = std::env::current_exe().unwrap();
+
+    path.pop();
+
+    if path.ends_with("deps") {
+        path.pop();
+    }
+
+    let name = format!("meowcro-fake-lsp{}", std::env::consts::EXE_SUFFIX);
+
+    let path = path.join("examples").join(name);
+
+    assert!(
+        path.is_file(),
+        "{} is missing, build it with `cargo build --examples`",
+        path.to_string_lossy()
+    );
+
+    path.to_string_lossy().to_string()
+}
+
+// lays every character out one column wide, so positions in tests are plain character counts
+pub struct PlainLayout;
+
+impl LineLayout for PlainLayout {
+    type Iter<'a> = std::vec::IntoIter<GraphemePosition>;
+
+    fn layout_line<'a>(&self, line: RopeSlice<'a>) -> Self::Iter<'a> {
+        (0..line.len_chars())
+            .map(|index| GraphemePosition {
+                start_column: index,
+                end_column: index + 1,
+                cursor: line.char_to_byte(index),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,79 @@
+mod support;
+
+use meowcro::editor::TextEditor;
+use meowcro::syntax::{Grammar, Highlighter};
+use meowcro::terminal::Highlight;
+use ropey::Rope;
+use std::rc::Rc;
+use support::PlainLayout;
+
+fn highlighter(editor: &TextEditor<PlainLayout>) -> Highlighter {
+    let grammar = Grammar::parse("rust", include_str!("../grammars/rust.toml")).unwrap();
+    let mut highlighter = Highlighter::new(Rc::new(grammar), editor.get_text());
+
+    highlighter.update(editor.get_text(), usize::MAX);
+
+    highlighter
+}
+
+fn highlight_at(highlighter: &Highlighter, line: usize, byte: usize) -> Highlight {
+    highlighter
+        .get_line_spans(line)
+        .iter()
+        .find(|x| x.start <= byte && byte < x.end)
+        .map(|x| x.highlight)
+        .unwrap_or(Highlight::Text)
+}
+
+#[test]
+fn opening_a_comment_or_string_rehighlights_the_lines_after_it() {
+    let mut editor = TextEditor::new("let a = 1;\nlet b = 2;\nlet c = 3;\n", PlainLayout, 4, false);
+    let mut highlighter = highlighter(&editor);
+
+    assert!(highlight_at(&highlighter, 2, 0) == Highlight::Keyword);
+
+    editor.insert_string(11, "/* ", true, true, true);
+    highlighter.apply_changes(&editor.take_changes());
+    highlighter.update(editor.get_text(), usize::MAX);
+
+    assert!(highlight_at(&highlighter, 0, 0) == Highlight::Keyword);
+    assert!(highlight_at(&highlighter, 1, 3) == Highlight::Comment);
+    assert!(highlight_at(&highlighter, 2, 0) == Highlight::Comment);
+    assert!(highlight_at(&highlighter, 2, 8) == Highlight::Comment);
+
+    editor.insert_string(11, "\"", true, true, true);
+    highlighter.apply_changes(&editor.take_changes());
+    highlighter.update(editor.get_text(), usize::MAX);
+
+    assert!(highlight_at(&highlighter, 1, 4) == Highlight::String);
+    assert!(highlight_at(&highlighter, 2, 0) == Highlight::String);
+
+    editor.remove_range(11, 15, true, true, true);
+    highlighter.apply_changes(&editor.take_changes());
+    highlighter.update(editor.get_text(), usize::MAX);
+
+    assert!(highlight_at(&highlighter, 1, 0) == Highlight::Keyword);
+    assert!(highlight_at(&highlighter, 2, 0) == Highlight::Keyword);
+    assert!(highlight_at(&highlighter, 2, 8) == Highlight::Number);
+}
+
+#[test]
+fn edits_that_keep_the_context_leave_later_lines_alone() {
+    let mut editor = TextEditor::new("/* one\ntwo\n*/ let x = 3;\nlet y = 4;\n", PlainLayout, 4, false);
+    let mut highlighter = highlighter(&editor);
+
+    editor.insert_string(8, "more ", true, true, true);
+    highlighter.apply_changes(&editor.take_changes());
+
+    // lines that are recomputed see the swapped last line, lines that are kept still show the old one
+    let mut text = Rope::from(editor.get_text());
+    let last = text.line_to_char(3);
+    text.remove(last..last + 3);
+    text.insert(last, "\"\"\"");
+
+    highlighter.update(text.slice(..), usize::MAX);
+
+    assert!(highlight_at(&highlighter, 1, 0) == Highlight::Comment);
+    assert!(highlight_at(&highlighter, 2, 3) == Highlight::Keyword);
+    assert!(highlight_at(&highlighter, 3, 0) == Highlight::Keyword);
+}
@@ -0,0 +1,43 @@
+use crossterm::style::Color;
+use meowcro::terminal::Highlight;
+use meowcro::theme::{ansi256_to_rgb, rgb_to_ansi16, rgb_to_ansi256, ColorSupport, Theme};
+
+#[test]
+fn colors_downgrade_to_the_nearest_palette_entry() {
+    assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+    assert_eq!(rgb_to_ansi256(0, 135, 175), 31);
+    assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+    assert_eq!(rgb_to_ansi256(130, 125, 128), 244);
+
+    assert_eq!(ansi256_to_rgb(3), (128, 128, 0));
+    assert_eq!(ansi256_to_rgb(31), (0, 135, 175));
+    assert_eq!(ansi256_to_rgb(244), (128, 128, 128));
+
+    assert_eq!(rgb_to_ansi16(250, 10, 10), Color::Red);
+    assert_eq!(rgb_to_ansi16(0, 135, 175), Color::DarkCyan);
+    assert_eq!(rgb_to_ansi16(128, 128, 128), Color::DarkGrey);
+    assert_eq!(rgb_to_ansi16(200, 200, 200), Color::Grey);
+}
+
+#[test]
+fn themes_only_use_colors_the_terminal_supports() {
+    let colors = |support| {
+        let theme = Theme::load("meownix", support).unwrap();
+
+        Highlight::ALL
+            .iter()
+            .flat_map(|x| [theme.style(*x).foreground, theme.style(*x).background])
+            .collect::<Vec<_>>()
+    };
+
+    assert!(colors(ColorSupport::TrueColor)
+        .iter()
+        .any(|x| matches!(x, Color::Rgb { .. })));
+    assert!(!colors(ColorSupport::Ansi256)
+        .iter()
+        .any(|x| matches!(x, Color::Rgb { .. })));
+    assert!(colors(ColorSupport::Ansi16)
+        .iter()
+        .all(|x| !matches!(x, Color::Rgb { .. } | Color::AnsiValue(_))));
+    assert!(colors(ColorSupport::None).iter().all(|x| *x == Color::Reset));
+}
@@ -0,0 +1,71 @@
+mod support;
+
+use meowcro::watcher::{FileStamp, FileWatcher};
+use support::TempDir;
+
+#[test]
+fn stamps_change_with_the_file() {
+    let root = TempDir::new("watcher-stamp");
+    let path = root.join("file.txt");
+
+    assert_eq!(FileStamp::read(&path), None);
+
+    std::fs::write(&path, "one\n").unwrap();
+
+    let stamp = FileStamp::read(&path);
+
+    assert!(stamp.is_some());
+    assert_eq!(FileStamp::read(&path), stamp);
+
+    std::fs::write(&path, "one\ntwo\n").unwrap();
+
+    assert_ne!(FileStamp::read(&path), stamp);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn replaced_files_are_noticed() {
+    let root = TempDir::new("watcher-replace");
+    let path = root.join("file.txt");
+
+    std::fs::write(&path, "one\n").unwrap();
+
+    let mut watcher = FileWatcher::default();
+
+    watcher.watch(&[&path]);
+
+    assert!(!watcher.poll());
+
+    std::fs::write(root.join("file.txt.new"), "two\n").unwrap();
+    std::fs::rename(root.join("file.txt.new"), &path).unwrap();
+
+    assert!(watcher.poll());
+    assert!(!watcher.poll());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn closed_folders_are_dropped_and_unwatchable_ones_polled() {
+    let root = TempDir::new("watcher-close");
+    let path = root.join("file.txt");
+    let later = root.join("later").join("file.txt");
+
+    std::fs::write(&path, "one\n").unwrap();
+
+    let mut watcher = FileWatcher::default();
+
+    // inotify can't watch a folder that doesn't exist yet
+    watcher.watch(&[&path, &later]);
+    watcher.watch(&[&later]);
+
+    std::fs::write(&path, "two\n").unwrap();
+
+    assert!(!watcher.poll());
+
+    std::fs::create_dir(root.join("later")).unwrap();
+    std::fs::write(&later, "one\n").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(2100));
+
+    assert!(watcher.poll());
+    assert!(!watcher.poll());
+}
@@ -0,0 +1,81 @@
+mod support;
+
+use meowcro::editor::TextEditor;
+use meowcro::fuzzy;
+use meowcro::words::WordIndex;
+use std::collections::HashMap;
+use support::PlainLayout;
+
+fn counts(index: &WordIndex) -> HashMap<String, usize> {
+    index.words().map(|(x, y)| (x.to_string(), y)).collect()
+}
+
+fn sync(index: &mut WordIndex, editor: &mut TextEditor<PlainLayout>) {
+    index.apply_changes(&editor.take_changes());
+    index.update(editor.get_text());
+}
+
+#[test]
+fn index_follows_edits() {
+    let mut editor = TextEditor::new("cat kitten\ncat 42 a\n", PlainLayout, 4, false);
+    let mut index = WordIndex::new(editor.get_text());
+
+    assert_eq!(
+        counts(&index),
+        HashMap::from([("cat".to_string(), 2), ("kitten".to_string(), 1)])
+    );
+
+    editor.insert_string(3, "nip\npurr", true, true, true);
+    editor.remove_range(0, 1, true, true, true);
+
+    sync(&mut index, &mut editor);
+
+    assert_eq!(
+        counts(&index),
+        HashMap::from([
+            ("atnip".to_string(), 1),
+            ("purr".to_string(), 1),
+            ("kitten".to_string(), 1),
+            ("cat".to_string(), 1),
+        ])
+    );
+
+    let end = editor.get_text().len_bytes();
+    editor.remove_range(0, end, true, true, true);
+
+    sync(&mut index, &mut editor);
+
+    assert!(counts(&index).is_empty());
+}
+
+#[test]
+fn fuzzy_matches_prefer_starts_and_boundaries() {
+    assert_eq!(fuzzy::fuzzy_score("xyz", "kitten"), None);
+    assert!(fuzzy::fuzzy_score("", "kitten").is_some());
+
+    let mut words = ["mittens", "kitten", "kit_tent", "skittles", "KitTen"];
+
+    words.sort_by_key(|x| std::cmp::Reverse(fuzzy::fuzzy_score("kitt", x).unwrap_or(i64::MIN)));
+
+    assert_eq!(words[0], "kitten");
+    assert_eq!(words[4], "mittens");
+    assert!(fuzzy::fuzzy_score("kt", "kit_tent") > fuzzy::fuzzy_score("kt", "skittles"));
+}
+
+#[test]
+fn replacing_a_prefix_is_one_undo_step() {
+    let mut editor = TextEditor::new("scratch kit", PlainLayout, 4, false);
+
+    editor.replace_range(8, 11, "kitten", true);
+
+    assert_eq!(editor.to_string(), "scratch kitten");
+    assert_eq!(editor.get_cursor(), 14);
+
+    editor.undo();
+
+    assert_eq!(editor.to_string(), "scratch kit");
+
+    editor.redo();
+
+    assert_eq!(editor.to_string(), "scratch kitten");
+}